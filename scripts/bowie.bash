@@ -93,7 +93,7 @@ show_all_changes() {
 
 	while read -r line; do
 		all_versions+=("$line")
-	done <<<"$(httm -n --unique=contents --omit-ditto "$filename")"
+	done <<<"$(httm -n --include-live --unique=contents --omit-ditto "$filename")"
 
 	# check if versions array is not empty
 	if [[ ${#all_versions[@]} -eq 0 ]]; then
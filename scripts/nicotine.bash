@@ -136,7 +136,7 @@ function get_unique_versions {
 	# commiting all directory versions we find
 	[[ -d "$path" ]] || while read -r line; do
 		version_list+=("$line")
-	done <<<"$(httm -n --omit-ditto "$path")"
+	done <<<"$(httm -n --include-live --omit-ditto "$path")"
 
 	# see above, one/zero version indicates $path has no snaps
 	if [[ -d "$path" ]] || [[ ${#version_list[@]} -eq 0 ]] || [[ ${#version_list[@]} -eq 1 ]]; then
@@ -18,8 +18,9 @@
 use crate::background::recursive::{PathProvenance, SharedRecursive};
 use crate::config::generate::DeletedMode;
 use crate::data::paths::{BasicDirEntryInfo, PathData};
+use crate::library::interrupt::Interrupt;
 use crate::library::results::{HttmError, HttmResult};
-use crate::lookup::deleted::{DeletedFiles, LastInTimeSet};
+use crate::lookup::deleted::{DeletedFiles, LastInTimeSet, LiveFilenamesCache};
 use crate::GLOBAL_CONFIG;
 use rayon::Scope;
 use skim::prelude::*;
@@ -28,6 +29,7 @@ use std::sync::atomic::AtomicBool;
 
 pub struct SpawnDeletedThread {
     requested_dir: PathBuf,
+    opt_remaining_depth: Option<usize>,
     skim_tx: SkimItemSender,
     hangup: Arc<AtomicBool>,
 }
@@ -36,20 +38,27 @@ impl SpawnDeletedThread {
     // "spawn" a lighter weight rayon/greenish thread for enumerate_deleted, if needed
     pub fn exec(
         requested_dir: &Path,
+        opt_remaining_depth: Option<usize>,
         deleted_scope: &Scope,
         skim_tx: &SkimItemSender,
         hangup: &Arc<AtomicBool>,
     ) {
-        let new = Self::new(requested_dir, skim_tx, hangup);
+        let new = Self::new(requested_dir, opt_remaining_depth, skim_tx, hangup);
 
         deleted_scope.spawn(move |_| {
             let _ = new.enter_directory();
         })
     }
 
-    fn new(requested_dir: &Path, skim_tx: &SkimItemSender, hangup: &Arc<AtomicBool>) -> Self {
+    fn new(
+        requested_dir: &Path,
+        opt_remaining_depth: Option<usize>,
+        skim_tx: &SkimItemSender,
+        hangup: &Arc<AtomicBool>,
+    ) -> Self {
         Self {
             requested_dir: requested_dir.to_path_buf(),
+            opt_remaining_depth,
             skim_tx: skim_tx.clone(),
             hangup: hangup.clone(),
         }
@@ -60,7 +69,9 @@ impl SpawnDeletedThread {
         // check -- should deleted threads keep working?
         // exit/error on disconnected channel, which closes
         // at end of browse scope
-        if self.hangup.as_ref().load(Ordering::Relaxed) {
+        if self.hangup.as_ref().load(Ordering::Relaxed) || Interrupt::is_interrupted() {
+            // never leave this dir's live file names behind in the cache
+            let _ = LiveFilenamesCache::take(&self.requested_dir);
             return Ok(());
         }
 
@@ -86,17 +97,31 @@ impl SpawnDeletedThread {
             &self.skim_tx,
         )?;
 
-        // disable behind deleted dirs with DepthOfOne,
-        // otherwise recurse and find all those deleted files
+        // the deleted files and dirs above are at a depth of one, so disable
+        // behind deleted dirs with a depth of one, otherwise recurse and find
+        // those deleted files, to the depth requested
         //
         // don't propagate errors, errors we are most concerned about
         // are transmission errors, which are handled elsewhere
-        if GLOBAL_CONFIG.opt_deleted_mode != Some(DeletedMode::DepthOfOne)
+        //
+        let opt_max_depth = Self::max_depth(
+            GLOBAL_CONFIG.opt_deleted_mode.as_ref(),
+            self.opt_remaining_depth,
+        );
+
+        if opt_max_depth.is_none_or(|max_depth| max_depth > 1)
             && GLOBAL_CONFIG.opt_recursive
             && !vec_dirs.is_empty()
         {
             // get latest in time per our policy
-            let path_set: Vec<PathData> = vec_dirs.into_iter().map(PathData::from).collect();
+            //
+            // deleted dir entries are paths upon a snapshot, and a snapshot path has no versions
+            // of its own, so we must look up the versions of their pseudo live paths, else
+            // nothing behind a deleted dir is ever found, whatever the depth requested
+            let path_set: Vec<PathData> = vec_dirs
+                .iter()
+                .map(|entry| PathData::from(self.requested_dir.join(entry.filename())))
+                .collect();
 
             return LastInTimeSet::new(path_set)?
                 .iter()
@@ -106,15 +131,31 @@ impl SpawnDeletedThread {
                         &self.requested_dir,
                         &self.skim_tx,
                         &self.hangup,
+                        opt_max_depth,
                     )
                 });
         }
 
         Ok(())
     }
+
+    // the live search's max depth, if any, also bounds the deleted depth
+    fn max_depth(
+        opt_deleted_mode: Option<&DeletedMode>,
+        opt_remaining_depth: Option<usize>,
+    ) -> Option<usize> {
+        match (opt_deleted_mode, opt_remaining_depth) {
+            (Some(DeletedMode::Depth(max_depth)), Some(remaining)) => {
+                Some(remaining.min(*max_depth))
+            }
+            (Some(DeletedMode::Depth(max_depth)), None) => Some(*max_depth),
+            (_, opt_remaining) => opt_remaining,
+        }
+    }
 }
 
 struct RecurseBehindDeletedDir {
+    depth: usize,
     vec_dirs: Vec<BasicDirEntryInfo>,
     deleted_dir_on_snap: PathBuf,
     pseudo_live_dir: PathBuf,
@@ -130,38 +171,73 @@ impl RecurseBehindDeletedDir {
         requested_dir: &Path,
         skim_tx: &SkimItemSender,
         hangup: &Arc<AtomicBool>,
+        opt_max_depth: Option<usize>,
     ) -> HttmResult<()> {
         // check -- should deleted threads keep working?
         // exit/error on disconnected channel, which closes
         // at end of browse scope
-        if hangup.load(Ordering::Relaxed) {
+        let is_stopped = || hangup.load(Ordering::Relaxed) || Interrupt::is_interrupted();
+
+        if is_stopped() {
             return Ok(());
         }
 
-        let mut queue = match &deleted_dir.file_name() {
-            Some(dir_name) => {
-                let from_deleted_dir = deleted_dir
-                    .parent()
-                    .ok_or_else(|| HttmError::new("Not a valid directory name!"))?;
+        let Some(dir_name) = deleted_dir.file_name() else {
+            return Err(HttmError::new("Not a valid directory name!").into());
+        };
 
-                let from_requested_dir = requested_dir;
+        let from_deleted_dir = deleted_dir
+            .parent()
+            .ok_or_else(|| HttmError::new("Not a valid directory name!"))?;
 
-                match RecurseBehindDeletedDir::enter_directory(
-                    Path::new(dir_name),
+        Self::walk(
+            Path::new(dir_name),
+            from_deleted_dir,
+            requested_dir,
+            opt_max_depth,
+            is_stopped,
+            |dir_name, from_deleted_dir, from_requested_dir, depth| {
+                Self::enter_directory(
+                    dir_name,
                     from_deleted_dir,
                     from_requested_dir,
                     skim_tx,
-                ) {
-                    Ok(res) if !res.vec_dirs.is_empty() => Vec::from([res]),
-                    _ => return Ok(()),
-                }
-            }
-            None => return Err(HttmError::new("Not a valid directory name!").into()),
+                    depth,
+                )
+            },
+        );
+
+        Ok(())
+    }
+
+    // enters the deleted dir, and each dir behind it, until the max depth, if any, is reached
+    fn walk<E, S>(
+        dir_name: &Path,
+        from_deleted_dir: &Path,
+        from_requested_dir: &Path,
+        opt_max_depth: Option<usize>,
+        is_stopped: S,
+        enter: E,
+    ) where
+        E: Fn(&Path, &Path, &Path, usize) -> HttmResult<RecurseBehindDeletedDir>,
+        S: Fn() -> bool,
+    {
+        // the contents of the first deleted dir are at a depth of two
+        let mut queue = match enter(dir_name, from_deleted_dir, from_requested_dir, 2) {
+            Ok(res) if !res.vec_dirs.is_empty() => Vec::from([res]),
+            _ => return,
         };
 
         while let Some(item) = queue.pop() {
-            if hangup.load(Ordering::Relaxed) {
-                return Ok(());
+            if is_stopped() {
+                return;
+            }
+
+            // stop descending once we are at the max depth
+            let depth = item.depth + 1;
+
+            if opt_max_depth.is_some_and(|max_depth| depth > max_depth) {
+                continue;
             }
 
             let mut new = item
@@ -169,11 +245,11 @@ impl RecurseBehindDeletedDir {
                 .into_iter()
                 .map(|basic_info| {
                     let dir_name = Path::new(basic_info.filename());
-                    RecurseBehindDeletedDir::enter_directory(
+                    enter(
                         dir_name,
                         &item.deleted_dir_on_snap,
                         &item.pseudo_live_dir,
-                        skim_tx,
+                        depth,
                     )
                 })
                 .flatten()
@@ -181,8 +257,6 @@ impl RecurseBehindDeletedDir {
 
             queue.append(&mut new);
         }
-
-        Ok(())
     }
 
     fn enter_directory(
@@ -190,6 +264,7 @@ impl RecurseBehindDeletedDir {
         from_deleted_dir: &Path,
         from_requested_dir: &Path,
         skim_tx: &SkimItemSender,
+        depth: usize,
     ) -> HttmResult<RecurseBehindDeletedDir> {
         // deleted_dir_on_snap is the path from the deleted dir on the snapshot
         // pseudo_live_dir is the path from the fake, deleted directory that once was
@@ -197,7 +272,7 @@ impl RecurseBehindDeletedDir {
         let pseudo_live_dir = from_requested_dir.to_path_buf().join(dir_name);
 
         let (vec_dirs, vec_files): (Vec<BasicDirEntryInfo>, Vec<BasicDirEntryInfo>) =
-            SharedRecursive::entries_partitioned(&deleted_dir_on_snap)?;
+            SharedRecursive::entries_partitioned(&deleted_dir_on_snap, false)?;
 
         SharedRecursive::combine_and_send_entries(
             vec_files,
@@ -208,9 +283,124 @@ impl RecurseBehindDeletedDir {
         )?;
 
         Ok(RecurseBehindDeletedDir {
+            depth,
             vec_dirs,
             deleted_dir_on_snap,
             pseudo_live_dir,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    // a deleted dir upon a snapshot, with one file at each depth below it, from two to five
+    fn deleted_tree() -> PathBuf {
+        let snap =
+            std::env::temp_dir().join(format!("httm-test-deleted-depth-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&snap);
+
+        let deepest = snap.join("d1/l2/l3/l4");
+        std::fs::create_dir_all(&deepest).unwrap();
+
+        [
+            &snap.join("d1"),
+            &snap.join("d1/l2"),
+            &snap.join("d1/l2/l3"),
+            &deepest,
+        ]
+        .iter()
+        .for_each(|dir| std::fs::write(dir.join("file"), b"deleted").unwrap());
+
+        snap
+    }
+
+    // the pseudo live paths of each deleted file found, to the max depth requested
+    fn files_found(snap: &Path, opt_max_depth: Option<usize>) -> Vec<PathBuf> {
+        let found: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+        RecurseBehindDeletedDir::walk(
+            Path::new("d1"),
+            snap,
+            Path::new("/live"),
+            opt_max_depth,
+            || false,
+            |dir_name, from_deleted_dir, from_requested_dir, depth| {
+                let deleted_dir_on_snap = from_deleted_dir.join(dir_name);
+                let pseudo_live_dir = from_requested_dir.join(dir_name);
+
+                let (vec_dirs, vec_files): (Vec<BasicDirEntryInfo>, Vec<BasicDirEntryInfo>) =
+                    std::fs::read_dir(&deleted_dir_on_snap)?
+                        .flatten()
+                        .map(|entry| BasicDirEntryInfo::new(entry.path(), entry.file_type().ok()))
+                        .partition(|entry| entry.path().is_dir());
+
+                found.lock().unwrap().extend(
+                    vec_files
+                        .iter()
+                        .map(|entry| pseudo_live_dir.join(entry.filename())),
+                );
+
+                Ok(RecurseBehindDeletedDir {
+                    depth,
+                    vec_dirs,
+                    deleted_dir_on_snap,
+                    pseudo_live_dir,
+                })
+            },
+        );
+
+        let mut found = found.into_inner().unwrap();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn deleted_depth_bounds_the_search_behind_a_deleted_dir() {
+        let snap = deleted_tree();
+
+        let all: Vec<PathBuf> = ["d1", "d1/l2", "d1/l2/l3", "d1/l2/l3/l4"]
+            .iter()
+            .map(|dir| Path::new("/live").join(dir).join("file"))
+            .collect();
+
+        (2..=5).for_each(|depth| {
+            let opt_max_depth =
+                SpawnDeletedThread::max_depth(Some(&DeletedMode::Depth(depth)), None);
+
+            let mut expected = all[..depth - 1].to_vec();
+            expected.sort();
+
+            assert_eq!(files_found(&snap, opt_max_depth), expected, "depth {depth}");
+        });
+
+        let mut expected = all.clone();
+        expected.sort();
+
+        assert_eq!(files_found(&snap, None), expected);
+
+        std::fs::remove_dir_all(&snap).unwrap();
+    }
+
+    #[test]
+    fn live_max_depth_also_bounds_the_deleted_depth() {
+        assert_eq!(
+            SpawnDeletedThread::max_depth(Some(&DeletedMode::Depth(3)), Some(2)),
+            Some(2)
+        );
+        assert_eq!(
+            SpawnDeletedThread::max_depth(Some(&DeletedMode::Depth(3)), Some(5)),
+            Some(3)
+        );
+        assert_eq!(
+            SpawnDeletedThread::max_depth(Some(&DeletedMode::All), Some(4)),
+            Some(4)
+        );
+        assert_eq!(
+            SpawnDeletedThread::max_depth(Some(&DeletedMode::All), None),
+            None
+        );
+    }
+}
@@ -16,32 +16,54 @@
 // that was distributed with this source code.
 
 use crate::background::deleted::SpawnDeletedThread;
-use crate::config::generate::{DeletedMode, ExecMode};
+use crate::config::generate::{BulkExclusion, Config, DeletedMode, ExecMode};
 use crate::data::paths::{BasicDirEntryInfo, PathData};
 use crate::display_versions::wrapper::VersionsDisplayWrapper;
+use crate::library::interrupt::Interrupt;
 use crate::library::results::{HttmError, HttmResult};
 use crate::library::utility::{print_output_buf, HttmIsDir};
-use crate::parse::mounts::{IsFilterDir, MaxLen};
+use crate::lookup::deleted::{LiveFilenames, LiveFilenamesCache};
+use crate::parse::mounts::{IsFilterDir, MaxLen, SnapDirName};
 use crate::{VersionsMap, BTRFS_SNAPPER_HIDDEN_DIRECTORY, GLOBAL_CONFIG, ZFS_HIDDEN_DIRECTORY};
 use rayon::{Scope, ThreadPool};
 use skim::prelude::*;
+use std::collections::HashSet;
 use std::fs::read_dir;
 use std::os::unix::fs::MetadataExt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::sync::LazyLock;
+use std::sync::Once;
 
 static OPT_REQUESTED_DIR_DEV: LazyLock<u64> = LazyLock::new(|| {
     GLOBAL_CONFIG
         .opt_requested_dir
         .as_ref()
         .expect("opt_requested_dir should be Some value at this point in execution")
-        .symlink_metadata()
+        // the requested dir may itself be a symlink to a dir, whose device is the one we descend
+        .metadata()
         .expect("Cannot read metadata for directory requested for search.")
         .dev()
 });
 
+// unlike the rest of the config, hidden files may be toggled at runtime in the interactive browse view
+pub static OPT_NO_HIDDEN: LazyLock<AtomicBool> =
+    LazyLock::new(|| AtomicBool::new(GLOBAL_CONFIG.opt_no_hidden));
+
+// like the config, except that every snapshot version of a deleted file is displayed, even in modes
+// which otherwise suppress snapshot versions, like --no-snap, with the deleted file's path after its versions
+static WITH_VERSIONS_CONFIG: LazyLock<Config> = LazyLock::new(|| {
+    let mut config = GLOBAL_CONFIG.clone();
+
+    if let Some(BulkExclusion::NoSnap) = config.opt_bulk_exclusion {
+        config.opt_bulk_exclusion = None;
+        config.opt_include_live = true;
+    }
+
+    config
+});
+
 static FILTER_DIRS_MAX_LEN: LazyLock<usize> =
     LazyLock::new(|| GLOBAL_CONFIG.dataset_collection.filter_dirs.max_len());
 
@@ -80,12 +102,16 @@ impl RecursiveSearch {
             // thread pool allows deleted to have its own scope, which means
             // all threads must complete before the scope exits.  this is important
             // for display recursive searches as the live enumeration will end before
-            // all deleted threads have completed
+            // all deleted threads have completed.
+            //
+            // this pool is bounded by THREADS too, like rayon's global pool.  the live enumeration
+            // and the deleted searches then share the same threads, not the number requested each
             let pool: ThreadPool = rayon::ThreadPoolBuilder::new()
+                .num_threads(GLOBAL_CONFIG.opt_threads.unwrap_or_default())
                 .build()
                 .expect("Could not initialize rayon threadpool for recursive deleted search");
 
-            pool.in_place_scope(|deleted_scope| {
+            let run_scoped = |deleted_scope: &Scope| {
                 run_loop(
                     requested_dir,
                     skim_tx,
@@ -93,7 +119,14 @@ impl RecursiveSearch {
                     hangup.clone(),
                     started,
                 )
-            })
+            };
+
+            // when bounded, the live enumeration must also run within the pool to be bounded
+            if GLOBAL_CONFIG.opt_threads.is_some() {
+                pool.scope(run_scoped)
+            } else {
+                pool.in_place_scope(run_scoped)
+            }
         } else {
             run_loop(requested_dir, skim_tx, None, hangup, started)
         }
@@ -127,39 +160,50 @@ impl RecursiveMainLoop {
             initial_vec_dirs.push(double_dot_as_entry)
         }
 
-        SharedRecursive::combine_and_send_entries(
-            vec![],
-            &initial_vec_dirs,
-            PathProvenance::FromLiveDataset,
-            requested_dir,
-            skim_tx,
-        )?;
+        // no need to display the requested dir and its parent in non-interactive modes
+        if matches!(GLOBAL_CONFIG.exec_mode, ExecMode::Interactive(_)) {
+            SharedRecursive::combine_and_send_entries(
+                vec![],
+                &initial_vec_dirs,
+                PathProvenance::FromLiveDataset,
+                requested_dir,
+                skim_tx,
+            )?;
+        }
 
         // runs once for non-recursive but also "primes the pump"
         // for recursive to have items available, also only place an
         // error can stop execution
-        let mut queue: Vec<BasicDirEntryInfo> =
-            Self::enter_directory(requested_dir, opt_deleted_scope, skim_tx, &hangup)?;
+        //
+        // each dir is queued alongside its depth below the requested dir, which is at depth zero
+        let queue: Vec<(BasicDirEntryInfo, usize)> =
+            Self::enter_directory(requested_dir, 0, opt_deleted_scope, skim_tx, &hangup)?;
 
         started.store(true, Ordering::SeqCst);
 
         if GLOBAL_CONFIG.opt_recursive {
-            // condition kills iter when user has made a selection
-            // pop_back makes this a LIFO queue which is supposedly better for caches
-            while let Some(item) = queue.pop() {
-                // check -- should deleted threads keep working?
-                // exit/error on disconnected channel, which closes
-                // at end of browse scope
-                if hangup.load(Ordering::Relaxed) {
-                    break;
-                }
-
-                // no errors will be propagated in recursive mode
-                // far too likely to run into a dir we don't have permissions to view
-                if let Ok(mut items) =
-                    Self::enter_directory(&item.path(), opt_deleted_scope, skim_tx, &hangup)
+            // check -- should deleted threads keep working?
+            // exit/error on disconnected channel, which closes
+            // at end of browse scope
+            // or, in non-interactive modes, has the user pressed Ctrl-C?
+            let is_stopped = || hangup.load(Ordering::Relaxed) || Interrupt::is_interrupted();
+
+            let opt_revisited = Self::walk(requested_dir, queue, is_stopped, |item_path, depth| {
+                Self::enter_directory(item_path, depth, opt_deleted_scope, skim_tx, &hangup)
+            });
+
+            // would only garble the interactive view, and once per run is enough
+            if let Some(item_path) = opt_revisited {
+                if !matches!(GLOBAL_CONFIG.exec_mode, ExecMode::Interactive(_))
+                    || GLOBAL_CONFIG.opt_debug
                 {
-                    queue.append(&mut items)
+                    static VISITED_NOTICE: Once = Once::new();
+
+                    VISITED_NOTICE.call_once(|| {
+                        eprintln!(
+                            "NOTICE: httm skipped at least one directory it had already visited, which may be a circular bind mount or link, such as: {item_path:?}"
+                        )
+                    });
                 }
             }
         }
@@ -167,15 +211,63 @@ impl RecursiveMainLoop {
         Ok(())
     }
 
+    // enters each dir queued, and each dir found within, only once, and returns
+    // the first dir skipped as already visited, if any
+    fn walk<E, S>(
+        requested_dir: &Path,
+        mut queue: Vec<(BasicDirEntryInfo, usize)>,
+        is_stopped: S,
+        enter: E,
+    ) -> Option<PathBuf>
+    where
+        E: Fn(&Path, usize) -> HttmResult<Vec<(BasicDirEntryInfo, usize)>>,
+        S: Fn() -> bool,
+    {
+        // track visited dirs by (dev, ino), so a circular bind mount or a symlink
+        // to an ancestor dir can never send us round in circles forever
+        let mut visited: HashSet<(u64, u64)> = HashSet::new();
+        let mut opt_revisited: Option<PathBuf> = None;
+
+        if let Ok(md) = requested_dir.metadata() {
+            visited.insert((md.dev(), md.ino()));
+        }
+
+        // condition kills iter when user has made a selection
+        // pop_back makes this a LIFO queue which is supposedly better for caches
+        while let Some((item, depth)) = queue.pop() {
+            if is_stopped() {
+                break;
+            }
+
+            let item_path = item.path();
+
+            if let Ok(md) = item_path.metadata() {
+                if !visited.insert((md.dev(), md.ino())) {
+                    opt_revisited.get_or_insert_with(|| item_path.to_path_buf());
+                    continue;
+                }
+            }
+
+            // no errors will be propagated in recursive mode
+            // far too likely to run into a dir we don't have permissions to view
+            if let Ok(mut items) = enter(item_path, depth) {
+                queue.append(&mut items)
+            }
+        }
+
+        opt_revisited
+    }
+
     fn enter_directory(
         requested_dir: &Path,
+        depth: usize,
         opt_deleted_scope: Option<&Scope>,
         skim_tx: &SkimItemSender,
         hangup: &Arc<AtomicBool>,
-    ) -> HttmResult<Vec<BasicDirEntryInfo>> {
+    ) -> HttmResult<Vec<(BasicDirEntryInfo, usize)>> {
         // combined entries will be sent or printed, but we need the vec_dirs to recurse
         let (vec_dirs, vec_files): (Vec<BasicDirEntryInfo>, Vec<BasicDirEntryInfo>) =
-            SharedRecursive::entries_partitioned(requested_dir)?;
+            SharedRecursive::entries_partitioned(requested_dir, opt_deleted_scope.is_some())?;
 
         SharedRecursive::combine_and_send_entries(
             vec_files,
@@ -185,11 +277,36 @@ impl RecursiveMainLoop {
             skim_tx,
         )?;
 
+        // deleted entries of this dir are at the same depth as its live entries,
+        // so only as many levels remain behind deleted dirs as remain for live dirs
+        let opt_remaining_depth = GLOBAL_CONFIG
+            .opt_max_depth
+            .map(|max_depth| max_depth.saturating_sub(depth) + 1);
+
         if let Some(deleted_scope) = opt_deleted_scope {
-            SpawnDeletedThread::exec(requested_dir, deleted_scope, skim_tx, hangup);
+            SpawnDeletedThread::exec(
+                requested_dir,
+                opt_remaining_depth,
+                deleted_scope,
+                skim_tx,
+                hangup,
+            );
         }
 
-        Ok(vec_dirs)
+        // skip enqueuing children once the max depth is reached
+        if GLOBAL_CONFIG
+            .opt_max_depth
+            .is_some_and(|max_depth| depth >= max_depth)
+        {
+            return Ok(Vec::new());
+        }
+
+        let next_depth = depth + 1;
+
+        Ok(vec_dirs
+            .into_iter()
+            .map(|entry| (entry, next_depth))
+            .collect())
     }
 }
 
@@ -211,7 +328,7 @@ impl SharedRecursive {
                 // live - not phantom
                 match GLOBAL_CONFIG.opt_deleted_mode {
                     Some(DeletedMode::Only) => return Ok(()),
-                    Some(DeletedMode::DepthOfOne | DeletedMode::All) | None => {
+                    Some(DeletedMode::Depth(_) | DeletedMode::All) => {
                         // never show live files is display recursive/deleted only file mode
                         if matches!(
                             GLOBAL_CONFIG.exec_mode,
@@ -221,6 +338,8 @@ impl SharedRecursive {
                         }
                         combined
                     }
+                    // plain display recursive mode shows only live files
+                    None => combined,
                 }
             }
             PathProvenance::IsPhantom => {
@@ -234,48 +353,123 @@ impl SharedRecursive {
 
     pub fn entries_partitioned(
         requested_dir: &Path,
+        should_cache_filenames: bool,
     ) -> HttmResult<(Vec<BasicDirEntryInfo>, Vec<BasicDirEntryInfo>)> {
+        // the deleted search compares against every live file name, filtered or not
+        let mut live_filenames = LiveFilenames::new();
+
         // separates entries into dirs and files
         let (vec_dirs, vec_files) = read_dir(requested_dir)?
             .flatten()
+            .inspect(|dir_entry| {
+                if should_cache_filenames {
+                    live_filenames.insert(dir_entry.file_name());
+                }
+            })
             // checking file_type on dir entries is always preferable
             // as it is much faster than a metadata call on the path
             .map(|dir_entry| BasicDirEntryInfo::from(&dir_entry))
             .filter(|entry| {
+                // user requested exclusions apply even when no other filter does
+                if Self::is_user_excluded(entry) {
+                    return false;
+                }
+
+                // like find -xdev, never descend across a mount boundary, even when unfiltered
                 if GLOBAL_CONFIG.opt_no_filter {
-                    return true;
+                    return !Self::is_entry_dir(entry) || Self::is_same_filesystem(entry);
                 }
 
-                if GLOBAL_CONFIG.opt_no_hidden
+                if OPT_NO_HIDDEN.load(Ordering::Relaxed)
                     && entry.filename().to_string_lossy().starts_with('.')
                 {
                     return false;
                 }
 
-                if GLOBAL_CONFIG.opt_one_filesystem {
-                    match entry.path().metadata() {
-                        Ok(path_md) if *OPT_REQUESTED_DIR_DEV == path_md.dev() => {}
-                        _ => {
-                            // if we can't read the metadata for a path,
-                            // we probably shouldn't show it either
-                            return false;
-                        }
-                    }
-                }
-
                 if let Ok(file_type) = entry.filetype() {
                     if file_type.is_dir() {
-                        return !Self::exclude_path(entry);
+                        return !Self::exclude_path(entry) && Self::is_same_filesystem(entry);
                     }
                 }
 
+                // symlinks to dirs may also be descended into
+                if GLOBAL_CONFIG.opt_one_filesystem && Self::is_entry_dir(entry) {
+                    return Self::is_same_filesystem(entry);
+                }
+
                 true
             })
-            .partition(Self::is_entry_dir);
+            .partition::<Vec<BasicDirEntryInfo>, _>(Self::is_entry_dir);
+
+        if should_cache_filenames {
+            LiveFilenamesCache::insert(requested_dir, live_filenames);
+        }
+
+        // dirs are always kept, so we may continue to recurse
+        let vec_files = match &GLOBAL_CONFIG.opt_extensions {
+            Some(extensions) => vec_files
+                .into_iter()
+                .filter(|entry| Self::is_matching_extension(entry, extensions))
+                .collect(),
+            None => vec_files,
+        };
 
         Ok((vec_dirs, vec_files))
     }
 
+    fn is_matching_extension(entry: &BasicDirEntryInfo, extensions: &[String]) -> bool {
+        let Some(entry_ext) = entry.path().extension() else {
+            return false;
+        };
+
+        let entry_ext = entry_ext.to_string_lossy();
+
+        if GLOBAL_CONFIG.opt_ext_strict {
+            return extensions.iter().any(|ext| *ext == entry_ext);
+        }
+
+        let entry_ext = entry_ext.to_lowercase();
+
+        extensions.contains(&entry_ext)
+    }
+
+    fn is_user_excluded(entry: &BasicDirEntryInfo) -> bool {
+        let Some(patterns) = &GLOBAL_CONFIG.opt_exclude else {
+            return false;
+        };
+
+        let path = entry.path();
+        let file_name = entry.filename().to_string_lossy();
+
+        // entries behind deleted dirs are not relative to the requested dir, so only match the file name
+        let opt_relative_path = GLOBAL_CONFIG
+            .opt_requested_dir
+            .as_ref()
+            .and_then(|requested_dir| path.strip_prefix(requested_dir).ok())
+            .map(|relative_path| relative_path.to_string_lossy());
+
+        patterns.iter().any(|pattern| {
+            pattern.is_match(&file_name)
+                || opt_relative_path
+                    .as_ref()
+                    .is_some_and(|relative_path| pattern.is_match(relative_path))
+        })
+    }
+
+    fn is_same_filesystem(entry: &BasicDirEntryInfo) -> bool {
+        if !GLOBAL_CONFIG.opt_one_filesystem {
+            return true;
+        }
+
+        // like find -xdev, only a dir can be a mount point we might descend into,
+        // so only dirs need the extra metadata call. if we can't read the metadata
+        // for a dir, we probably shouldn't show it either
+        match entry.path().metadata() {
+            Ok(path_md) => *OPT_REQUESTED_DIR_DEV == path_md.dev(),
+            Err(_) => false,
+        }
+    }
+
     pub fn is_entry_dir(entry: &BasicDirEntryInfo) -> bool {
         // must do is_dir() look up on DirEntry file_type() as look up on Path will traverse links!
         if GLOBAL_CONFIG.opt_no_traverse {
@@ -295,7 +489,10 @@ impl SharedRecursive {
         // never check the hidden snapshot directory for live files (duh)
         // didn't think this was possible until I saw a SMB share return
         // a .zfs dir entry
-        if path.ends_with(ZFS_HIDDEN_DIRECTORY) || path.ends_with(BTRFS_SNAPPER_HIDDEN_DIRECTORY) {
+        if path.ends_with(ZFS_HIDDEN_DIRECTORY)
+            || path.ends_with(BTRFS_SNAPPER_HIDDEN_DIRECTORY)
+            || SnapDirName::is_user_hidden_dir(path)
+        {
             return true;
         }
 
@@ -314,7 +511,8 @@ impl SharedRecursive {
         }
 
         // finally : is a non-supported dataset?
-        // bailout easily if path is larger than max_filter_dir len
+        // bailout easily if path is larger than max_filter_dir len, this is sound
+        // however deeply nested the filter dir, see MaxLen for FilterDirs
         if path.components().count() > *FILTER_DIRS_MAX_LEN {
             return false;
         }
@@ -359,7 +557,7 @@ impl SharedRecursive {
             )
                     }
                 } else {
-                    NonInteractiveRecursiveWrapper::print(entries)?;
+                    NonInteractiveRecursiveWrapper::print(entries, is_phantom)?;
 
                     // keeps spinner from squashing last line of output
                     if GLOBAL_CONFIG.opt_recursive {
@@ -401,6 +599,9 @@ impl NonInteractiveRecursiveWrapper {
         let started = Arc::new(AtomicBool::new(true));
         let hangup = Arc::new(AtomicBool::new(false));
 
+        // wind down gracefully on Ctrl-C, as there is no skim to intercept the key
+        Interrupt::install()?;
+
         match &GLOBAL_CONFIG.opt_requested_dir {
             Some(requested_dir) => {
                 RecursiveSearch::exec(requested_dir, dummy_skim_tx, hangup, started);
@@ -413,15 +614,83 @@ impl NonInteractiveRecursiveWrapper {
             }
         }
 
+        // everything found before the interrupt has been printed and flushed,
+        // so exit as a shell would expect of a process ended by SIGINT
+        if Interrupt::is_interrupted() {
+            eprintln!("NOTICE: httm was interrupted.  Recursive search ended early.");
+            std::process::exit(130)
+        }
+
         Ok(())
     }
 
-    fn print(entries: Vec<BasicDirEntryInfo>) -> HttmResult<()> {
-        let pseudo_live_set: Vec<PathData> = entries.into_iter().map(PathData::from).collect();
+    fn print(entries: Vec<BasicDirEntryInfo>, is_phantom: PathProvenance) -> HttmResult<()> {
+        let mut pseudo_live_set: Vec<PathData> = entries.into_iter().map(PathData::from).collect();
+
+        // versions are displayed in the order of the paths requested, so in path order here
+        pseudo_live_set.sort_unstable();
 
         let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &pseudo_live_set)?;
-        let output_buf = VersionsDisplayWrapper::from(&GLOBAL_CONFIG, versions_map).to_string();
+
+        let config: &Config = match is_phantom {
+            PathProvenance::IsPhantom if GLOBAL_CONFIG.opt_with_versions => &WITH_VERSIONS_CONFIG,
+            _ => &GLOBAL_CONFIG,
+        };
+
+        let output_buf = VersionsDisplayWrapper::from(config, versions_map).to_string();
 
         print_output_buf(&output_buf)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn walk_enters_each_dir_of_a_symlink_loop_once() {
+        let root = std::env::temp_dir().join(format!("httm-test-walk-loop-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("a/b")).unwrap();
+        std::os::unix::fs::symlink(&root, root.join("a/b/to_root")).unwrap();
+        std::os::unix::fs::symlink("..", root.join("a/to_parent")).unwrap();
+
+        let entered: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+        // like a search which follows symlinks, each symlink to a dir is itself a dir
+        let enter = |dir: &Path, depth: usize| -> HttmResult<Vec<(BasicDirEntryInfo, usize)>> {
+            entered.lock().unwrap().push(dir.canonicalize()?);
+
+            let dirs = std::fs::read_dir(dir)?
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| {
+                    let entry = BasicDirEntryInfo::new(entry.path(), entry.file_type().ok());
+                    (entry, depth + 1)
+                })
+                .collect();
+
+            Ok(dirs)
+        };
+
+        let queue = enter(&root, 0).unwrap();
+        let opt_revisited = RecursiveMainLoop::walk(&root, queue, || false, enter);
+
+        assert!(opt_revisited.is_some());
+
+        let mut entered = entered.into_inner().unwrap();
+        entered.sort();
+
+        let canonical_root = root.canonicalize().unwrap();
+        let expected = vec![
+            canonical_root.clone(),
+            canonical_root.join("a"),
+            canonical_root.join("a/b"),
+        ];
+
+        assert_eq!(entered, expected);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
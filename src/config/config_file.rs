@@ -0,0 +1,462 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use crate::library::results::{HttmError, HttmResult};
+use clap::parser::ValueSource;
+use clap::{Arg, ArgMatches, Command};
+use indexmap::IndexMap;
+use serde::de::{Deserialize, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+const CONFIG_DIR: &str = "httm";
+const CONFIG_FILE_NAMES: [&str; 2] = ["config.toml", "config.json"];
+
+// default flags, keyed by their long names, such as "recursive = true" or "deleted = \"all\"",
+// which are given to clap before the flags requested on the command line
+pub struct ConfigFile {
+    path: PathBuf,
+    entries: IndexMap<String, FileValue>,
+}
+
+impl ConfigFile {
+    // a requested config file must exist, whereas the default config file is optional
+    pub fn new(opt_requested: Option<&Path>) -> HttmResult<Option<Self>> {
+        let path = match opt_requested {
+            Some(requested) => requested.to_path_buf(),
+            None => match Self::default_path() {
+                Some(default) => default,
+                None => return Ok(None),
+            },
+        };
+
+        let contents = std::fs::read_to_string(&path).map_err(|err| {
+            let msg = format!("httm could not read the config file requested: {:?}", path);
+            HttmError::with_context(&msg, &err)
+        })?;
+
+        Self::from_contents(path, &contents).map(Some)
+    }
+
+    fn from_contents(path: PathBuf, contents: &str) -> HttmResult<Self> {
+        let res = if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            serde_json::from_str(contents).map_err(|err| err.to_string())
+        } else {
+            toml::from_str(contents).map_err(|err| err.to_string())
+        };
+
+        match res {
+            Ok(entries) => Ok(Self { path, entries }),
+            Err(err) => {
+                let msg = format!("httm could not parse the config file {:?}: {}", path, err);
+                Err(HttmError::new(&msg).into())
+            }
+        }
+    }
+
+    fn default_path() -> Option<PathBuf> {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|config_home| !config_home.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+        CONFIG_FILE_NAMES
+            .iter()
+            .map(|file_name| config_home.join(CONFIG_DIR).join(file_name))
+            .find(|path| path.exists())
+    }
+
+    // flags requested on the command line override the same flags in the config file, and a value
+    // which conflicts with the flags requested is ignored.  Once combined, the flags are validated together,
+    // and, when invalid, the config file is ignored as a whole, so the config file never breaks an invocation
+    pub fn merge(
+        &self,
+        command: &Command,
+        cli_args: &[OsString],
+        cli_matches: &ArgMatches,
+    ) -> Vec<OsString> {
+        let Some((bin_name, requested_args)) = cli_args.split_first() else {
+            return cli_args.to_vec();
+        };
+
+        let file_args: Vec<OsString> = self
+            .entries
+            .iter()
+            .filter_map(|(key, value)| self.entry_args(command, cli_matches, key, value))
+            .flatten()
+            .collect();
+
+        let merged_args: Vec<OsString> = std::iter::once(bin_name)
+            .chain(file_args.iter())
+            .chain(requested_args.iter())
+            .cloned()
+            .collect();
+
+        match command.clone().try_get_matches_from(&merged_args) {
+            Ok(_) => merged_args,
+            Err(err) => {
+                let rendered = err.to_string();
+                let reason = rendered
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim_start_matches("error: ");
+
+                eprintln!(
+                    "WARN: httm ignored the config file {:?}: {}",
+                    self.path, reason
+                );
+
+                cli_args.to_vec()
+            }
+        }
+    }
+
+    fn entry_args(
+        &self,
+        command: &Command,
+        cli_matches: &ArgMatches,
+        key: &str,
+        value: &FileValue,
+    ) -> Option<Vec<OsString>> {
+        let long = key.replace('_', "-");
+
+        let Some(arg) = command.get_arguments().find(|arg| {
+            arg.get_long() == Some(long.as_str())
+                || arg
+                    .get_all_aliases()
+                    .is_some_and(|aliases| aliases.contains(&long.as_str()))
+        }) else {
+            eprintln!(
+                "WARN: httm ignored {:?} in the config file {:?}, as no such flag exists.",
+                key, self.path
+            );
+            return None;
+        };
+
+        let is_requested = |arg: &Arg| {
+            matches!(
+                cli_matches.value_source(arg.get_id().as_str()),
+                Some(ValueSource::CommandLine)
+            )
+        };
+
+        if arg.get_id() == "CONFIG" || is_requested(arg) {
+            return None;
+        }
+
+        let is_conflict = |first: &Arg, second: &Arg| {
+            command
+                .get_arg_conflicts_with(first)
+                .iter()
+                .any(|conflict| conflict.get_id() == second.get_id())
+        };
+
+        if let Some(requested) = command
+            .get_arguments()
+            .filter(|other| is_requested(other))
+            .find(|other| is_conflict(arg, other) || is_conflict(other, arg))
+        {
+            eprintln!(
+                "WARN: httm ignored {:?} in the config file {:?}, as it conflicts with {:?}, requested on the command line.",
+                key,
+                self.path,
+                requested.get_id().as_str()
+            );
+            return None;
+        }
+
+        let single = |value: &FileValue| match value {
+            FileValue::String(string) | FileValue::Number(string) => {
+                Some(OsString::from(format!("--{long}={string}")))
+            }
+            _ => None,
+        };
+
+        let opt_args = match value {
+            // a false boolean is the same as the flag's absence
+            FileValue::Bool(true) => Some(vec![OsString::from(format!("--{long}"))]),
+            FileValue::Bool(false) => Some(Vec::new()),
+            FileValue::String(_) | FileValue::Number(_) => single(value).map(|arg| vec![arg]),
+            FileValue::Array(values) => values.iter().map(single).collect(),
+            FileValue::Unsupported => None,
+        };
+
+        if opt_args.is_none() {
+            eprintln!(
+                "WARN: httm ignored {:?} in the config file {:?}, as its value is not a boolean, a number, a string, or an array of strings.",
+                key, self.path
+            );
+        }
+
+        opt_args
+    }
+}
+
+// the values, common to TOML and JSON, which map to flags, with a number kept as it would be written
+// on the command line, and any other value, such as a table or a date, kept only to warn it is unsupported
+#[derive(Debug, PartialEq)]
+enum FileValue {
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<FileValue>),
+    Unsupported,
+}
+
+impl<'de> Deserialize<'de> for FileValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(FileValueVisitor)
+    }
+}
+
+struct FileValueVisitor;
+
+impl<'de> Visitor<'de> for FileValueVisitor {
+    type Value = FileValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a boolean, a number, a string, or an array")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E> {
+        Ok(FileValue::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(FileValue::Number(value.to_string()))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(FileValue::Number(value.to_string()))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E> {
+        Ok(FileValue::Number(value.to_string()))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(FileValue::String(value.to_owned()))
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(FileValue::Unsupported)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::new();
+
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+
+        Ok(FileValue::Array(values))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+
+        Ok(FileValue::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_command() -> Command {
+        Command::new("httm")
+            .arg(Arg::new("INPUT_FILES").action(clap::ArgAction::Append))
+            .arg(
+                Arg::new("RECURSIVE")
+                    .long("recursive")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("DELETED")
+                    .long("deleted")
+                    .value_parser(["all", "single", "only"]),
+            )
+            .arg(
+                Arg::new("SNAP_POINT")
+                    .long("snap-point")
+                    .visible_alias("map-aliases")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                Arg::new("LAST_SNAP")
+                    .long("last-snap")
+                    .action(clap::ArgAction::SetTrue)
+                    .conflicts_with("DELETED"),
+            )
+            .arg(
+                Arg::new("CONFIG")
+                    .long("config")
+                    .value_parser(clap::value_parser!(PathBuf)),
+            )
+    }
+
+    fn merged(contents: &str, file_name: &str, cli_args: &[&str]) -> Vec<String> {
+        let config_file = ConfigFile::from_contents(PathBuf::from(file_name), contents).unwrap();
+
+        let command = test_command();
+        let cli_args: Vec<OsString> = cli_args.iter().map(OsString::from).collect();
+        let cli_matches = command.clone().get_matches_from(&cli_args);
+
+        config_file
+            .merge(&command, &cli_args, &cli_matches)
+            .into_iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn toml_and_json_files_parse_to_the_same_values() {
+        let toml = ConfigFile::from_contents(
+            PathBuf::from("config.toml"),
+            "# a comment\nrecursive = true\ndeleted = 'all' # literal\nthreads = 2\nsnap_point = [\"a\", \"b\"]\n[table]\nkey = 1\n",
+        )
+        .unwrap();
+
+        let json = ConfigFile::from_contents(
+            PathBuf::from("config.json"),
+            r#"{"recursive": true, "deleted": "all", "threads": 2, "snap_point": ["a", "b"], "table": {"key": 1}}"#,
+        )
+        .unwrap();
+
+        let expected = [
+            ("recursive", FileValue::Bool(true)),
+            ("deleted", FileValue::String("all".to_owned())),
+            ("threads", FileValue::Number("2".to_owned())),
+            (
+                "snap_point",
+                FileValue::Array(vec![
+                    FileValue::String("a".to_owned()),
+                    FileValue::String("b".to_owned()),
+                ]),
+            ),
+            ("table", FileValue::Unsupported),
+        ];
+
+        [toml, json].iter().for_each(|config_file| {
+            expected.iter().for_each(|(key, value)| {
+                assert_eq!(config_file.entries.get(*key), Some(value));
+            })
+        });
+    }
+
+    #[test]
+    fn invalid_files_are_errors() {
+        assert!(ConfigFile::from_contents(PathBuf::from("config.toml"), "recursive = ").is_err());
+        assert!(ConfigFile::from_contents(PathBuf::from("config.json"), "[true]").is_err());
+    }
+
+    #[test]
+    fn file_flags_precede_the_flags_requested() {
+        assert_eq!(
+            merged(
+                "recursive = true\nsnap_point = [\"a\", \"b\"]\n",
+                "config.toml",
+                &["httm", "/tmp"],
+            ),
+            [
+                "httm",
+                "--recursive",
+                "--snap-point=a",
+                "--snap-point=b",
+                "/tmp"
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_requested_override_the_file() {
+        assert_eq!(
+            merged(
+                "deleted = \"all\"\nmap_aliases = [\"a\"]\n",
+                "config.toml",
+                &["httm", "--deleted=only", "--snap-point=b"],
+            ),
+            ["httm", "--deleted=only", "--snap-point=b"]
+        );
+    }
+
+    #[test]
+    fn false_booleans_unknown_keys_and_unsupported_values_are_ignored() {
+        assert_eq!(
+            merged(
+                "recursive = false\nno_such_flag = true\ndeleted = [[\"all\"]]\nconfig = \"other.toml\"\n",
+                "config.toml",
+                &["httm"],
+            ),
+            ["httm"]
+        );
+    }
+
+    #[test]
+    fn values_which_conflict_with_the_flags_requested_are_ignored() {
+        assert_eq!(
+            merged(
+                "deleted = \"all\"\n",
+                "config.toml",
+                &["httm", "--last-snap"]
+            ),
+            ["httm", "--last-snap"]
+        );
+        assert_eq!(
+            merged(
+                "last_snap = true\n",
+                "config.toml",
+                &["httm", "--deleted=all"]
+            ),
+            ["httm", "--deleted=all"]
+        );
+    }
+
+    #[test]
+    fn an_invalid_combination_ignores_the_whole_file() {
+        assert_eq!(
+            merged(
+                r#"{"recursive": true, "deleted": "all", "last_snap": true}"#,
+                "config.json",
+                &["httm", "/tmp"],
+            ),
+            ["httm", "/tmp"]
+        );
+        assert_eq!(
+            merged(
+                "recursive = true\ndeleted = \"none\"\n",
+                "config.toml",
+                &["httm"]
+            ),
+            ["httm"]
+        );
+    }
+}
@@ -16,21 +16,34 @@
 // that was distributed with this source code.
 
 use crate::config::install_hot_keys::install_hot_keys;
+use crate::config::config_file::ConfigFile;
 use crate::data::filesystem_info::FilesystemInfo;
 use crate::data::paths::PathDeconstruction;
 use crate::data::paths::{PathData, ZfsSnapPathGuard};
 use crate::library::results::{HttmError, HttmResult};
-use crate::library::utility::{pwd, HttmIsDir};
+use crate::library::utility::{
+    glob_to_regex, parse_date_format, parse_duration, parse_size, parse_time_bound, pwd,
+    HttmIsDir, FOLLOW_SYMLINKS,
+};
 use crate::lookup::file_mounts::MountDisplay;
-use crate::parse::mounts::FilesystemType;
+use crate::parse::mounts::{FilesystemType, OPT_SNAP_DIR_NAME};
+use crate::roll_forward::exec::RollForward;
+use crate::zfs::run_command::RunZFSCommand;
+use crate::zfs::snap_mounts::SnapNameTemplate;
 use crate::ROOT_DIRECTORY;
 use clap::parser::ValuesRef;
-use clap::{crate_name, crate_version, Arg, ArgAction, ArgMatches};
+use clap::{crate_name, crate_version, Arg, ArgAction, ArgMatches, Command};
 use indicatif::ProgressBar;
 use rayon::prelude::*;
-use std::io::Read;
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::{OsStr, OsString};
+use std::io::{IsTerminal, Read};
+use std::os::unix::ffi::OsStrExt;
 use std::ops::Index;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use time::format_description::OwnedFormatItem;
 use time::UtcOffset;
 
 #[derive(Debug, Clone)]
@@ -43,7 +56,12 @@ pub enum ExecMode {
     MountsForFiles(MountDisplay),
     SnapsForFiles(Option<ListSnapsFilters>),
     NumVersions(NumVersionsMode),
+    StaleThan(Duration),
+    Stat(Option<Duration>),
     RollForward(String),
+    CompareDatasets(PathBuf, PathBuf),
+    MountSnapshot(String, PathBuf),
+    JsonSchema,
 }
 
 #[derive(Debug, Clone)]
@@ -70,6 +88,7 @@ pub enum SelectMode {
     Path,
     Contents,
     Preview,
+    Diff,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -87,13 +106,56 @@ pub enum PrintMode {
     RawZero,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonFormat {
+    Pretty,
+    Compact,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FollowMode {
+    Watch,
+    Interval(Duration),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DeletedMode {
-    DepthOfOne,
+    Depth(usize),
     All,
     Only,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowSymlinks {
+    Never,
+    Dirs,
+    Always,
+}
+
+#[derive(Debug, Clone)]
+pub enum SizeFormat {
+    Binary,
+    Decimal,
+    Raw,
+}
+
+// key: live path, val: the number of the snapshot version requested, 1 being the newest
+pub type MapOfNumberedVersions = BTreeMap<PathBuf, usize>;
+
+#[derive(Debug, Clone)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone)]
+pub enum SortBy {
+    Date,
+    Size,
+    Path,
+}
+
 #[derive(Debug, Clone)]
 pub enum DedupBy {
     Disable,
@@ -101,6 +163,69 @@ pub enum DedupBy {
     Contents,
 }
 
+// how the paths read from stdin, or from a file, are split
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSplit {
+    Heuristic,
+    Null,
+    Exact,
+}
+
+// which attributes are copied when preserving attributes, by default all of them
+#[derive(Debug, Clone, Copy)]
+pub struct PreserveAttrs {
+    pub mode: bool,
+    pub owner: bool,
+    #[cfg_attr(not(feature = "xattrs"), allow(dead_code))]
+    pub xattr: bool,
+    #[cfg_attr(not(feature = "acls"), allow(dead_code))]
+    pub acl: bool,
+    pub time: bool,
+}
+
+impl Default for PreserveAttrs {
+    fn default() -> Self {
+        Self {
+            mode: true,
+            owner: true,
+            xattr: true,
+            acl: true,
+            time: true,
+        }
+    }
+}
+
+impl PreserveAttrs {
+    fn new(values: &str) -> HttmResult<Self> {
+        let mut preserve_attrs = Self {
+            mode: false,
+            owner: false,
+            xattr: false,
+            acl: false,
+            time: false,
+        };
+
+        for value in values.split(',').map(str::trim).filter(|value| !value.is_empty()) {
+            match value {
+                "mode" => preserve_attrs.mode = true,
+                "owner" | "ownership" => preserve_attrs.owner = true,
+                "xattr" | "xattrs" => preserve_attrs.xattr = true,
+                "acl" | "acls" => preserve_attrs.acl = true,
+                "time" | "timestamps" => preserve_attrs.time = true,
+                "all" => preserve_attrs = Self::default(),
+                _ => {
+                    let msg = format!(
+                        "PRESERVE attribute \"{value}\" is not a recognized attribute.  Valid attributes are: \"mode\", \"owner\", \"xattr\", \"acl\", \"time\", or \"all\"."
+                    );
+                    return Err(HttmError::new(&msg).into());
+                }
+            }
+        }
+
+        Ok(preserve_attrs)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ListSnapsFilters {
     pub select_mode: bool,
@@ -108,6 +233,12 @@ pub struct ListSnapsFilters {
     pub name_filters: Option<Vec<String>>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotOutput {
+    Names,
+    Json,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LastSnapMode {
     Any,
@@ -134,11 +265,38 @@ const NATIVE_SNAP_SUFFIXES: [&str; 4] = [
     "httmSnapRestore",
 ];
 
-fn parse_args() -> ArgMatches {
+fn parse_args() -> HttmResult<ArgMatches> {
+    let command = build_command();
+
+    // the test harness's arguments are not httm's, so tests generate a config, as if only for the JSON schema,
+    // which needs neither a config file, nor any supported dataset
+    if cfg!(test) {
+        return Ok(command.get_matches_from(["httm", "--json-schema"]));
+    }
+
+    // help and version requests, and invalid requests, exit here, before any config file is read
+    let cli_args: Vec<OsString> = std::env::args_os().collect();
+    let cli_matches = command.clone().get_matches_from(&cli_args);
+
+    let opt_requested_config = cli_matches.get_one::<PathBuf>("CONFIG");
+
+    match ConfigFile::new(opt_requested_config.map(PathBuf::as_path))? {
+        Some(config_file) => {
+            let merged_args = config_file.merge(&command, &cli_args, &cli_matches);
+            Ok(command.get_matches_from(merged_args))
+        }
+        None => Ok(cli_matches),
+    }
+}
+
+fn build_command() -> Command {
     clap::command!(crate_name!())
         .about("httm prints the size, date and corresponding locations of available unique versions of files residing on snapshots. \
         May also be used interactively to select and restore from such versions, and even to snapshot datasets which contain certain files.")
         .version(crate_version!())
+        .after_long_help("Exit status: 0 on success, and, when displaying versions, when at least one snapshot version was found, \
+        1 upon any error, 2 upon invalid usage, 3 when displaying versions, and the requested paths exist, but no snapshot version was found, \
+        and 4 when displaying versions, and neither a live version, nor any snapshot version, was found.")
         .arg(
             Arg::new("INPUT_FILES")
                 .help("in any non-interactive mode, put requested paths here. If you include no paths as arguments, \
@@ -195,6 +353,103 @@ fn parse_args() -> ArgMatches {
                 .display_order(4)
                 .action(ArgAction::Append)
         )
+        .arg(
+            Arg::new("RESTORE_TO")
+                .long("restore-to")
+                .requires("RESTORE")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("in the \"copy\" or \"copy-and-preserve\" restore modes, restore each snapshot version beneath the directory specified, \
+                instead of to the current working directory, such that the old version may be inspected side-by-side with the live version. \
+                The path of each file relative to its dataset is recreated beneath the directory specified, which will be created if it does not exist. \
+                httm will not overwrite any file which already exists beneath the directory specified.")
+                .display_order(4)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("MAX_RESTORE_SIZE")
+                .long("max-restore-size")
+                .alias("quota")
+                .requires("RESTORE")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("in a restore mode, refuse to restore any snapshot version, such as a directory, whose total size in bytes exceeds the value specified. \
+                Regardless of this value, httm will always refuse to restore a snapshot version larger than the space available at its destination.")
+                .display_order(4)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("FROM_SNAPSHOT")
+                .long("from-snapshot")
+                .requires_all(["RESTORE", "INPUT_FILES"])
+                .value_parser(clap::value_parser!(String))
+                .help("in restore mode, skip the interactive dialogs and restore each input file from the snapshot specified by name, \
+                such as \"snap_x\" (or the snapshot mounted at \".zfs/snapshot/snap_x\" on the file's dataset). \
+                Restore mode semantics, such as \"overwrite\" or \"guard\", remain the same. Note: this is a ZFS only option.")
+                .display_order(4)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("MERGE")
+                .long("merge")
+                .requires("RESTORE")
+                .conflicts_with("BACKUP_EXISTING")
+                .help("in the \"overwrite\" or \"guard\" restore modes, when restoring a directory, merge the snapshot version into the live directory, \
+                copying only those files which are newer on the snapshot, or which only exist on the snapshot, and leaving alone those live files \
+                which are newer or the same.")
+                .display_order(4)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("PROGRESS")
+                .long("progress")
+                .requires("RESTORE")
+                .help("in restore mode, when restoring a directory, display a progress bar of the bytes and files copied, \
+                in place of a line for each path restored.  The progress bar is only displayed when stderr is a terminal.")
+                .display_order(4)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("NO_CLOBBER")
+                .long("no-clobber")
+                .requires("RESTORE")
+                .conflicts_with_all(["MERGE", "BACKUP_EXISTING"])
+                .help("in restore mode, like \"cp -n\", never overwrite an existing file.  Each path is checked as httm descends a directory restored, \
+                and any path which already exists at the destination is skipped, with a notice.  Most useful with the \"overwrite\" restore mode.")
+                .display_order(4)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("BACKUP_EXISTING")
+                .long("backup-existing")
+                .requires("RESTORE")
+                .help("in restore mode, before a restore overwrites an existing file or directory, move the existing file or directory aside, \
+                to the same path with a \".httm-backup-<timestamp>\" suffix, such that a mistaken restore is recoverable, even without a snapshot. \
+                Most useful with the \"overwrite\" restore mode.")
+                .display_order(4)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("DRY_RUN")
+                .long("dry-run")
+                .help("in snapshot mode, or in a restore mode, print the snapshot commands httm would run, including any precautionary snapshot taken by a guarded restore, \
+                as well as any copy a restore would perform, but do not actually take any snapshot, or restore any file.  \
+                In prune mode, list the snapshots which would be destroyed, even when CONFIRM is specified.  \
+                In mount snap mode, print the mount command httm would run.  \
+                Should the user lack the privileges required, httm will warn, rather than quit, such that the commands may be audited before granting those privileges.")
+                .display_order(4)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("VERIFY")
+                .long("verify")
+                .requires("RESTORE")
+                .help("in restore mode, after each file is copied, verify the copy by comparing the size and then, byte for byte, the contents of both the source and the destination files. \
+                If they differ, httm will quit with an error naming the mismatched path.  Useful for catching truncated copies, such as on flaky network shares, \
+                but can be expensive, as every file restored must be read back.")
+                .display_order(4)
+                .action(ArgAction::SetTrue)
+        )
         .arg(
             Arg::new("DELETED")
                 .short('d')
@@ -210,12 +465,44 @@ fn parse_args() -> ArgMatches {
                 .display_order(5)
                 .action(ArgAction::Append)
         )
+        .arg(
+            Arg::new("DELETED_DEPTH")
+                .long("deleted-depth")
+                .requires("RECURSIVE")
+                .value_parser(clap::value_parser!(usize))
+                .help("in recursive mode, search for deleted files, but search behind deleted directories only to the depth specified. \
+                A depth of one is the same as the \"single\" deleted mode, and includes only the deleted files and directories of each live directory. \
+                A depth of two also includes the contents of those deleted directories, and so on.")
+                .display_order(5)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("MAX_DEPTH")
+                .long("max-depth")
+                .requires("RECURSIVE")
+                .value_parser(clap::value_parser!(usize))
+                .help("in recursive mode, descend only to the depth specified below the requested directory. \
+                A depth of zero includes only the entries of the requested directory itself, a depth of one also includes the entries of its sub-directories, and so on. \
+                Deleted files found behind deleted directories are subject to the same bound.")
+                .display_order(5)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("WITH_VERSIONS")
+                .long("with-versions")
+                .requires("DELETED")
+                .help("in the non-interactive deleted file modes, display all the snapshot versions of each deleted file found, \
+                even in those modes which would otherwise display only the path of each deleted file, such as NO_SNAP.")
+                .display_order(5)
+                .action(ArgAction::SetTrue)
+        )
         .arg(
             Arg::new("RECURSIVE")
                 .short('R')
                 .long("recursive")
                 .conflicts_with_all(&["SNAPSHOT"])
-                .help("recurse into the selected directory to find more files. Only available in interactive and deleted file modes.")
+                .help("recurse into the selected directory to find more files. In interactive and deleted file modes, recursion extends the search into subdirectories. \
+                When no interactive or deleted mode is specified, httm will non-interactively display the versions of every file found beneath the requested directory.")
                 .display_order(6)
                 .action(ArgAction::SetTrue)
         )
@@ -246,6 +533,17 @@ fn parse_args() -> ArgMatches {
                 .display_order(8)
                 .action(ArgAction::Append)
         )
+        .arg(
+            Arg::new("DIFF")
+                .long("diff")
+                .help("interactive browse and search a specified directory, as with SELECT, but, once a snapshot version is selected, \
+                display the differences between that snapshot version and its live version, such as before a restore.  \
+                The differ is \"diff -u\" by default, but the user may specify another differ and its arguments via the environment variable HTTM_DIFFER, \
+                such as \"HTTM_DIFFER='git diff --no-index'\".  The differ is given the snapshot version path, and then the live version path, as its last two arguments.")
+                .conflicts_with("RESTORE")
+                .display_order(8)
+                .action(ArgAction::SetTrue)
+        )
         .arg(
             Arg::new("DEDUP_BY")
                 .long("dedup-by")
@@ -286,6 +584,121 @@ fn parse_args() -> ArgMatches {
                 .display_order(11)
                 .action(ArgAction::Append)
         )
+        .arg(
+            Arg::new("LABEL")
+                .long("label")
+                .requires("SNAPSHOT")
+                .value_parser(clap::value_parser!(String))
+                .help("when taking snapshots, set a ZFS user property, in the form KEY=VALUE, on each snapshot taken, such as \"httm:batch=nightly\". \
+                Useful for later grouping snapshots with 'zfs get'. May be specified multiple times. \
+                Per ZFS, a user property name must contain a colon. A failure to set a property will not fail the snapshot.")
+                .display_order(11)
+                .action(ArgAction::Append)
+        )
+        .arg(
+            Arg::new("SNAP_NAME_TEMPLATE")
+                .long("snap-name-template")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(String))
+                .help("when taking snapshots, name each snapshot according to the template specified, instead of the default \"{dataset}@snap_{timestamp}_{suffix}\", \
+                such as to match the naming conventions of other snapshot tools, like \"{dataset}@autosnap-{hostname}-{timestamp}\".  \
+                The placeholders are \"{dataset}\", \"{timestamp}\", \"{suffix}\", the snapshot suffix, and \"{hostname}\".  \
+                A template must begin with \"{dataset}@\", and must produce a legal ZFS snapshot name, which may not contain spaces.  \
+                Any precautionary snapshots taken before a restore or a roll forward are also named according to the template, \
+                and, if the template lacks a \"{suffix}\", such snapshots have \"_\" and their suffix appended, so pre and post snapshots remain distinct.  \
+                Note: Other snapshots named by a template which lacks a \"{suffix}\" will not be matched by \"--list-snaps=native\", as it matches upon the suffixes of snapshots taken by httm.  \
+                Note: This is a ZFS only option.")
+                .display_order(11)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("SNAPSHOT_OUTPUT")
+                .long("snapshot-output")
+                .alias("snap-output")
+                .require_equals(true)
+                .value_parser(["names", "json"])
+                .requires("SNAPSHOT")
+                .help("when taking snapshots, display the snapshots taken in a format suitable for scripts. \
+                The value \"names\" will display only the names of the snapshots taken, one per line, and \"json\" will display \
+                a JSON array of each snapshot taken with its dataset, pool, and creation timestamp. \
+                Specifying \"--json\" when taking snapshots is the same as specifying \"json\" here.")
+                .display_order(11)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("SNAPSHOT_IF_CHANGED")
+                .long("snapshot-if-changed")
+                .aliases(["snap-if-changed", "if-changed"])
+                .requires("SNAPSHOT")
+                .help("when taking snapshots, skip any dataset to which no data has been written since its latest snapshot (its 'written' property is zero), \
+                so idle datasets do not accumulate no-op snapshots. If the 'written' property cannot be read, httm will take the snapshot anyway.")
+                .display_order(11)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("SNAPSHOT_FROM_STDIN")
+                .long("snapshot-from-stdin")
+                .alias("snap-from-stdin")
+                .requires("SNAPSHOT")
+                .help("when taking snapshots, also read paths from stdin, in addition to any paths specified as arguments, \
+                such as: \"find ~ -newer ~/.last_run | httm --snap --snapshot-from-stdin\".  \
+                Paths read from stdin are delimited in the same manner as in other modes.")
+                .display_order(11)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("DATASETS_FROM")
+                .long("datasets-from")
+                .alias("recursive-datasets-from")
+                .requires("SNAPSHOT")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("when taking snapshots, snapshot the datasets listed in the file specified, rather than the datasets of any paths specified. \
+                Each line of the file may contain either a ZFS dataset name, like \"rpool/home\", or a dataset mount, like \"/home\". \
+                Empty lines and those lines beginning with \"#\" are ignored.  By default, an unknown dataset is an error.  See also IGNORE_UNKNOWN.")
+                .conflicts_with_all(["INPUT_FILES", "SNAPSHOT_FROM_STDIN"])
+                .display_order(11)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("IGNORE_UNKNOWN")
+                .long("ignore-unknown")
+                .requires("DATASETS_FROM")
+                .help("when reading datasets from a file, warn about and skip any unknown or non-ZFS dataset, rather than quit.")
+                .display_order(11)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("FILES_FROM")
+                .long("files-from")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("in any non-interactive mode, read the requested paths from the file specified, such as a manifest of paths, \
+                in addition to any paths specified as arguments.  The paths are split exactly as when read from stdin, see also NULL_IN.  \
+                When the file specified is \"-\", httm reads the paths from stdin.")
+                .conflicts_with_all(["SNAPSHOT_FROM_STDIN", "DATASETS_FROM", "BROWSE", "SELECT", "RESTORE"])
+                .display_order(11)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("NULL_IN")
+                .long("null-in")
+                .aliases(["zero-in", "files0-from-stdin"])
+                .help("when reading paths from stdin, or from FILES_FROM, split the input strictly upon NULL characters, and never upon newlines, quotes, or whitespace, \
+                such as: \"find . -print0 | httm --null-in\".  Useful for paths which may contain newlines, quotes, or spaces.  \
+                Paths need not be valid UTF-8.")
+                .display_order(11)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("STDIN_EXACT")
+                .long("stdin-exact")
+                .help("treat the whole of the input read from stdin, less one trailing newline, as a single path, \
+                and never split the input upon newlines, quotes, or whitespace, such as: \"echo -n \"/my dir/file\" | httm --stdin-exact\".  \
+                As such, only one path may be read from stdin.  A file of paths, read via FILES_FROM, is always split as usual.  \
+                See also NULL_IN, to read multiple paths which may contain whitespace.")
+                .conflicts_with("NULL_IN")
+                .display_order(11)
+                .action(ArgAction::SetTrue)
+        )
         .arg(
             Arg::new("LIST_SNAPS")
                 .long("list-snaps")
@@ -306,6 +719,63 @@ fn parse_args() -> ArgMatches {
                 .display_order(12)
                 .action(ArgAction::Append)
         )
+        .arg(
+            Arg::new("COMMON_SNAP")
+                .long("common-snap")
+                .aliases(["common-snapshot", "latest-common-snap"])
+                .requires("ALT_REPLICATED")
+                .help("display the name of the newest snapshot which exists upon both the most proximate dataset, \
+                and every locally replicated dataset, of each path, such as to find a safe common point before a send/receive resync.  \
+                When no such snapshot exists, httm will say so.  Note: This is a ZFS only option.")
+                .conflicts_with_all(["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "SNAPSHOT", "LIST_SNAPS", "PRUNE", "NUM_VERSIONS", "STALE_THAN", "STAT", "LAST_SNAP", "COUNT_ONLY", "FILE_MOUNT", "ROLL_FORWARD"])
+                .display_order(12)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("DATASET_SNAPS")
+                .long("dataset-snaps")
+                .aliases(["all-snaps"])
+                .requires("LIST_SNAPS")
+                .conflicts_with_all(["JSON", "SELECT", "PRUNE"])
+                .help("in list snapshots mode, display the bare names of all the snapshots of the dataset/s backing each path, \
+                whether or not a snapshot contains a version of the path, such as for building shell completions.  \
+                Names are sorted, deduplicated, and delimited by a NEWLINE character, or a NULL character, when ZEROS is specified.  \
+                Any pattern/s specified at LIST_SNAPS also filter these names.  Note: This is a ZFS only option.")
+                .display_order(12)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("COMPARE_DATASETS")
+                .long("compare-datasets")
+                .aliases(["compare", "compare-roots"])
+                .value_names(["FIRST", "SECOND"])
+                .value_parser(clap::value_parser!(PathBuf))
+                .num_args(2)
+                .help("compare two directory trees, such as two dataset roots, or a dataset and its replica, or two snapshots, and display those paths \
+                which differ between the two, relative to each root.  \"added\" paths exist only in the second tree, \"removed\" paths exist only in the first tree, \
+                and \"changed\" paths exist in both, but their metadata (size and modify time) differ, or, when \"--dedup-by=contents\" is specified, their contents differ.  \
+                In raw modes, each path is preceded by its category and a tab.  Useful for validating 'zfs send' replication.")
+                .conflicts_with_all(["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "SNAPSHOT", "ROLL_FORWARD", "PRUNE", "INPUT_FILES"])
+                .display_order(30)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("MOUNT_SNAP")
+                .long("mount-snap")
+                .aliases(["mount-snapshot"])
+                .value_names(["SNAPSHOT", "TARGET"])
+                .value_parser(clap::value_parser!(String))
+                .num_args(2)
+                .help("mount a snapshot, read-only, upon the target directory specified, which must exist and be empty, \
+                such as to expose the full tree of a snapshot, rather than recovering individual files.  \
+                A ZFS snapshot is specified by its full snapshot name, such as \"--mount-snap rpool/home@snap_name /mnt/recover\", \
+                and is mounted via 'mount -t zfs'.  A btrfs snapshot is specified by its dataset's mount, such as \"--mount-snap /home@snap_name /mnt/recover\", \
+                and is bind mounted.  The snapshot must be among those httm has found for the dataset.  \
+                Note: This is a ZFS and btrfs only option which requires super user privileges.")
+                .conflicts_with_all(["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "SNAPSHOT", "ROLL_FORWARD", "PRUNE", "COMPARE_DATASETS", "INPUT_FILES"])
+                .display_order(30)
+                .action(ArgAction::Set)
+        )
         .arg(
             Arg::new("ROLL_FORWARD")
                 .long("roll-forward")
@@ -315,6 +785,7 @@ fn parse_args() -> ArgMatches {
                 .require_equals(true)
                 .help("traditionally 'zfs rollback' is a destructive operation, whereas httm roll-forward is non-destructive. \
                 httm will copy only files and their attributes that have changed since a specified snapshot, from that snapshot, to its live dataset. \
+                The snapshot is specified by its full ZFS snapshot name, such as \"--roll-forward=rpool/home@snap_name\", and must exist upon that mounted dataset. \
                 httm will also take two precautionary snapshots, one before and one after the copy. \
                 Should the roll forward fail for any reason, httm will roll back to the pre-execution state. \
                 Caveats: This is a ZFS only option which requires super user privileges.  \
@@ -334,11 +805,22 @@ fn parse_args() -> ArgMatches {
                 Careless use may cause you to lose snapshot data you care about. \
                 This argument requires and will be filtered according to any values specified at LIST_SNAPS. \
                 User may also enable SELECT mode to make a granular selection of specific snapshots to prune. \
+                By default, httm only lists the snapshots which would be destroyed, grouped by dataset, and the space each would reclaim. \
+                User must also specify CONFIRM to actually destroy any snapshot. \
                 Note: This is a ZFS only option.")
                 .conflicts_with_all(&["BROWSE", "RESTORE", "ALT_REPLICATED", "REMOTE_DIR", "LOCAL_DIR"])                
                 .display_order(13)
                 .action(ArgAction::SetTrue)
         )
+        .arg(
+            Arg::new("CONFIRM")
+                .long("confirm")
+                .requires("PRUNE")
+                .help("in prune mode, actually destroy the snapshots listed, after httm asks for the user's consent. \
+                Without this flag, prune mode only lists the snapshots which would be destroyed.")
+                .display_order(13)
+                .action(ArgAction::SetTrue)
+        )
         .arg(
             Arg::new("FILE_MOUNT")
                 .short('m')
@@ -358,6 +840,16 @@ fn parse_args() -> ArgMatches {
                 .display_order(14)
                 .action(ArgAction::Append)
         )
+        .arg(
+            Arg::new("WITH_USAGE")
+                .long("with-usage")
+                .requires("FILE_MOUNT")
+                .help("when displaying the mounts of the input files, also display the space used by each ZFS dataset, \
+                and the portion of that space used by its snapshots, as reported by 'zfs list -o used,usedbysnapshots'. \
+                Useful to judge whether pruning the snapshots of a given path will free meaningful space.")
+                .display_order(14)
+                .action(ArgAction::SetTrue)
+        )
         .arg(
             Arg::new("LAST_SNAP")
                 .short('l')
@@ -393,12 +885,21 @@ fn parse_args() -> ArgMatches {
                 .short('0')
                 .long("zero")
                 .visible_alias("null")
-                .help("display the snapshot locations only, without extraneous information, delimited by a NULL character.")
+                .help("display the snapshot locations only, without extraneous information, delimited by a NULL character.  \
+                When displaying mounts, snapshot names, or last snapshots, each value is preceded by its file, as NULL delimited pairs: file, then value.")
                 .conflicts_with_all(&["RAW", "NOT_SO_PRETTY"])
                 
                 .display_order(17)
                 .action(ArgAction::SetTrue)
         )
+        .arg(
+            Arg::new("INCLUDE_LIVE")
+                .long("include-live")
+                .help("in the RAW or ZEROS display modes, also display the live version's location, after the snapshot locations.")
+                .conflicts_with_all(["NO_LIVE", "NO_SNAP"])
+                .display_order(17)
+                .action(ArgAction::SetTrue)
+        )
         .arg(
             Arg::new("NOT_SO_PRETTY")
                 .long("not-so-pretty")
@@ -408,13 +909,120 @@ fn parse_args() -> ArgMatches {
                 .display_order(18)
                 .action(ArgAction::SetTrue)
         )
+        .arg(
+            Arg::new("FORMAT")
+                .long("format")
+                .require_equals(true)
+                .value_parser(["auto", "pretty", "not-so-pretty", "raw", "zero"])
+                .help("choose the display format.  \"pretty\" is the default and is the ordinary output, \"not-so-pretty\", \"raw\" and \"zero\" \
+                are equivalent to the NOT_SO_PRETTY, RAW and ZEROS flags.  \"auto\" displays the ordinary output when stdout is a terminal, \
+                and, like `ls`, displays the RAW output, paths delimited by a NEWLINE character, when stdout is piped or redirected.  \
+                NOTE: \"auto\" is never the default, so existing scripts which parse the ordinary output are unaffected.  \
+                The NOT_SO_PRETTY, RAW and ZEROS flags, when specified, always take precedence over this option.")
+                .display_order(18)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("SIZE_FORMAT")
+                .long("size-format")
+                .require_equals(true)
+                .value_parser(["binary", "decimal", "si", "raw", "bytes"])
+                .help("choose how file sizes are displayed.  \"binary\" is the default, and displays sizes with binary prefixes, like \"1.0 MiB\".  \
+                \"decimal\" or \"si\" displays sizes with decimal (SI) prefixes, like \"1.0 MB\", as in 'ls -l --si'.  \
+                \"raw\" or \"bytes\" displays the exact byte count without any suffix, so sizes may be summed by scripts.")
+                .display_order(18)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("BYTES")
+                .long("bytes")
+                .aliases(["no-human-readable"])
+                .help("display the exact byte count of each version, right aligned in the size column, in every display mode, \
+                including the ordinary pretty display, such as for import into a spreadsheet.  Equivalent to \"--size-format=raw\".")
+                .conflicts_with("SIZE_FORMAT")
+                .display_order(18)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("COLOR")
+                .long("color")
+                .alias("colour")
+                .require_equals(true)
+                .num_args(0..=1)
+                .default_missing_value("always")
+                .value_parser(["auto", "always", "never"])
+                .help("choose when file names are colorized, according to LS_COLORS, and when deleted files are dimmed.  \
+                \"auto\" is the default, and colorizes only in the interactive modes, or when stdout is a terminal, \
+                unless the NO_COLOR environment variable is set, or CLICOLOR is set to \"0\".  \
+                A CLICOLOR_FORCE environment variable, set to other than \"0\", will force colorization in \"auto\" mode.  \
+                The flag without a value is the same as \"always\".")
+                .display_order(18)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("SORT")
+                .long("sort")
+                .require_equals(true)
+                .value_parser(["date", "size", "path"])
+                .help("choose the order in which snapshot versions are displayed.  \"date\" is the default, and displays versions oldest first, by modify time.  \
+                \"size\" displays versions largest first, and \"path\" displays versions sorted by their snapshot paths.  \
+                Versions of equal size are displayed in date order.")
+                .display_order(18)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("REVERSE")
+                .long("reverse")
+                .help("reverse the order in which snapshot versions are displayed, whichever order is chosen via SORT.")
+                .display_order(18)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("SUMMARY")
+                .long("summary")
+                .help("in the ordinary and not-so-pretty display modes, append a summary line for each live file, \
+                displaying the number of unique snapshot versions found, and the total size of those versions. \
+                When more than one file is requested, also append a grand total across all files.")
+                .display_order(18)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("HUMANIZE_PATHS")
+                .long("humanize-paths")
+                .help("in the ordinary and not-so-pretty display modes, abbreviate paths in the user's home directory with a \"~\". \
+                This is purely cosmetic. Lookups are always performed upon the full path.")
+                .display_order(18)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("STRIP_PREFIX")
+                .long("strip-prefix")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("in the ordinary and not-so-pretty display modes, display paths relative to the directory specified, \
+                for instance \"--strip-prefix=/srv/backups\".  Paths which are not found under the directory specified are displayed in full. \
+                This is purely cosmetic. Lookups are always performed upon the full path.")
+                .display_order(18)
+        )
         .arg(
             Arg::new("JSON")
                 .long("json")
-                .help("display the ordinary output, but as formatted JSON.")
+                .require_equals(true)
+                .num_args(0..=1)
+                .default_missing_value("json")
+                .value_parser(["json", "pretty", "compact", "ndjson"])
+                .help("display the ordinary output, but as formatted JSON.  \
+                The JSON object includes a \"schema_version\" integer, which changes whenever the structure of the output changes, \
+                beside the values displayed, such as \"{\"schema_version\":3,\"versions\":{...}}\".  \
+                Each entry includes an \"is_phantom\" boolean, which is true when the live file has been deleted, so that consumers may filter deleted files.  \
+                By default, the JSON is pretty printed when stdout is a terminal, and compact when piped, whatever the display mode, \
+                but the values \"pretty\" and \"compact\" request either explicitly.  \
+                The value \"ndjson\" instead displays newline delimited JSON, one self-contained JSON object per live file, per line, \
+                such as \"{\"path\":\"/home/user/.zshrc\",\"is_phantom\":false,\"versions\":[...]}\", which may be more suitable for streaming consumers, like 'jq --stream' or log pipelines.  \
+                The name of the values depends upon the mode: \"versions\", \"mounts\", \"snapshot_names\", \"last_snap\", \"common_snap\", \"age_secs\", or \"stats\".")
                 .conflicts_with_all(&["SELECT", "RESTORE"])
                 .display_order(19)
-                .action(ArgAction::SetTrue)
+                .action(ArgAction::Set)
         )
         .arg(
             Arg::new("OMIT_DITTO")
@@ -424,6 +1032,68 @@ fn parse_args() -> ArgMatches {
                 .display_order(20)
                 .action(ArgAction::SetTrue)
         )
+        .arg(
+            Arg::new("PRUNE_EMPTY")
+                .long("prune-empty")
+                .aliases(["only-with-snaps", "hide-empty"])
+                .help("omit display of those files which have no snapshot versions, such as when running over a large tree, \
+                and only the files with snapshot history are of interest.  Unlike NO_LIVE, the live versions of the remaining files are still displayed.  \
+                Files are omitted after any OMIT_DITTO or LAST_SNAP filtering, so, for instance, a file whose only snapshot version is identical to the live version \
+                is omitted when OMIT_DITTO is also specified.")
+                .conflicts_with_all(["NO_SNAP", "BROWSE", "SELECT", "RESTORE"])
+                .display_order(20)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("SHOW_SOURCE")
+                .long("show-source")
+                .aliases(["provenance"])
+                .requires("ALT_REPLICATED")
+                .help("when searching locally replicated datasets, append a tag to each snapshot version, \
+                \"[local]\" when found upon the most proximate dataset, or \"[alt:<pool>]\" when found upon a replicated dataset.  \
+                Only the formatted display modes are affected.")
+                .display_order(20)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("WITH_FSTYPE")
+                .long("with-fstype")
+                .requires("NOT_SO_PRETTY")
+                .help("in the not-so-pretty display mode, append the filesystem type of the dataset which yielded each version, such as \"zfs\" or \"btrfs\", \
+                as a final tab delimited field.")
+                .display_order(20)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("DEDUP_GLOBAL")
+                .long("dedup-global")
+                .help("deduplicate snapshot versions across all the input files, such that a version which shares the same size and modify time \
+                with a version of another input file, such as a hard link or an identical copy, is listed only once, under the first input file (in sorted order) to list it.  \
+                Each version so listed is displayed with a note of those other input files with which it is shared, and, in JSON, with a \"shared_with\" field.")
+                .display_order(20)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("NO_DEDUP_INPUT")
+                .long("no-dedup-input")
+                .help("by default, versions are displayed in the order in which the input paths were requested, and a path requested more than once, \
+                or any symlink requested alongside its target, is looked up, and displayed, only once, at its first position.  \
+                This option disables the deduplication of a symlink and its target, for those who genuinely want repeats, so that each is displayed, at its own position.  \
+                A path requested more than once, by the same name, is still displayed only once.")
+                .display_order(20)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("OMIT_IDENTICAL_ACROSS_DATASETS")
+                .long("omit-identical-across-datasets")
+                .aliases(["omit-replicas", "omit-identical"])
+                .requires("ALT_REPLICATED")
+                .help("when searching locally replicated datasets, collapse those snapshot versions which share the same size and modify time, \
+                whichever dataset they were found upon, such that exact replicas on a backup pool are displayed only once.  \
+                The version found upon the most proximate dataset is preferred, and is displayed with a note of those other datasets which also held the versions collapsed, and, in JSON, with an \"also_found_on\" field.")
+                .display_order(20)
+                .action(ArgAction::SetTrue)
+        )
         .arg(
             Arg::new("NO_FILTER")
                 .long("no-filter")
@@ -437,7 +1107,8 @@ fn parse_args() -> ArgMatches {
             Arg::new("FILTER_HIDDEN")
                 .long("no-hidden")
                 .aliases(&["no-hide", "nohide", "filter-hidden"])
-                .help("do not show information regarding hidden files and directories (those that start with a \'.\') in the recursive or interactive modes.")
+                .help("do not show information regarding hidden files and directories (those that start with a \'.\') in the recursive or interactive modes. \
+                In the interactive browse view, hidden files may also be toggled with \"alt-h\".")
                 
                 .display_order(22)
                 .action(ArgAction::SetTrue)
@@ -445,12 +1116,67 @@ fn parse_args() -> ArgMatches {
         .arg(
             Arg::new("ONE_FILESYSTEM")
                 .long("one-filesystem")
-                .aliases(&["same-filesystem", "single-filesystem", "one-fs", "onefs"])
+                .aliases(&["one-file-system", "same-filesystem", "single-filesystem", "one-fs", "onefs", "xdev"])
                 .requires("RECURSIVE")
-                .help("limit recursive search to file and directories on the same filesystem/device as the target directory.")
+                .help("limit recursive search to file and directories on the same filesystem/device as the target directory.  \
+                like find's -xdev, recursive search will not descend into directories which are mount points for other filesystems, such as bind mounts.")
                 .display_order(23)
                 .action(ArgAction::SetTrue)
         )
+        .arg(
+            Arg::new("EXT")
+                .long("ext")
+                .requires("RECURSIVE")
+                .value_parser(clap::value_parser!(String))
+                .help("in recursive mode, only show files with the given extension, such as \"conf\" or \".rs\". \
+                Directories are always shown so that the recursive search may continue. May be specified multiple times. \
+                Matching is case insensitive, unless \"--ext-strict\" is also specified.")
+                .display_order(23)
+                .action(ArgAction::Append)
+        )
+        .arg(
+            Arg::new("EXT_STRICT")
+                .long("ext-strict")
+                .requires("EXT")
+                .help("match the extensions given with \"--ext\" case sensitively.")
+                .display_order(23)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("MATCH_REGEX")
+                .long("match-regex")
+                .aliases(["regex", "match"])
+                .value_parser(clap::value_parser!(String))
+                .help("display only those versions whose live file path matches the regular expression specified, such as \"config\", \
+                or \"\\.(toml|yaml)$\".  Regular expression syntax is that of the Rust 'regex' crate.  Most useful in combination with \"--recursive\".")
+                .conflicts_with_all(["BROWSE", "SELECT", "RESTORE"])
+                .display_order(23)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("FOLLOW_SYMLINKS")
+                .long("follow-symlinks")
+                .require_equals(true)
+                .value_parser(["never", "dirs", "always"])
+                .help("choose when httm treats a symlink to a directory as a directory.  \"dirs\" is the default, and follows a symlink to a directory, \
+                unless the link and its target share a common path, as httm's bespoke algorithm prevents traversing pathologically recursive symlinks.  \
+                \"never\" treats every symlink strictly as a file, so no symlink is ever followed, which may be useful to avoid traversing into large target directories or network mounts.  \
+                \"always\" follows every symlink to a directory, except a symlink to one of its own ancestors, which would recurse infinitely.")
+                .display_order(23)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("EXCLUDE")
+                .long("exclude")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(String))
+                .help("in recursive mode, exclude any file or directory which matches the pattern specified, such as \"node_modules\" or \"*.tmp\". \
+                An excluded directory is never descended into.  A pattern is a glob, where \"*\" and \"?\" never match a path separator, but \"**\" matches anything, \
+                and is matched against both the file name and the path relative to the directory requested.  \
+                A pattern prefixed with \"regex:\" is instead a regular expression, such as \"regex:^\\.cache$\".  May be specified multiple times.")
+                .display_order(23)
+                .action(ArgAction::Append)
+        )
         .arg(
             Arg::new("NO_TRAVERSE")
                 .long("no-traverse")
@@ -495,12 +1221,12 @@ fn parse_args() -> ArgMatches {
         .arg(
             Arg::new("MAP_ALIASES")
                 .long("map-aliases")
-                .visible_aliases(&["aliases"])
+                .visible_aliases(["aliases", "map-alias"])
                 .help("manually map a local directory (eg. \"/Users/<User Name>\") as an alias of a mount point for ZFS or btrfs, \
                 such as the local mount point for a backup on a remote share (eg. \"/Volumes/Home\"). \
                 This option is useful if you wish to view snapshot versions from within the local directory you back up to a remote network share. \
                 This option requires a value. Such a value is delimited by a colon, ':', and is specified in the form <LOCAL_DIR>:<REMOTE_DIR> \
-                (eg. --map-aliases /Users/<User Name>:/Volumes/Home). Multiple maps may be specified delimited by a comma, ','. \
+                (eg. --map-aliases /Users/<User Name>:/Volumes/Home). Multiple maps may be specified delimited by a comma, ',', or by repeating the option. \
                 You may also set via the environment variable HTTM_MAP_ALIASES.")
                 .use_value_delimiter(true)
                 .value_parser(clap::builder::ValueParser::os_string())
@@ -508,6 +1234,19 @@ fn parse_args() -> ArgMatches {
                 .display_order(28)
                 .action(ArgAction::Append)
         )
+        .arg(
+            Arg::new("SNAP_DIR_NAME")
+                .long("snap-dir-name")
+                .aliases(["snapdir", "snap-dir"])
+                .require_equals(true)
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("search for snapshots within the directory specified, relative to each btrfs dataset's mount, such as \"--snap-dir-name=.snaps\", \
+                instead of the compiled in default, \".snapshots\", for custom snapshot mounts, or btrfs layouts which don't follow the Snapper convention.  \
+                Each entry of the directory specified should be a snapshot of the dataset.  Datasets upon which the directory specified does not exist are searched as usual.  \
+                ZFS datasets are always searched at \".zfs/snapshot\", and never at the directory specified.  Note: This is a btrfs only option.")
+                .display_order(28)
+                .action(ArgAction::Set)
+        )
         .arg(
             Arg::new("NUM_VERSIONS")
                 .long("num-versions")
@@ -526,6 +1265,110 @@ fn parse_args() -> ArgMatches {
                 .display_order(29)
                 .action(ArgAction::Append)
         )
+        .arg(
+            Arg::new("COUNT_ONLY")
+                .long("count-only")
+                .help("display only the number of unique snapshot versions found for each input file, and the path of that file, tab delimited, \
+                such as: \"3\t/home/user/file\".  Each line is delimited by a NEWLINE character, or a NULL character, when ZEROS is specified.  \
+                Lighter than NUM_VERSIONS, which also counts the live version, and intended for scripts, such as for monitoring snapshot retention.")
+                .conflicts_with_all(["NUM_VERSIONS", "STALE_THAN", "STAT", "JSON", "LAST_SNAP", "BROWSE", "SELECT", "RESTORE", "RECURSIVE", "SNAPSHOT", "NO_LIVE", "NO_SNAP", "OMIT_DITTO"])
+                .display_order(29)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("STALE_THAN")
+                .long("stale-than")
+                .value_parser(clap::value_parser!(String))
+                .help("display only those files whose most recent snapshot version is older than the duration specified, \
+                and the age of that most recent snapshot version, sorted oldest first. Such files likely haven't changed in a long while. \
+                A duration is a number followed by a unit, such as \"90d\", where units may be \"s\", \"m\", \"h\", \"d\", \"w\", or \"y\". \
+                Files without any snapshot versions, and deleted files, are not displayed.")
+                .conflicts_with_all(["NUM_VERSIONS", "STAT", "LAST_SNAP", "BROWSE", "SELECT", "RESTORE", "RECURSIVE", "SNAPSHOT", "NO_LIVE", "NO_SNAP", "OMIT_DITTO"])
+                .display_order(29)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("STAT")
+                .long("stat")
+                .require_equals(true)
+                .num_args(0..=1)
+                .default_missing_value("")
+                .value_parser(clap::value_parser!(String))
+                .help("display, for each input file, the number of unique snapshot versions, and the minimum, median, and maximum intervals \
+                between the modify times of consecutive snapshot versions, useful for auditing whether a snapshot schedule ran as expected. \
+                Intervals greater than the duration specified, such as \"--stat=2d\", are displayed as gaps in coverage. \
+                When no duration is specified, intervals greater than twice the median interval are displayed as gaps. \
+                A duration is a number followed by a unit, where units may be \"s\", \"m\", \"h\", \"d\", \"w\", or \"y\". \
+                In RAW or ZEROS mode, each file is displayed on one tab delimited line: the path, the number of versions, \
+                the minimum, median, and maximum intervals in seconds, and the number of gaps.")
+                .conflicts_with_all(["NUM_VERSIONS", "STALE_THAN", "LAST_SNAP", "BROWSE", "SELECT", "RESTORE", "RECURSIVE", "SNAPSHOT", "NO_LIVE", "NO_SNAP", "OMIT_DITTO"])
+                .display_order(29)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("FOLLOW")
+                .long("follow")
+                .aliases(["watch"])
+                .help("display the ordinary output, then watch the input files, and display their updated versions whenever a live file changes, \
+                clearing the screen between runs, until Ctrl-C is pressed.  Note: Changes are detected via inotify, \
+                which is only available on Linux, elsewhere httm will fall back to re-running the lookup on a timer, see INTERVAL.")
+                .conflicts_with_all(["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "SNAPSHOT", "LIST_SNAPS", "PRUNE", "COMMON_SNAP", "COMPARE_DATASETS", "FILE_MOUNT", "ROLL_FORWARD"])
+                .display_order(29)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("INTERVAL")
+                .long("interval")
+                .require_equals(true)
+                .requires("FOLLOW")
+                .value_parser(clap::value_parser!(String))
+                .help("in follow mode, instead of watching for changes, simply re-run the lookup once per the duration specified, such as \"--interval=30s\", \
+                for filesystems without inotify support.  Unlike watching the live files, this will also catch newly created snapshots. \
+                A duration is a number followed by a unit, where units may be \"s\", \"m\", \"h\", \"d\", \"w\", or \"y\".")
+                .display_order(29)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("NEWER_THAN")
+                .long("newer-than")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(String))
+                .help("display only those snapshot versions modified after the time specified.  The time may be a duration before now, \
+                such as \"7d\" or \"24h\", where units may be \"s\", \"m\", \"h\", \"d\", \"w\", or \"y\", or a date, such as \"2024-01-31\" or \"2024-01-31 12:00:00\", \
+                in the same offset as dates are displayed.")
+                .display_order(29)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("OLDER_THAN")
+                .long("older-than")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(String))
+                .help("display only those snapshot versions modified before the time specified.  The time may be a duration before now, \
+                such as \"7d\" or \"24h\", or a date, such as \"2024-01-31\" or \"2024-01-31 12:00:00\", in the same offset as dates are displayed.")
+                .display_order(29)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("MIN_SIZE")
+                .long("min-size")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(String))
+                .help("display only those snapshot versions at least as large as the size specified, such as when hunting for which snapshot version of a file ballooned.  \
+                A size is a number followed by an optional unit, such as \"10M\" or \"1.5GiB\", where a unit with an \"i\" is binary, and one without is decimal.  \
+                A number without a unit is a number of bytes.")
+                .display_order(29)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("MAX_SIZE")
+                .long("max-size")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(String))
+                .help("display only those snapshot versions at most as large as the size specified, such as \"10M\" or \"1.5GiB\".")
+                .display_order(29)
+                .action(ArgAction::Set)
+        )
         .arg(
             Arg::new("REMOTE_DIR")
                 .long("remote-dir")
@@ -544,6 +1387,7 @@ fn parse_args() -> ArgMatches {
                 .visible_alias("local")
                 .help("DEPRECATED. Use MAP_ALIASES. Used with \"remote-dir\" to determine where the corresponding live root filesystem of the dataset is. \
                 Put more simply, the \"local-dir\" is likely the directory you backup to your \"remote-dir\". If not set, httm defaults to your current working directory. \
+                Both may be repeated, and the nth \"local-dir\" is paired with the nth \"remote-dir\". \
                 You may also set via the environment variable HTTM_LOCAL_DIR.")
                 .requires("REMOTE_DIR")
                 .value_parser(clap::builder::ValueParser::os_string())
@@ -557,6 +1401,28 @@ fn parse_args() -> ArgMatches {
                 .display_order(32)
                 .action(ArgAction::SetTrue)
         )
+        .arg(
+            Arg::new("DATE_FORMAT")
+                .long("date-format")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(String))
+                .help("use a custom format for date display, specified as a 'time' crate format description, \
+                such as \"[year]-[month]-[day] [hour]:[minute]:[second]\".  The values \"iso8601\" and \"rfc3339\" are shorthand for those formats.  \
+                A custom format should include any offset it wishes to display, as httm will not append \"UTC\" to a custom format.  \
+                If the format cannot be parsed, httm will warn and use the default format.  Snapshot and backup name timestamps are never affected.")
+                .display_order(32)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("RELATIVE_DATES")
+                .long("relative-dates")
+                .visible_alias("relative-time")
+                .help("display each version's modify time relative to now, such as \"3 days ago\", instead of as an absolute date.  \
+                Snapshot and backup name timestamps are never affected.")
+                .conflicts_with("DATE_FORMAT")
+                .display_order(32)
+                .action(ArgAction::SetTrue)
+        )
         .arg(
             Arg::new("NO_CLONES")
                 .long("no-clones")
@@ -566,6 +1432,65 @@ fn parse_args() -> ArgMatches {
                 .display_order(33)
                 .action(ArgAction::SetTrue)
         )
+        .arg(
+            Arg::new("PRESERVE_ACLS")
+                .long("preserve-acls")
+                .value_parser(["true", "false"])
+                .num_args(0..=1)
+                .default_missing_value("true")
+                .require_equals(true)
+                .help("when preserving attributes, such as during a restore in \"overwrite\", \"guard\" or \"copy-and-preserve\" modes, \
+                httm will copy POSIX ACLs, if httm was built with the \"acls\" feature. Here, you may specify \"false\" to skip copying ACLs, \
+                for instance, when the destination filesystem does not support ACLs. Default value is \"true\".")
+                .display_order(33)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("PRESERVE")
+                .long("preserve")
+                .value_parser(clap::value_parser!(String))
+                .require_equals(true)
+                .help("when preserving attributes, such as during a restore in \"overwrite\", \"guard\" or \"copy-and-preserve\" modes, \
+                copy only the attributes specified, as a comma delimited list of any of: \"mode\", \"owner\", \"xattr\", \"acl\", and \"time\", \
+                such as \"--preserve=mode,time\", when restoring across users.  By default, all attributes are copied. \
+                When httm lacks the privileges to copy ownership, httm will warn, rather than quit.")
+                .conflicts_with("ROLL_FORWARD")
+                .display_order(33)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("PARALLEL_FILES")
+                .long("parallel-files")
+                .value_parser(clap::value_parser!(usize))
+                .help("limit the number of input files for which httm will look up versions concurrently. \
+                By default, httm looks up versions for all input files concurrently, using as many threads as are available. \
+                Lookups for each individual file, across its datasets, will still proceed concurrently. \
+                A lower value may help when many input files are specified, and such lookups are IO bound.")
+                .display_order(33)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("THREADS")
+                .long("threads")
+                .value_parser(clap::value_parser!(usize))
+                .help("limit the number of threads httm uses for its concurrent lookups and directory enumeration to the value specified, \
+                rather than using as many threads as are available.  A value of 1 makes execution effectively serial, such as for debugging. \
+                In recursive mode, the search for deleted files shares the same limit.")
+                .display_order(33)
+                .action(ArgAction::Set)
+        )
+        .arg(
+            Arg::new("NETWORK_TIMEOUT")
+                .long("network-timeout")
+                .value_parser(clap::value_parser!(String))
+                .help("for datasets mounted over the network, such as NFS or SMB shares, limit the time httm will wait upon the metadata of \
+                any single snapshot version to the duration specified, such as \"5s\".  A snapshot version whose metadata is not available in time, \
+                perhaps because the mount is stale, is treated as unavailable. Once a snapshot version of a dataset has timed out, \
+                the remaining versions of that dataset are also treated as unavailable. Local datasets are never subject to this timeout. \
+                A duration is a number followed by a unit, such as \"s\", \"m\", or \"h\".")
+                .display_order(33)
+                .action(ArgAction::Set)
+        )
         .arg(
             Arg::new("DEBUG")
                 .long("debug")
@@ -581,7 +1506,27 @@ fn parse_args() -> ArgMatches {
                 .display_order(35)
                 .action(ArgAction::SetTrue)
         )
-        .get_matches()
+        .arg(
+            Arg::new("JSON_SCHEMA")
+                .long("json-schema")
+                .help("print a JSON Schema describing the structure of httm's JSON output, and then exit.")
+                .exclusive(true)
+                .display_order(36)
+                .action(ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("CONFIG")
+                .long("config")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("read default flags from the config file specified, instead of the default config file at \"$XDG_CONFIG_HOME/httm/config.toml\", \
+                or \"~/.config/httm/config.toml\" when XDG_CONFIG_HOME is not set.  A config file with the extension \".json\" is read as a JSON object.  \
+                Each key is the long name of a flag, and each value is either a boolean, such as \"recursive = true\", or the value of the flag, such as \"deleted = \\\"all\\\"\".  \
+                Flags requested on the command line override those in the config file, and any config file value which conflicts with the flags requested is ignored with a warning.  \
+                Should the combined flags still be invalid, the whole config file is ignored with a warning.  \
+                The default config file is optional, and, when absent, changes nothing.")
+                .display_order(36)
+                .action(ArgAction::Set)
+        )
 }
 
 #[derive(Debug, Clone)]
@@ -593,17 +1538,71 @@ pub struct Config {
     pub opt_debug: bool,
     pub opt_no_traverse: bool,
     pub opt_omit_ditto: bool,
+    pub opt_prune_empty: bool,
+    pub opt_omit_identical_across_datasets: bool,
+    pub opt_dedup_global: bool,
+    pub opt_no_dedup_input: bool,
+    pub opt_dataset_snaps: bool,
+    pub opt_common_snap: bool,
     pub opt_no_hidden: bool,
     pub opt_json: bool,
+    pub opt_ndjson: bool,
+    pub json_format: JsonFormat,
+    pub opt_count_only: bool,
+    pub opt_include_live: bool,
+    pub opt_with_versions: bool,
+    pub opt_humanize_paths: bool,
+    pub opt_strip_prefix: Option<PathBuf>,
     pub opt_one_filesystem: bool,
+    pub opt_max_depth: Option<usize>,
+    pub opt_numbered_versions: Option<MapOfNumberedVersions>,
+    pub opt_with_usage: bool,
+    pub opt_ext_strict: bool,
+    pub opt_match_regex: Option<Regex>,
+    pub opt_exclude: Option<Vec<Regex>>,
     pub opt_no_clones: bool,
+    pub preserve_attrs: PreserveAttrs,
     pub dedup_by: DedupBy,
+    pub size_format: SizeFormat,
+    pub color_mode: ColorMode,
+    pub sort_by: SortBy,
+    pub opt_reverse: bool,
+    pub opt_summary: bool,
     pub opt_bulk_exclusion: Option<BulkExclusion>,
     pub opt_last_snap: Option<LastSnapMode>,
     pub opt_preview: Option<String>,
+    pub opt_snap_labels: Option<Vec<String>>,
+    pub opt_snap_name_template: Option<SnapNameTemplate>,
+    pub opt_snapshot_if_changed: bool,
+    pub opt_datasets_from: Option<PathBuf>,
+    pub opt_ignore_unknown: bool,
+    pub opt_snapshot_output: Option<SnapshotOutput>,
+    pub opt_parallel_files: Option<usize>,
+    pub opt_threads: Option<usize>,
+    pub opt_network_timeout: Option<Duration>,
+    pub opt_follow: Option<FollowMode>,
+    pub opt_from_snapshot: Option<String>,
+    pub opt_backup_existing: bool,
+    pub opt_restore_to: Option<PathBuf>,
+    pub opt_max_restore_size: Option<u64>,
+    pub opt_verify: bool,
+    pub opt_dry_run: bool,
+    pub opt_confirm: bool,
+    pub opt_merge: bool,
+    pub opt_no_clobber: bool,
+    pub opt_progress: bool,
+    pub opt_extensions: Option<Vec<String>>,
     pub opt_deleted_mode: Option<DeletedMode>,
     pub opt_requested_dir: Option<PathBuf>,
     pub requested_utc_offset: UtcOffset,
+    pub opt_date_format: Option<OwnedFormatItem>,
+    pub opt_relative_dates: bool,
+    pub opt_show_source: bool,
+    pub opt_with_fstype: bool,
+    pub opt_newer_than: Option<SystemTime>,
+    pub opt_older_than: Option<SystemTime>,
+    pub opt_min_size: Option<u64>,
+    pub opt_max_size: Option<u64>,
     pub exec_mode: ExecMode,
     pub print_mode: PrintMode,
     pub dataset_collection: FilesystemInfo,
@@ -612,7 +1611,7 @@ pub struct Config {
 
 impl Config {
     pub fn new() -> HttmResult<Self> {
-        let arg_matches = parse_args();
+        let arg_matches = parse_args()?;
         let config = Config::from_matches(&arg_matches)?;
         if config.opt_debug {
             eprintln!("{config:#?}");
@@ -621,10 +1620,36 @@ impl Config {
     }
 
     fn from_matches(matches: &ArgMatches) -> HttmResult<Self> {
+        // bound rayon's global pool before any lookup, so that generating this config,
+        // such as parsing the mounts and the input paths, is bounded too
+        let opt_threads = matches.get_one::<usize>("THREADS").copied();
+
+        if let Some(num_threads) = opt_threads {
+            if num_threads == 0 {
+                return Err(HttmError::new("THREADS must be a value greater than zero.").into());
+            }
+
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build_global()
+                .map_err(|err| HttmError::with_context("Could not initialize rayon threadpool", &err))?;
+        }
+
         if matches.get_flag("ZSH_HOT_KEYS") {
             install_hot_keys()?
         }
 
+        // httm_is_dir is used while the config is still being generated, so set this first
+        let follow_symlinks = match matches.get_one::<String>("FOLLOW_SYMLINKS").map(|inner| inner.as_str()) {
+            Some("never") => FollowSymlinks::Never,
+            Some("always") => FollowSymlinks::Always,
+            _ => FollowSymlinks::Dirs,
+        };
+
+        FOLLOW_SYMLINKS
+            .set(follow_symlinks)
+            .expect("FOLLOW_SYMLINKS should be set only once, as the config is generated only once");
+
         let requested_utc_offset = if matches.get_flag("UTC") {
             UtcOffset::UTC
         } else {
@@ -634,7 +1659,67 @@ impl Config {
             UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC)
         };
 
-        let opt_json = matches.get_flag("JSON");
+        let opt_newer_than = match matches.get_one::<String>("NEWER_THAN") {
+            Some(value) => Some(parse_time_bound(value, requested_utc_offset)?),
+            None => None,
+        };
+
+        let opt_older_than = match matches.get_one::<String>("OLDER_THAN") {
+            Some(value) => Some(parse_time_bound(value, requested_utc_offset)?),
+            None => None,
+        };
+
+        if let (Some(newer_than), Some(older_than)) = (opt_newer_than, opt_older_than) {
+            if newer_than >= older_than {
+                return Err(HttmError::new(
+                    "The time specified for NEWER_THAN must be earlier than the time specified for OLDER_THAN.",
+                )
+                .into());
+            }
+        }
+
+        let opt_min_size = match matches.get_one::<String>("MIN_SIZE") {
+            Some(value) => Some(parse_size(value)?),
+            None => None,
+        };
+
+        let opt_max_size = match matches.get_one::<String>("MAX_SIZE") {
+            Some(value) => Some(parse_size(value)?),
+            None => None,
+        };
+
+        if let (Some(min_size), Some(max_size)) = (opt_min_size, opt_max_size) {
+            if min_size > max_size {
+                return Err(HttmError::new(
+                    "The size specified for MIN_SIZE must not be larger than the size specified for MAX_SIZE.",
+                )
+                .into());
+            }
+        }
+
+        let opt_date_format = matches
+            .get_one::<String>("DATE_FORMAT")
+            .and_then(|requested_format| parse_date_format(requested_format));
+
+        let opt_relative_dates = matches.get_flag("RELATIVE_DATES");
+        let opt_show_source = matches.get_flag("SHOW_SOURCE");
+        let opt_with_fstype = matches.get_flag("WITH_FSTYPE");
+
+        let opt_json = matches.contains_id("JSON");
+        let opt_count_only = matches.get_flag("COUNT_ONLY");
+        let opt_ndjson = matches!(
+            matches.get_one::<String>("JSON").map(|inner| inner.as_str()),
+            Some("ndjson")
+        );
+        let json_format = match matches.get_one::<String>("JSON").map(|inner| inner.as_str()) {
+            Some("pretty") => JsonFormat::Pretty,
+            Some("compact") => JsonFormat::Compact,
+            // like ls, pretty for a terminal, compact when piped
+            _ if std::io::stdout().is_terminal() => JsonFormat::Pretty,
+            _ => JsonFormat::Compact,
+        };
+        let opt_humanize_paths = matches.get_flag("HUMANIZE_PATHS");
+        let opt_with_versions = matches.get_flag("WITH_VERSIONS");
 
         let mut print_mode = if matches.get_flag("ZEROS") {
             PrintMode::RawZero
@@ -643,7 +1728,14 @@ impl Config {
         } else if matches.get_flag("NOT_SO_PRETTY") {
             PrintMode::FormattedNotPretty
         } else {
-            PrintMode::FormattedDefault
+            match matches.get_one::<String>("FORMAT").map(|inner| inner.as_str()) {
+                Some("zero") => PrintMode::RawZero,
+                Some("raw") => PrintMode::RawNewline,
+                Some("not-so-pretty") => PrintMode::FormattedNotPretty,
+                // like ls, pretty for a terminal, parseable when piped
+                Some("auto") if !std::io::stdout().is_terminal() => PrintMode::RawNewline,
+                _ => PrintMode::FormattedDefault,
+            }
         };
 
         let opt_bulk_exclusion = if matches.get_flag("NO_LIVE") {
@@ -663,8 +1755,21 @@ impl Config {
             }
         }
 
+        let opt_include_live = matches.get_flag("INCLUDE_LIVE");
+
+        if opt_include_live && !matches!(print_mode, PrintMode::RawNewline | PrintMode::RawZero) {
+            return Err(HttmError::new(
+                "INCLUDE_LIVE is only available if RAW or ZEROS are specified.",
+            )
+            .into());
+        }
+
         // force a raw mode if one is not set for no_snap mode
         let opt_one_filesystem = matches.get_flag("ONE_FILESYSTEM");
+
+        let opt_max_depth = matches.get_one::<usize>("MAX_DEPTH").copied();
+
+        let opt_with_usage = matches.get_flag("WITH_USAGE");
         let opt_recursive = matches.get_flag("RECURSIVE");
 
         let opt_exact = matches.get_flag("EXACT");
@@ -673,6 +1778,65 @@ impl Config {
         let opt_no_hidden = matches.get_flag("FILTER_HIDDEN");
         let opt_no_clones =
             matches.get_flag("NO_CLONES") || std::env::var_os("HTTM_NO_CLONE").is_some();
+        let mut preserve_attrs = match matches.get_one::<String>("PRESERVE") {
+            Some(values) => PreserveAttrs::new(values)?,
+            None => PreserveAttrs::default(),
+        };
+
+        if matches!(
+            matches
+                .get_one::<String>("PRESERVE_ACLS")
+                .map(|inner| inner.as_str()),
+            Some("false")
+        ) {
+            preserve_attrs.acl = false;
+        }
+
+        let opt_ext_strict = matches.get_flag("EXT_STRICT");
+        let opt_extensions: Option<Vec<String>> = matches.get_many::<String>("EXT").map(|extensions| {
+            extensions
+                .map(|ext| ext.trim_start_matches('.'))
+                .map(|ext| {
+                    if opt_ext_strict {
+                        ext.to_owned()
+                    } else {
+                        ext.to_lowercase()
+                    }
+                })
+                .collect()
+        });
+
+        let opt_match_regex = match matches.get_one::<String>("MATCH_REGEX") {
+            Some(pattern) => match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(err) => {
+                    let msg = format!("MATCH_REGEX pattern {pattern:?} is not a valid regular expression: {err}");
+                    return Err(HttmError::new(&msg).into());
+                }
+            },
+            None => None,
+        };
+
+        let opt_exclude: Option<Vec<Regex>> = match matches.get_many::<String>("EXCLUDE") {
+            Some(patterns) => Some(
+                patterns
+                    .map(|pattern| match pattern.strip_prefix("regex:") {
+                        Some(regex_pattern) => Regex::new(regex_pattern).map_err(|err| {
+                            let msg = format!("EXCLUDE pattern {pattern:?} is not a valid regular expression: {err}");
+                            HttmError::new(&msg).into()
+                        }),
+                        None => glob_to_regex(pattern),
+                    })
+                    .collect::<HttmResult<Vec<Regex>>>()?,
+            ),
+            None => None,
+        };
+
+        let opt_parallel_files = matches.get_one::<usize>("PARALLEL_FILES").copied();
+
+        if matches!(opt_parallel_files, Some(0)) {
+            return Err(HttmError::new("PARALLEL_FILES must be a value greater than zero.").into());
+        }
 
         let opt_last_snap = match matches.get_one::<String>("LAST_SNAP").map(|inner| inner.as_str()) {
             Some("" | "any") => Some(LastSnapMode::Any),
@@ -699,6 +1863,41 @@ impl Config {
             return Err(HttmError::new("The NUM_VERSIONS graph mode and the RAW or ZEROS display modes are an invalid combination.").into());
         }
 
+        let opt_stale_than = match matches.get_one::<String>("STALE_THAN") {
+            Some(value) => Some(parse_duration(value)?),
+            None => None,
+        };
+
+        // an empty value requests the default, relative to the median interval, gap threshold
+        let opt_stat = match matches.get_one::<String>("STAT") {
+            Some(value) if value.is_empty() => Some(None),
+            Some(value) => Some(Some(parse_duration(value)?)),
+            None => None,
+        };
+
+        let opt_follow = match matches.get_one::<String>("INTERVAL") {
+            Some(value) => {
+                let interval = parse_duration(value)?;
+
+                if interval.is_zero() {
+                    return Err(HttmError::new("INTERVAL must be a duration greater than zero.").into());
+                }
+
+                Some(FollowMode::Interval(interval))
+            }
+            None if matches.get_flag("FOLLOW") => Some(FollowMode::Watch),
+            None => None,
+        };
+
+        let opt_network_timeout = match matches.get_one::<String>("NETWORK_TIMEOUT") {
+            Some(value) => Some(parse_duration(value)?),
+            None => None,
+        };
+
+        if matches!(opt_network_timeout, Some(timeout) if timeout.is_zero()) {
+            return Err(HttmError::new("NETWORK_TIMEOUT must be a duration greater than zero.").into());
+        }
+
         let opt_mount_display = match matches.get_one::<String>("FILE_MOUNT").map(|inner| inner.as_str()) {
             Some("" | "mount" | "target" | "directory") => Some(MountDisplay::Target),
             Some("source" | "device" | "dataset") => Some(MountDisplay::Source),
@@ -714,11 +1913,43 @@ impl Config {
 
         let mut opt_deleted_mode = match matches.get_one::<String>("DELETED").map(|inner| inner.as_str()) {
             Some("" | "all") => Some(DeletedMode::All),
-            Some("single") => Some(DeletedMode::DepthOfOne),
+            Some("single") => Some(DeletedMode::Depth(1)),
             Some("only") => Some(DeletedMode::Only),
             _ => None,
         };
 
+        if let Some(deleted_depth) = matches.get_one::<usize>("DELETED_DEPTH").copied() {
+            if deleted_depth == 0 {
+                return Err(HttmError::new("DELETED_DEPTH must be a value greater than zero.").into());
+            }
+
+            if matches!(opt_deleted_mode, Some(DeletedMode::Only)) {
+                return Err(HttmError::new("DELETED_DEPTH may not be combined with the \"only\" deleted mode.").into());
+            }
+
+            opt_deleted_mode = Some(DeletedMode::Depth(deleted_depth));
+        }
+
+        let opt_from_snapshot = matches.get_one::<String>("FROM_SNAPSHOT").cloned();
+
+        let opt_backup_existing = matches.get_flag("BACKUP_EXISTING");
+        let opt_verify = matches.get_flag("VERIFY");
+        let opt_dry_run = matches.get_flag("DRY_RUN");
+
+        if opt_dry_run
+            && !matches.contains_id("SNAPSHOT")
+            && !matches.contains_id("RESTORE")
+            && !matches.get_flag("PRUNE")
+            && !matches.contains_id("MOUNT_SNAP")
+        {
+            return Err(HttmError::new(
+                "DRY_RUN is only available if either SNAPSHOT, RESTORE, PRUNE, or MOUNT_SNAP is specified.",
+            )
+            .into());
+        }
+
+        let opt_confirm = matches.get_flag("CONFIRM");
+
         let opt_select_mode = matches.get_one::<String>("SELECT");
         let opt_restore_mode = matches.get_one::<String>("RESTORE");
         
@@ -742,20 +1973,61 @@ impl Config {
                 }
                 _ => Some(InteractiveMode::Restore(RestoreMode::CopyOnly)),
             }
+        } else if matches.get_flag("DIFF") {
+            Some(InteractiveMode::Select(SelectMode::Diff))
         } else if opt_select_mode.is_some() || opt_preview.is_some() {
             match opt_select_mode.map(|inner| inner.as_str()) {
                 Some("contents") => Some(InteractiveMode::Select(SelectMode::Contents)),
                 Some("preview") => Some(InteractiveMode::Select(SelectMode::Preview)),
                 Some(_) | None => Some(InteractiveMode::Select(SelectMode::Path)),
             }
-        // simply enable browse mode -- if deleted mode not enabled but recursive search is specified,
-        // that is, if delete recursive search is not specified, don't error out, let user browse
-        } else if matches.get_flag("BROWSE") || (opt_recursive && opt_deleted_mode.is_none()) {
+        } else if matches.get_flag("BROWSE") {
             Some(InteractiveMode::Browse)
         } else {
             None
         };
 
+        let opt_merge = matches.get_flag("MERGE");
+        let opt_no_clobber = matches.get_flag("NO_CLOBBER");
+        let opt_progress = matches.get_flag("PROGRESS");
+
+        let opt_max_restore_size = matches.get_one::<u64>("MAX_RESTORE_SIZE").copied();
+
+        let is_overwrite_restore = matches!(
+            opt_interactive_mode,
+            Some(InteractiveMode::Restore(RestoreMode::Overwrite(_)))
+        );
+
+        if opt_merge && !is_overwrite_restore {
+            return Err(HttmError::new(
+                "MERGE is only available in the \"overwrite\" or \"guard\" restore modes.",
+            )
+            .into());
+        }
+
+        let size_format = match matches.get_one::<String>("SIZE_FORMAT").map(|inner| inner.as_str()) {
+            Some("decimal" | "si") => SizeFormat::Decimal,
+            Some("raw" | "bytes") => SizeFormat::Raw,
+            _ if matches.get_flag("BYTES") => SizeFormat::Raw,
+            _ => SizeFormat::Binary,
+        };
+
+        let color_mode = match matches.get_one::<String>("COLOR").map(|inner| inner.as_str()) {
+            Some("always") => ColorMode::Always,
+            Some("never") => ColorMode::Never,
+            _ => ColorMode::Auto,
+        };
+
+        let sort_by = match matches.get_one::<String>("SORT").map(|inner| inner.as_str()) {
+            Some("size") => SortBy::Size,
+            Some("path") => SortBy::Path,
+            _ => SortBy::Date,
+        };
+
+        let opt_reverse = matches.get_flag("REVERSE");
+
+        let opt_summary = matches.get_flag("SUMMARY");
+
         let dedup_by = match matches.get_one::<String>("DEDUP_BY").map(|inner| inner.as_str()) {
             _ if matches.get_flag("PRUNE") =>  DedupBy::Disable,
             Some("all" | "no-filter" | "disable") => DedupBy::Disable,
@@ -765,6 +2037,13 @@ impl Config {
             None => DedupBy::Metadata,
         };
 
+        if opt_exclude.is_some() && !opt_recursive && opt_interactive_mode.is_none() {
+            return Err(HttmError::new(
+                "EXCLUDE is only available if either an interactive mode or recursive mode is specified.",
+            )
+            .into());
+        }
+
         if opt_no_hidden && !opt_recursive && opt_interactive_mode.is_none() {
             return Err(HttmError::new(
                 "FILTER_HIDDEN is only available if either an interactive mode or recursive mode is specified.",
@@ -780,6 +2059,33 @@ impl Config {
             print_mode = PrintMode::RawNewline
         }
 
+        let opt_snapshot_if_changed = matches.get_flag("SNAPSHOT_IF_CHANGED");
+        let opt_datasets_from = matches.get_one::<PathBuf>("DATASETS_FROM").cloned();
+        let opt_ignore_unknown = matches.get_flag("IGNORE_UNKNOWN");
+
+        let opt_snapshot_output = match matches.get_one::<String>("SNAPSHOT_OUTPUT").map(|inner| inner.as_str()) {
+            Some("names") => Some(SnapshotOutput::Names),
+            Some("json") => Some(SnapshotOutput::Json),
+            _ if opt_json && matches.contains_id("SNAPSHOT") => Some(SnapshotOutput::Json),
+            _ => None,
+        };
+
+        let opt_snap_labels: Option<Vec<String>> = match matches.get_many::<String>("LABEL") {
+            Some(labels) => Some(
+                labels
+                    .map(|label| {
+                        RunZFSCommand::validate_user_property(label).map(|_| label.to_owned())
+                    })
+                    .collect::<HttmResult<Vec<String>>>()?,
+            ),
+            None => None,
+        };
+
+        let opt_snap_name_template = match matches.get_one::<String>("SNAP_NAME_TEMPLATE") {
+            Some(template) => Some(SnapNameTemplate::new(template)?),
+            None => None,
+        };
+
         let opt_snap_file_mount =
             if let Some(requested_snapshot_suffix) = matches.get_one::<String>("SNAPSHOT") {
                 if requested_snapshot_suffix == &"httmSnapFileMount" {
@@ -820,10 +2126,43 @@ impl Config {
             None
         };
 
-        let mut exec_mode = if let Some(full_snap_name) = matches.get_one::<String>("ROLL_FORWARD") {
+        let mut exec_mode = if matches.get_flag("JSON_SCHEMA") {
+            ExecMode::JsonSchema
+        } else if let Some(full_snap_name) = matches.get_one::<String>("ROLL_FORWARD") {
+            // fail on a malformed name now, whether the snapshot exists is checked once datasets are known
+            RollForward::split_full_snap_name(full_snap_name)?;
+
             ExecMode::RollForward(full_snap_name.to_string())
+        } else if let Some(mut roots) = matches.get_many::<PathBuf>("COMPARE_DATASETS") {
+            match (roots.next(), roots.next()) {
+                (Some(first_root), Some(second_root)) => {
+                    ExecMode::CompareDatasets(first_root.clone(), second_root.clone())
+                }
+                _ => {
+                    return Err(HttmError::new(
+                        "COMPARE_DATASETS requires exactly two values, a first and a second root.",
+                    )
+                    .into())
+                }
+            }
+        } else if let Some(mut values) = matches.get_many::<String>("MOUNT_SNAP") {
+            match (values.next(), values.next()) {
+                (Some(full_snap_name), Some(target)) => {
+                    ExecMode::MountSnapshot(full_snap_name.clone(), PathBuf::from(target))
+                }
+                _ => {
+                    return Err(HttmError::new(
+                        "MOUNT_SNAP requires exactly two values, a snapshot name and a target directory.",
+                    )
+                    .into())
+                }
+            }
         } else if let Some(num_versions_mode) = opt_num_versions {
             ExecMode::NumVersions(num_versions_mode)
+        } else if let Some(stale_than) = opt_stale_than {
+            ExecMode::StaleThan(stale_than)
+        } else if let Some(opt_gap_threshold) = opt_stat {
+            ExecMode::Stat(opt_gap_threshold)
         } else if let Some(mount_display) = opt_mount_display {
             ExecMode::MountsForFiles(mount_display)
         } else if matches.get_flag("PRUNE") {
@@ -834,7 +2173,8 @@ impl Config {
             ExecMode::SnapFileMount(requested_snapshot_suffix.to_string())
         } else if let Some(interactive_mode) = opt_interactive_mode {
             ExecMode::Interactive(interactive_mode)
-        } else if opt_deleted_mode.is_some() {
+        // a recursive search without an interactive mode or a deleted mode is a plain display recursive search
+        } else if opt_deleted_mode.is_some() || opt_recursive {
             let progress_bar: ProgressBar = indicatif::ProgressBar::new_spinner();
             ExecMode::NonInteractiveRecursive(progress_bar)
         } else {
@@ -851,6 +2191,32 @@ impl Config {
         // current working directory will be helpful in a number of places
         let pwd = pwd()?;
 
+        let opt_strip_prefix: Option<PathBuf> = matches
+            .get_one::<PathBuf>("STRIP_PREFIX")
+            .map(|prefix| {
+                if prefix.is_relative() {
+                    pwd.join(prefix)
+                } else {
+                    prefix.to_owned()
+                }
+            });
+
+        let opt_restore_to: Option<PathBuf> = match matches.get_one::<PathBuf>("RESTORE_TO") {
+            Some(_) if is_overwrite_restore => {
+                return Err(HttmError::new(
+                    "RESTORE_TO is only available in the \"copy\" or \"copy-and-preserve\" restore modes.",
+                )
+                .into());
+            }
+            Some(restore_to) if restore_to.exists() && !restore_to.is_dir() => {
+                let msg = format!("RESTORE_TO must be a directory: {restore_to:?}");
+                return Err(HttmError::new(&msg).into());
+            }
+            Some(restore_to) if restore_to.is_absolute() => Some(restore_to.to_path_buf()),
+            Some(restore_to) => Some(pwd.join(restore_to)),
+            None => None,
+        };
+
         // obtain a map of datasets, a map of snapshot directories, and possibly a map of
         // alternate filesystems and map of aliases if the user requests
         let mut opt_map_aliases = matches.get_raw("MAP_ALIASES");
@@ -866,24 +2232,82 @@ impl Config {
             opt_map_aliases = None;
         }
 
-        let dataset_collection = FilesystemInfo::new(
-            matches.get_flag("ALT_REPLICATED"),
-            opt_debug,
-            matches.get_one::<String>("REMOTE_DIR").map(|inner| inner.as_str()),
-            matches.get_one::<String>("LOCAL_DIR").map(|inner| inner.as_str()),
-            opt_map_aliases,
-            &mut opt_alt_store,
-            &pwd,
-        )?;
+        // must be set before any mounts are parsed, as the snapshot dirs are searched as the mounts are parsed
+        if let Some(snap_dir_name) = matches.get_one::<PathBuf>("SNAP_DIR_NAME") {
+            let is_relative_below = snap_dir_name
+                .components()
+                .all(|component| matches!(component, std::path::Component::Normal(_)));
+
+            if snap_dir_name.as_os_str().is_empty() || !is_relative_below {
+                return Err(HttmError::new(
+                    "SNAP_DIR_NAME must be a relative path below each dataset's mount, such as \".snaps\", without any \"..\" component.",
+                )
+                .into());
+            }
+
+            OPT_SNAP_DIR_NAME
+                .set(snap_dir_name.to_owned())
+                .expect("OPT_SNAP_DIR_NAME should be set only once, as the config is generated only once");
+        }
+
+        // the JSON schema describes no dataset, so it prints even where no supported dataset exists
+        let dataset_collection = if matches!(exec_mode, ExecMode::JsonSchema) {
+            FilesystemInfo::default()
+        } else {
+            FilesystemInfo::new(
+                matches.get_flag("ALT_REPLICATED"),
+                opt_debug,
+                matches.get_raw("REMOTE_DIR"),
+                matches.get_raw("LOCAL_DIR"),
+                opt_map_aliases,
+                &mut opt_alt_store,
+                &pwd,
+            )?
+        };
 
         // paths are immediately converted to our PathData struct
         let opt_os_values = matches.get_many::<PathBuf>("INPUT_FILES");
 
-        let paths: Vec<PathData> = Self::paths(opt_os_values, &exec_mode, &pwd)?;
+        let is_snapshot_from_stdin = matches.get_flag("SNAPSHOT_FROM_STDIN");
+
+        if is_snapshot_from_stdin && std::io::stdin().is_terminal() {
+            return Err(HttmError::new(
+                "SNAPSHOT_FROM_STDIN requires paths be piped to httm via stdin",
+            )
+            .into());
+        }
+
+        let input_split = if matches.get_flag("NULL_IN") {
+            InputSplit::Null
+        } else if matches.get_flag("STDIN_EXACT") {
+            InputSplit::Exact
+        } else {
+            InputSplit::Heuristic
+        };
+
+        // datasets read from a file need no paths, and we shouldn't wait on stdin
+        let paths: Vec<PathData> = if opt_datasets_from.is_some() {
+            Vec::new()
+        } else {
+            Self::paths(
+                opt_os_values,
+                &exec_mode,
+                &pwd,
+                is_snapshot_from_stdin,
+                input_split,
+                matches.get_one::<PathBuf>("FILES_FROM").map(PathBuf::as_path),
+            )?
+        };
+
+        let (paths, opt_numbered_versions) = Self::numbered_versions(paths, &exec_mode)?;
 
         // for exec_modes in which we can only take a single directory, process how we handle those here
-        let opt_requested_dir: Option<PathBuf> =
-            Self::opt_requested_dir(&mut exec_mode, &mut opt_deleted_mode, &paths, &pwd)?;
+        // restoring from a named snapshot is not a browse, so it has no requested dir
+        let opt_requested_dir: Option<PathBuf> = if opt_from_snapshot.is_some() {
+            None
+        } else {
+            Self::opt_requested_dir(&mut exec_mode, &mut opt_deleted_mode, &paths, &pwd)?
+        };
 
         if opt_one_filesystem && opt_requested_dir.is_none() {
             return Err(HttmError::new(
@@ -910,19 +2334,33 @@ impl Config {
         }
 
         let opt_omit_ditto = matches.get_flag("OMIT_DITTO");
+        let opt_prune_empty = matches.get_flag("PRUNE_EMPTY");
+        let opt_omit_identical_across_datasets =
+            matches.get_flag("OMIT_IDENTICAL_ACROSS_DATASETS");
+        let opt_dedup_global = matches.get_flag("DEDUP_GLOBAL");
+        let opt_no_dedup_input = matches.get_flag("NO_DEDUP_INPUT");
+        let opt_dataset_snaps = matches.get_flag("DATASET_SNAPS");
+        let opt_common_snap = matches.get_flag("COMMON_SNAP");
 
         // opt_omit_identical doesn't make sense in Display Recursive mode as no live files will exists?
-        if opt_omit_ditto && matches!(exec_mode, ExecMode::NonInteractiveRecursive(_)) {
+        if opt_omit_ditto
+            && opt_deleted_mode.is_some()
+            && matches!(exec_mode, ExecMode::NonInteractiveRecursive(_))
+        {
             return Err(HttmError::new(
                 "OMIT_DITTO not available when a deleted recursive search is specified. Quitting.",
             )
             .into());
         }
 
-        if opt_last_snap.is_some() && matches!(exec_mode, ExecMode::NonInteractiveRecursive(_)) {
-            return Err(
-                HttmError::new("LAST_SNAP is not available in Display Recursive Mode.").into(),
-            );
+        if opt_last_snap.is_some()
+            && opt_deleted_mode.is_some()
+            && matches!(exec_mode, ExecMode::NonInteractiveRecursive(_))
+        {
+            return Err(HttmError::new(
+                "LAST_SNAP is not available when a deleted recursive search is specified.",
+            )
+            .into());
         }
 
         let config = Config {
@@ -934,14 +2372,68 @@ impl Config {
             opt_debug,
             opt_no_traverse,
             opt_omit_ditto,
+            opt_prune_empty,
+            opt_omit_identical_across_datasets,
+            opt_dedup_global,
+            opt_no_dedup_input,
+            opt_dataset_snaps,
+            opt_common_snap,
             opt_no_hidden,
             opt_last_snap,
             opt_preview,
+            opt_snap_labels,
+            opt_snap_name_template,
+            opt_snapshot_if_changed,
+            opt_datasets_from,
+            opt_ignore_unknown,
+            opt_snapshot_output,
+            opt_parallel_files,
+            opt_threads,
+            opt_network_timeout,
+            opt_follow,
+            opt_from_snapshot,
+            opt_backup_existing,
+            opt_restore_to,
+            opt_max_restore_size,
+            opt_verify,
+            opt_dry_run,
+            opt_confirm,
+            opt_merge,
+            opt_no_clobber,
+            opt_progress,
             opt_json,
+            opt_ndjson,
+            json_format,
+            opt_count_only,
+            opt_include_live,
+            opt_with_versions,
+            opt_humanize_paths,
+            opt_strip_prefix,
             opt_one_filesystem,
+            opt_max_depth,
+            opt_numbered_versions,
+            opt_with_usage,
+            opt_ext_strict,
+            opt_match_regex,
+            opt_exclude,
+            opt_extensions,
             opt_no_clones,
+            preserve_attrs,
             dedup_by,
+            size_format,
+            color_mode,
+            sort_by,
+            opt_reverse,
+            opt_summary,
             requested_utc_offset,
+            opt_date_format,
+            opt_relative_dates,
+            opt_show_source,
+            opt_with_fstype,
+            opt_newer_than,
+            opt_older_than,
+            opt_min_size,
+            opt_max_size,
             exec_mode,
             print_mode,
             opt_deleted_mode,
@@ -957,15 +2449,37 @@ impl Config {
         opt_os_values: Option<ValuesRef<'_, PathBuf>>,
         exec_mode: &ExecMode,
         pwd: &Path,
+        is_snapshot_from_stdin: bool,
+        input_split: InputSplit,
+        opt_files_from: Option<&Path>,
     ) -> HttmResult<Vec<PathData>> {
-        let mut paths = if let Some(input_files) = opt_os_values {
-            input_files
-                .into_iter()
-                .par_bridge()
-                // canonicalize() on a deleted relative path will not exist,
-                // so we have to join with the pwd to make a path that
-                // will exist on a snapshot
-                .map(PathData::from)
+        // paths read from a file are treated just like those specified as arguments
+        let opt_input_files: Option<Vec<PathData>> = match (opt_os_values, opt_files_from) {
+            (None, None) => None,
+            (opt_os_values, opt_files_from) => {
+                let mut input_files: Vec<PathData> = opt_os_values
+                    .into_iter()
+                    .flatten()
+                    // unlike par_bridge, keeps the order in which the paths were requested
+                    .collect::<Vec<&PathBuf>>()
+                    .into_par_iter()
+                    // canonicalize() on a deleted relative path will not exist,
+                    // so we have to join with the pwd to make a path that
+                    // will exist on a snapshot
+                    .map(PathData::from)
+                    .collect();
+
+                if let Some(files_from) = opt_files_from {
+                    input_files.extend(Self::read_files_from(files_from, input_split)?);
+                }
+
+                Some(input_files)
+            }
+        };
+
+        let mut paths: Vec<PathData> = if let Some(input_files) = opt_input_files {
+            let mut paths: Vec<PathData> = input_files
+                .into_par_iter()
                 .map(|pd| {
                     // but what about snapshot paths?
                     // here we strip the additional snapshot VFS bits and make them look like live versions
@@ -977,7 +2491,14 @@ impl Config {
                         _ => pd,
                     }
                 })
-                .collect()
+                .collect();
+
+            // snapshot both the paths specified as arguments and those on stdin
+            if is_snapshot_from_stdin {
+                paths.extend(Self::read_stdin(input_split)?);
+            }
+
+            paths
         } else {
             match exec_mode {
                 // setting pwd as the path, here, keeps us from waiting on stdin when in certain modes
@@ -985,7 +2506,10 @@ impl Config {
                 // input, and waiting on one input from stdin is pretty silly
                 ExecMode::Interactive(_)
                 | ExecMode::NonInteractiveRecursive(_)
-                | ExecMode::RollForward(_) => {
+                | ExecMode::RollForward(_)
+                | ExecMode::CompareDatasets(_, _)
+                | ExecMode::MountSnapshot(_, _)
+                | ExecMode::JsonSchema => {
                     vec![PathData::from(pwd)]
                 }
                 ExecMode::BasicDisplay
@@ -993,32 +2517,168 @@ impl Config {
                 | ExecMode::Prune(_)
                 | ExecMode::MountsForFiles(_)
                 | ExecMode::SnapsForFiles(_)
-                | ExecMode::NumVersions(_) => Self::read_stdin()?,
+                | ExecMode::NumVersions(_)
+                | ExecMode::StaleThan(_)
+                | ExecMode::Stat(_) => Self::read_stdin(input_split)?,
             }
         };
 
-        // deduplicate pathdata and sort if in display mode --
+        Self::deny_relative(paths.iter().map(PathData::path))?;
+
+        // deduplicate pathdata, keeping the order in which the paths were requested --
         // so input of ./.z* and ./.zshrc will only print ./.zshrc once
-        paths = if paths.len() > 1 {
-            paths.sort_unstable();
-            // dedup needs to be sorted/ordered first to work (not like a BTreeMap)
-            paths.dedup();
+        if paths.len() > 1 {
+            let mut seen: HashSet<PathBuf> = HashSet::with_capacity(paths.len());
 
-            paths
-        } else {
-            paths
-        };
+            paths.retain(|pathdata| seen.insert(pathdata.path().to_path_buf()));
+        }
 
         Ok(paths)
     }
 
-    pub fn read_stdin() -> HttmResult<Vec<PathData>> {
+    // a relative path remains only when it could not be resolved against the cwd,
+    // and we would rather quit than quietly look up the wrong path
+    fn deny_relative<'a>(mut paths: impl Iterator<Item = &'a Path>) -> HttmResult<()> {
+        if let Some(relative) = paths.find(|path| path.is_relative()) {
+            let msg = format!(
+                "httm could not resolve the path requested to an absolute path: {:?}.  \
+                Perhaps the working directory no longer exists?  Try an absolute path.",
+                relative
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(())
+    }
+
+    // a path such as "file@3", which does not itself exist, requests only the 3rd newest snapshot version of "file"
+    fn numbered_versions(
+        paths: Vec<PathData>,
+        exec_mode: &ExecMode,
+    ) -> HttmResult<(Vec<PathData>, Option<MapOfNumberedVersions>)> {
+        if !matches!(exec_mode, ExecMode::BasicDisplay) {
+            return Ok((paths, None));
+        }
+
+        let mut map_of_numbered: MapOfNumberedVersions = BTreeMap::new();
+
+        let mut paths: Vec<PathData> = paths
+            .into_iter()
+            .map(|pathdata| {
+                let path = pathdata.path();
+
+                if path.symlink_metadata().is_ok() {
+                    return Ok(pathdata);
+                }
+
+                let Some((stripped, number)) = path
+                    .to_str()
+                    .and_then(|path_str| path_str.rsplit_once('@'))
+                    .filter(|(stripped, number)| {
+                        !stripped.is_empty()
+                            && !number.is_empty()
+                            && number.chars().all(|c| c.is_ascii_digit())
+                    })
+                else {
+                    return Ok(pathdata);
+                };
+
+                let number: usize = match number.parse() {
+                    Ok(number) if number > 0 => number,
+                    _ => {
+                        let msg = format!("The version number requested must be a value greater than zero: {path:?}");
+                        return Err(HttmError::new(&msg).into());
+                    }
+                };
+
+                let stripped = PathData::from(Path::new(stripped));
+
+                if map_of_numbered.insert(stripped.path().to_path_buf(), number).is_some() {
+                    let msg = format!(
+                        "Only a single numbered version may be requested per path: {:?}",
+                        stripped.path()
+                    );
+                    return Err(HttmError::new(&msg).into());
+                }
+
+                Ok(stripped)
+            })
+            .collect::<HttmResult<Vec<PathData>>>()?;
+
+        if map_of_numbered.is_empty() {
+            return Ok((paths, None));
+        }
+
+        let len = paths.len();
+        paths.sort_unstable();
+        paths.dedup();
+
+        if paths.len() != len {
+            return Err(HttmError::new(
+                "A path with a numbered version may not also be requested without a number.",
+            )
+            .into());
+        }
+
+        Ok((paths, Some(map_of_numbered)))
+    }
+
+    pub fn read_stdin(input_split: InputSplit) -> HttmResult<Vec<PathData>> {
         let stdin = std::io::stdin();
         let mut stdin = stdin.lock();
         let mut buffer = Vec::new();
         stdin.read_to_end(&mut buffer)?;
 
-        let buffer_string = std::str::from_utf8(&buffer)?;
+        Self::split_paths(&buffer, input_split)
+    }
+
+    // a file of "-" is stdin, otherwise the file is split exactly like stdin
+    fn read_files_from(files_from: &Path, input_split: InputSplit) -> HttmResult<Vec<PathData>> {
+        if files_from == Path::new("-") {
+            return Self::read_stdin(input_split);
+        }
+
+        let buffer = std::fs::read(files_from).map_err(|err| {
+            let msg = format!("httm could not read the paths file {files_from:?}");
+            HttmError::with_context(&msg, &err)
+        })?;
+
+        // STDIN_EXACT only ever applies to stdin, a file of paths is always a list of paths
+        let input_split = match input_split {
+            InputSplit::Exact => InputSplit::Heuristic,
+            _ => input_split,
+        };
+
+        Self::split_paths(&buffer, input_split)
+    }
+
+    fn split_paths(buffer: &[u8], input_split: InputSplit) -> HttmResult<Vec<PathData>> {
+        // no splitting at all, the whole buffer, less one trailing newline, is a single path
+        if matches!(input_split, InputSplit::Exact) {
+            let bytes = buffer
+                .strip_suffix(b"\r\n")
+                .or_else(|| buffer.strip_suffix(b"\n"))
+                .unwrap_or(buffer);
+
+            if bytes.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            return Ok(vec![PathData::from(Path::new(OsStr::from_bytes(bytes)))]);
+        }
+
+        // no heuristics, only NULL chars delimit paths, which may be any bytes at all
+        if matches!(input_split, InputSplit::Null) {
+            let paths = buffer
+                .split(|byte| *byte == b'\0')
+                .filter(|bytes| !bytes.is_empty())
+                .map(|bytes| PathData::from(Path::new(OsStr::from_bytes(bytes))))
+                .collect();
+
+            return Ok(paths);
+        }
+
+        let buffer_string = std::str::from_utf8(buffer)?;
 
         let broken_string = if buffer_string.contains(['\n', '\0']) {
             // always split on newline or null char, if available
@@ -1100,11 +2760,16 @@ impl Config {
 
             ExecMode::BasicDisplay
             | ExecMode::RollForward(_)
+            | ExecMode::CompareDatasets(_, _)
+            | ExecMode::MountSnapshot(_, _)
+            | ExecMode::JsonSchema
             | ExecMode::SnapFileMount(_)
             | ExecMode::Prune(_)
             | ExecMode::MountsForFiles(_)
             | ExecMode::SnapsForFiles(_)
-            | ExecMode::NumVersions(_) => {
+            | ExecMode::NumVersions(_)
+            | ExecMode::StaleThan(_)
+            | ExecMode::Stat(_) => {
                 // in non-interactive mode / display mode, requested dir is just a file
                 // like every other file and pwd must be the requested working dir.
                 None
@@ -1154,3 +2819,20 @@ impl Config {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_paths_which_remain_are_refused() {
+        assert!(Config::deny_relative([Path::new("/tank/ds/file")].into_iter()).is_ok());
+
+        let err = Config::deny_relative(
+            [Path::new("/tank/ds/file"), Path::new("dir/file")].into_iter(),
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("\"dir/file\""));
+    }
+}
@@ -0,0 +1,124 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use crate::data::paths::{PathData, PathMetadata};
+use crate::display_map::format::{json_output_schema, PrintAsMap, PRINT_AS_MAP_VALUES_NAMES};
+use crate::display_versions::stat::VersionStat;
+use crate::display_versions::wrapper::VersionsDisplayWrapper;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::print_output_buf;
+use crate::lookup::versions::VersionsMap;
+use crate::zfs::snap_mounts::SnapshotTaken;
+
+fn json_schema() -> serde_json::Value {
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "httm JSON output",
+        "oneOf": [
+            json_output_schema(&["versions"], "#/$defs/versions"),
+            json_output_schema(&PRINT_AS_MAP_VALUES_NAMES, "#/$defs/print_as_map"),
+            json_output_schema(&["snapshots_taken"], "#/$defs/snapshots_taken"),
+            json_output_schema(&["stats"], "#/$defs/stats")
+        ],
+        "$defs": {
+            "versions": VersionsDisplayWrapper::json_schema(),
+            "versions_map": VersionsMap::json_schema(),
+            "print_as_map": PrintAsMap::json_schema(),
+            "path_data": PathData::json_schema(),
+            "path_metadata": PathMetadata::json_schema(),
+            "snapshots_taken": SnapshotTaken::json_schema(),
+            "stats": VersionStat::json_schema()
+        }
+    })
+}
+
+pub fn print_json_schema() -> HttmResult<()> {
+    match serde_json::to_string_pretty(&json_schema()) {
+        Ok(s) => print_output_buf(&format!("{s}\n")),
+        Err(err) => {
+            Err(HttmError::with_context("httm could not serialize JSON schema", &err).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display_map::format::JsonOutput;
+    use crate::zfs::snap_mounts::SnapResult;
+    use crate::GLOBAL_CONFIG;
+    use indexmap::IndexMap;
+    use std::fs::File;
+    use std::time::{Duration, SystemTime};
+
+    // the samples are serialized by httm's own Serialize impls, so the schema must describe what httm prints
+    #[test]
+    fn json_output_is_valid_against_the_json_schema() {
+        let validator = jsonschema::validator_for(&json_schema()).unwrap();
+
+        let test_dir =
+            std::env::temp_dir().join(format!("httm_json_schema_{}", std::process::id()));
+        std::fs::create_dir_all(&test_dir).unwrap();
+
+        // versions a second apart, and then a gap
+        let now = SystemTime::now();
+        let versions: Vec<PathData> = [0, 1, 2, 100]
+            .into_iter()
+            .map(|secs| {
+                let path = test_dir.join(format!("version_{secs}"));
+                File::create(&path)
+                    .unwrap()
+                    .set_modified(now + Duration::from_secs(secs))
+                    .unwrap();
+                PathData::from(path.as_path())
+            })
+            .collect();
+
+        let live = PathData::from(test_dir.join("version_100").as_path());
+        let phantom = PathData::from(test_dir.join("deleted").as_path());
+
+        let versions_map = VersionsMap::from(IndexMap::from([
+            (live, versions.clone()),
+            (phantom, versions[..1].to_vec()),
+        ]));
+        let print_as_map = PrintAsMap::from(&versions_map);
+        let wrapper = VersionsDisplayWrapper::from(&GLOBAL_CONFIG, versions_map);
+
+        let snap_result = SnapResult {
+            dataset: "rpool/data".to_owned(),
+            snapshot_name: "rpool/data@snap_test".to_owned(),
+            pool: "rpool".to_owned(),
+            succeeded: true,
+        };
+        let snapshots_taken = [SnapshotTaken::new(&snap_result, "Wed Oct 14 14:25:13 2026")];
+        let stats = wrapper.stats(&None);
+
+        let samples = [
+            serde_json::to_value(JsonOutput::new("versions", &wrapper)),
+            serde_json::to_value(JsonOutput::new("values", &print_as_map)),
+            serde_json::to_value(JsonOutput::new("snapshots_taken", &snapshots_taken[..])),
+            serde_json::to_value(JsonOutput::new("stats", &stats)),
+        ];
+
+        std::fs::remove_dir_all(&test_dir).unwrap();
+
+        samples.into_iter().for_each(|sample| {
+            let sample = sample.unwrap();
+            assert!(validator.is_valid(&sample), "{sample:#}");
+        });
+    }
+}
@@ -24,7 +24,7 @@ use clap::parser::RawValues;
 use std::path::Path;
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct FilesystemInfo {
     // key: mount, val: (dataset/subvol, fs_type, mount_type)
     pub map_of_datasets: MapOfDatasets,
@@ -44,15 +44,19 @@ impl FilesystemInfo {
     pub fn new(
         opt_alt_replicated: bool,
         opt_debug: bool,
-        opt_remote_dir: Option<&str>,
-        opt_local_dir: Option<&str>,
+        opt_raw_remote_dirs: Option<RawValues>,
+        opt_raw_local_dirs: Option<RawValues>,
         opt_raw_aliases: Option<RawValues>,
         opt_alt_store: &mut Option<&FilesystemType>,
         pwd: &Path,
     ) -> HttmResult<FilesystemInfo> {
         // only create a map of aliases if necessary (aliases conflicts with alt stores)
-        let opt_map_of_aliases =
-            MapOfAliases::new(opt_raw_aliases, opt_remote_dir, opt_local_dir, pwd)?;
+        let opt_map_of_aliases = MapOfAliases::new(
+            opt_raw_aliases,
+            opt_raw_remote_dirs,
+            opt_raw_local_dirs,
+            pwd,
+        )?;
 
         let base_fs_info = BaseFilesystemInfo::new(opt_debug, opt_alt_store, &opt_map_of_aliases)?;
 
@@ -29,7 +29,7 @@ use serde::{Serialize, Serializer};
 use std::cmp::{Ord, Ordering, PartialOrd};
 use std::ffi::OsStr;
 use std::fs::{symlink_metadata, DirEntry, FileType, Metadata};
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 use std::sync::OnceLock;
@@ -42,24 +42,48 @@ static DATASET_MAX_LEN: LazyLock<usize> =
 
 // only the most basic data from a DirEntry
 // for use to display in browse window and internally
-#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct BasicDirEntryInfo {
     path: PathBuf,
     opt_filetype: Option<FileType>,
+    // whether we count this entry as a dir is asked more than once during a recursive search,
+    // and, for a symlink, each answer would otherwise canonicalize the link again
+    is_dir: OnceLock<bool>,
+}
+
+// the memoized is_dir is derived from the path and file type, so is never compared
+impl PartialEq for BasicDirEntryInfo {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path && self.opt_filetype == other.opt_filetype
+    }
+}
+
+impl Eq for BasicDirEntryInfo {}
+
+impl Hash for BasicDirEntryInfo {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.path.hash(state);
+        self.opt_filetype.hash(state);
+    }
 }
 
 impl From<&DirEntry> for BasicDirEntryInfo {
     fn from(dir_entry: &DirEntry) -> Self {
-        BasicDirEntryInfo {
-            path: dir_entry.path(),
-            opt_filetype: dir_entry.file_type().ok(),
-        }
+        Self::new(dir_entry.path(), dir_entry.file_type().ok())
     }
 }
 
 impl BasicDirEntryInfo {
     pub fn new(path: PathBuf, opt_filetype: Option<FileType>) -> Self {
-        Self { path, opt_filetype }
+        Self {
+            path,
+            opt_filetype,
+            is_dir: OnceLock::new(),
+        }
+    }
+
+    pub fn is_dir_memoized(&self, is_dir: impl FnOnce() -> bool) -> bool {
+        *self.is_dir.get_or_init(is_dir)
     }
 
     pub fn filename(&self) -> &OsStr {
@@ -87,7 +111,8 @@ impl BasicDirEntryInfo {
 
 impl Into<SelectionCandidate> for BasicDirEntryInfo {
     fn into(self) -> SelectionCandidate {
-        unsafe { std::mem::transmute(self) }
+        // moved field by field, as only a BasicDirEntryInfo memoizes is_dir, their layouts differ
+        SelectionCandidate::new(self.path, self.opt_filetype)
     }
 }
 
@@ -148,10 +173,21 @@ impl PathData {
         //
         // in general we handle those cases elsewhere, like the ingest
         // of input files in Config::from for deleted relative paths, etc.
-        let canonical_path: PathBuf =
-            realpath(path, RealpathFlags::ALLOW_MISSING).unwrap_or_else(|_| path.to_path_buf());
-
-        let path_metadata = opt_metadata.and_then(|md| PathMetadata::new(&md));
+        let path_metadata = opt_metadata.and_then(|md| PathMetadata::new(path, &md));
+
+        // NOTE: a symlink's PathData is the symlink itself, never its target.  Once, a symlink was
+        // canonicalized to its target's path, and a symlink's versions were its target's versions.
+        // Now, our metadata is the symlink's own, so we canonicalize only the symlink's parent,
+        // else our path would be the symlink's target
+        let canonical_path: PathBuf = match (path_metadata, path.parent(), path.file_name()) {
+            (Some(md), Some(parent), Some(file_name)) if md.is_symlink() => {
+                realpath(parent, RealpathFlags::ALLOW_MISSING)
+                    .map(|canonical_parent| canonical_parent.join(file_name))
+                    .unwrap_or_else(|_| Self::absolute_fallback(path, std::env::current_dir().ok()))
+            }
+            _ => realpath(path, RealpathFlags::ALLOW_MISSING)
+                .unwrap_or_else(|_| Self::absolute_fallback(path, std::env::current_dir().ok())),
+        };
 
         Self {
             path_buf: canonical_path,
@@ -159,6 +195,15 @@ impl PathData {
         }
     }
 
+    // when realpath fails, a relative path is joined to the actual cwd, never the PWD env var,
+    // which may be stale.  if the cwd itself is gone, the path remains relative, see Config::paths
+    fn absolute_fallback(path: &Path, opt_cwd: Option<PathBuf>) -> PathBuf {
+        match opt_cwd {
+            Some(cwd) if path.is_relative() => cwd.join(path),
+            _ => path.to_path_buf(),
+        }
+    }
+
     pub fn path<'a>(&'a self) -> &'a Path {
         &self.path_buf
     }
@@ -394,10 +439,12 @@ impl Serialize for PathData {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("PathData", 2)?;
+        let mut state = serializer.serialize_struct("PathData", 3)?;
 
         state.serialize_field("path", &self.path_buf)?;
         state.serialize_field("metadata", &self.metadata)?;
+        // a phantom is a deleted live file, which has no metadata
+        state.serialize_field("is_phantom", &self.metadata.is_none())?;
         state.end()
     }
 }
@@ -431,20 +478,101 @@ impl Serialize for PathMetadata {
     }
 }
 
+impl PathData {
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "metadata": {
+                    "oneOf": [
+                        { "$ref": "#/$defs/path_metadata" },
+                        { "type": "null" }
+                    ]
+                },
+                "is_phantom": { "type": "boolean" },
+                "also_found_on": {
+                    "description": "snapshot versions only: other datasets upon which identical versions, omitted from display, were found",
+                    "type": "array",
+                    "items": { "type": "string" }
+                },
+                "shared_with": {
+                    "description": "snapshot versions only: other live paths under which identical versions, omitted from display, were found",
+                    "type": "array",
+                    "items": { "type": "string" }
+                }
+            },
+            "required": ["path", "metadata", "is_phantom"]
+        })
+    }
+}
+
+impl PathMetadata {
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "oneOf": [
+                {
+                    "description": "default and not-so-pretty modes: human readable size and display date",
+                    "type": "object",
+                    "properties": {
+                        "size": { "type": "string" },
+                        "modify_time": { "type": "string" }
+                    },
+                    "required": ["size", "modify_time"]
+                },
+                {
+                    "description": "raw and zeros modes: size in bytes and time since the Unix epoch",
+                    "type": "object",
+                    "properties": {
+                        "size": { "type": "integer", "minimum": 0 },
+                        "modify_time": {
+                            "type": "object",
+                            "properties": {
+                                "secs_since_epoch": { "type": "integer", "minimum": 0 },
+                                "nanos_since_epoch": { "type": "integer", "minimum": 0 }
+                            },
+                            "required": ["secs_since_epoch", "nanos_since_epoch"]
+                        }
+                    },
+                    "required": ["size", "modify_time"]
+                }
+            ]
+        })
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub struct PathMetadata {
     size: u64,
     modify_time: SystemTime,
+    is_symlink: bool,
+    // a hash of a symlink's target, resolved only once, here, so that metadata which compares equal
+    // is also the same link, and so the PathMetadata remains Copy
+    opt_link_target_hash: Option<u64>,
 }
 
 impl PathMetadata {
     // call symlink_metadata, as we need to resolve symlinks to get non-"phantom" metadata
     #[inline(always)]
-    pub fn new(md: &Metadata) -> Option<Self> {
+    pub fn new(path: &Path, md: &Metadata) -> Option<Self> {
+        let is_symlink = md.file_type().is_symlink();
+
+        let opt_link_target_hash =
+            is_symlink
+                .then(|| path.read_link().ok())
+                .flatten()
+                .map(|link_target| {
+                    let mut ahasher = ahash::AHasher::default();
+                    link_target.hash(&mut ahasher);
+                    ahasher.finish()
+                });
+
         // may fail on systems that don't collect a modify time
         md.modified().ok().map(|time| PathMetadata {
             size: md.len(),
             modify_time: time,
+            is_symlink,
+            opt_link_target_hash,
         })
     }
 
@@ -459,6 +587,17 @@ impl PathMetadata {
     pub fn size(&self) -> u64 {
         self.size
     }
+
+    // the metadata of a symlink is the link's own metadata, never its target's
+    #[inline(always)]
+    pub fn is_symlink(&self) -> bool {
+        self.is_symlink
+    }
+
+    #[inline(always)]
+    pub fn opt_link_target_hash(&self) -> Option<u64> {
+        self.opt_link_target_hash
+    }
 }
 
 pub const PHANTOM_DATE: SystemTime = SystemTime::UNIX_EPOCH;
@@ -467,6 +606,8 @@ pub const PHANTOM_SIZE: u64 = 0u64;
 pub const PHANTOM_PATH_METADATA: PathMetadata = PathMetadata {
     size: PHANTOM_SIZE,
     modify_time: PHANTOM_DATE,
+    is_symlink: false,
+    opt_link_target_hash: None,
 };
 
 #[derive(Eq, PartialEq)]
@@ -486,12 +627,21 @@ impl Ord for CompareVersionsContainer {
     #[inline(always)]
     fn cmp(&self, other: &Self) -> Ordering {
         if self.mtime() == other.mtime() {
-            return self.size().cmp(&other.size());
+            // symlinks with the same metadata may still point to different targets
+            // and, when deduping by contents, files with the same metadata may still differ re bytes
+            return self
+                .size()
+                .cmp(&other.size())
+                .then_with(|| self.cmp_symlink_targets(other))
+                .then_with(|| self.cmp_file_contents(other));
         }
 
         // if files, differ re mtime, but have same size, we test by bytes whether the same
+        // (never for symlinks, whose contents would be the contents of their targets)
         if self.size() == other.size()
             && self.opt_hash.is_some()
+            && !self.is_symlink()
+            && !other.is_symlink()
             // if above is true/false then "&& other.opt_hash.is_some()" is the same
             && self.is_same_file_contents(other)
         {
@@ -529,8 +679,42 @@ impl CompareVersionsContainer {
         self.pathdata.metadata_infallible().size
     }
 
-    #[allow(unused_assignments)]
+    #[inline(always)]
+    pub fn is_symlink(&self) -> bool {
+        self.pathdata.metadata_infallible().is_symlink()
+    }
+
+    fn cmp_symlink_targets(&self, other: &Self) -> Ordering {
+        let self_md = self.pathdata.metadata_infallible();
+        let other_md = other.pathdata.metadata_infallible();
+
+        self_md
+            .is_symlink()
+            .cmp(&other_md.is_symlink())
+            .then_with(|| {
+                self_md
+                    .opt_link_target_hash()
+                    .cmp(&other_md.opt_link_target_hash())
+            })
+    }
+
+    fn cmp_file_contents(&self, other: &Self) -> Ordering {
+        if self.opt_hash.is_none() || self.is_symlink() || other.is_symlink() {
+            return Ordering::Equal;
+        }
+
+        let (self_hash, other_hash) = self.file_hashes(other);
+
+        self_hash.cmp(&other_hash)
+    }
+
     pub fn is_same_file_contents(&self, other: &Self) -> bool {
+        let (self_hash, other_hash) = self.file_hashes(other);
+
+        self_hash == other_hash
+    }
+
+    fn file_hashes(&self, other: &Self) -> (u64, u64) {
         // SAFETY: Unwrap will fail on opt_hash is None, here we've guarded this above
         let self_hash_cell = self
             .opt_hash
@@ -541,31 +725,149 @@ impl CompareVersionsContainer {
             .as_ref()
             .expect("opt_hash should be check prior to this point and must be Some");
 
-        let (self_hash, other_hash): (HttmResult<u64>, HttmResult<u64>) = rayon::join(
+        rayon::join(
+            || *self_hash_cell.get_or_init(|| HashFileContents::path_to_hash(self.pathdata.path())),
             || {
-                if let Some(hash_value) = self_hash_cell.get() {
-                    return Ok(*hash_value);
-                };
-
-                Ok(*self_hash_cell
-                    .get_or_init(|| HashFileContents::path_to_hash(self.pathdata.path())))
+                *other_hash_cell
+                    .get_or_init(|| HashFileContents::path_to_hash(other.pathdata.path()))
             },
-            || {
-                if let Some(hash_value) = other_hash_cell.get() {
-                    return Ok(*hash_value);
-                }
+        )
+    }
+}
 
-                Ok(*other_hash_cell
-                    .get_or_init(|| HashFileContents::path_to_hash(other.pathdata.path())))
-            },
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+
+    fn write_with_mtime(path: &Path, contents: &[u8], mtime: SystemTime) {
+        let mut file = File::create(path).unwrap();
+        file.write_all(contents).unwrap();
+        file.set_modified(mtime).unwrap();
+    }
+
+    #[test]
+    fn relative_paths_fall_back_to_the_cwd_only_while_it_exists() {
+        let cwd = PathBuf::from("/tank/ds");
+
+        assert_eq!(
+            PathData::absolute_fallback(Path::new("dir/file"), Some(cwd.clone())),
+            PathBuf::from("/tank/ds/dir/file")
+        );
+        assert_eq!(
+            PathData::absolute_fallback(Path::new("/other/file"), Some(cwd)),
+            PathBuf::from("/other/file")
         );
 
-        if let Ok(res_self) = self_hash {
-            if let Ok(res_other) = other_hash {
-                return res_self == res_other;
-            }
-        }
+        // the cwd is gone, so the path remains relative, and Config refuses it
+        assert_eq!(
+            PathData::absolute_fallback(Path::new("dir/file"), None),
+            PathBuf::from("dir/file")
+        );
+    }
+
+    #[test]
+    fn same_mtime_and_size_but_different_contents_are_not_deduped() {
+        let dir = std::env::temp_dir().join(format!("httm-test-dedup-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mtime = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        let first = dir.join("first");
+        let second = dir.join("second");
+        let third = dir.join("third");
+        write_with_mtime(&first, b"abcd", mtime);
+        write_with_mtime(&second, b"wxyz", mtime);
+        write_with_mtime(&third, b"abcd", mtime);
+
+        let container = |path: &Path, dedup_by: &DedupBy| {
+            CompareVersionsContainer::new(PathData::from(path), dedup_by)
+        };
+
+        // metadata alone can't tell these apart
+        assert_eq!(
+            container(&first, &DedupBy::Metadata).cmp(&container(&second, &DedupBy::Metadata)),
+            Ordering::Equal
+        );
+
+        // but their contents can
+        assert_ne!(
+            container(&first, &DedupBy::Contents).cmp(&container(&second, &DedupBy::Contents)),
+            Ordering::Equal
+        );
+        assert_eq!(
+            container(&first, &DedupBy::Contents).cmp(&container(&third, &DedupBy::Contents)),
+            Ordering::Equal
+        );
+
+        // an ordering must be consistent whichever side we start from
+        assert_eq!(
+            container(&first, &DedupBy::Contents).cmp(&container(&second, &DedupBy::Contents)),
+            container(&second, &DedupBy::Contents)
+                .cmp(&container(&first, &DedupBy::Contents))
+                .reverse()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn symlink_with_mtime(target: &str, link: &Path, mtime: SystemTime) {
+        std::os::unix::fs::symlink(target, link).unwrap();
+
+        let secs = mtime
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let time_spec = nix::sys::time::TimeSpec::new(secs, 0);
+
+        nix::sys::stat::utimensat(
+            None,
+            link,
+            &time_spec,
+            &time_spec,
+            nix::sys::stat::UtimensatFlags::NoFollowSymlink,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn symlinks_are_compared_by_their_own_targets() {
+        let dir = std::env::temp_dir().join(format!("httm-test-symlink-{}", std::process::id()));
+        // as if the same symlink, upon two snapshots, and the live dataset
+        let (first_snap, second_snap, live) =
+            (dir.join("snap1"), dir.join("snap2"), dir.join("live"));
+        [&first_snap, &second_snap, &live]
+            .iter()
+            .for_each(|dir| std::fs::create_dir_all(dir).unwrap());
+
+        // same link mtime, and same link size, as the targets have the same len
+        let mtime = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        symlink_with_mtime("target_a", &first_snap.join("link"), mtime);
+        symlink_with_mtime("target_b", &second_snap.join("link"), mtime);
+        symlink_with_mtime("target_a", &live.join("link"), mtime);
+
+        let first = PathData::from(first_snap.join("link"));
+        let second = PathData::from(second_snap.join("link"));
+        let same_as_first = PathData::from(live.join("link"));
+
+        // the path is the link itself, never its target, which need not exist
+        assert!(first.path().ends_with("snap1/link"));
+        assert!(first.metadata_infallible().is_symlink());
+
+        // the target changed, so the metadata differs, as do the versions
+        assert_ne!(first.opt_metadata(), second.opt_metadata());
+        assert_eq!(first.opt_metadata(), same_as_first.opt_metadata());
+
+        let container = |pathdata: &PathData| {
+            CompareVersionsContainer::new(pathdata.clone(), &DedupBy::Metadata)
+        };
+
+        assert_ne!(container(&first).cmp(&container(&second)), Ordering::Equal);
+        assert_eq!(
+            container(&first).cmp(&container(&same_as_first)),
+            Ordering::Equal
+        );
 
-        false
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
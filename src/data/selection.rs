@@ -16,7 +16,7 @@
 // that was distributed with this source code.
 
 use crate::background::recursive::PathProvenance;
-use crate::config::generate::{DedupBy, PrintMode};
+use crate::config::generate::{DedupBy, JsonFormat, PreserveAttrs, PrintMode};
 use crate::data::paths::PathData;
 use crate::display_versions::wrapper::VersionsDisplayWrapper;
 use crate::library::results::HttmResult;
@@ -67,7 +67,7 @@ impl SelectionCandidate {
 
         // finally run search on those paths
         let all_snap_versions: VersionsMap =
-            [Versions::new(&display_pathdata, &display_config)?.into_inner()].into();
+            Versions::new(&display_pathdata, &display_config)?.into();
 
         let output_buf =
             VersionsDisplayWrapper::from(&display_config, all_snap_versions).to_string();
@@ -144,15 +144,70 @@ impl From<Vec<PathData>> for Config {
             opt_no_traverse: false,
             opt_no_hidden: false,
             opt_json: false,
+            opt_ndjson: false,
+            json_format: JsonFormat::Compact,
+            opt_count_only: false,
+            opt_include_live: false,
+            opt_with_versions: false,
+            opt_humanize_paths: config.opt_humanize_paths,
+            // select parses full snapshot paths from this display
+            opt_strip_prefix: None,
             opt_one_filesystem: false,
+            opt_max_depth: None,
+            opt_numbered_versions: None,
+            opt_with_usage: false,
+            opt_ext_strict: false,
+            opt_match_regex: None,
+            opt_exclude: None,
+            opt_extensions: None,
             opt_no_clones: false,
+            preserve_attrs: PreserveAttrs::default(),
             opt_bulk_exclusion: None,
             opt_last_snap: None,
             opt_preview: None,
+            opt_snap_labels: None,
+            opt_snap_name_template: None,
+            opt_snapshot_if_changed: false,
+            opt_datasets_from: None,
+            opt_ignore_unknown: false,
+            opt_snapshot_output: None,
+            opt_parallel_files: None,
+            opt_threads: config.opt_threads,
+            opt_network_timeout: None,
+            opt_follow: None,
+            opt_from_snapshot: None,
+            opt_backup_existing: false,
+            opt_restore_to: None,
+            opt_max_restore_size: None,
+            opt_verify: false,
+            opt_dry_run: false,
+            opt_confirm: false,
+            opt_merge: false,
+            opt_no_clobber: false,
+            opt_progress: false,
             opt_deleted_mode: None,
             dedup_by: DedupBy::Metadata,
+            size_format: config.size_format.clone(),
+            color_mode: config.color_mode.clone(),
+            sort_by: config.sort_by.clone(),
+            opt_reverse: config.opt_reverse,
+            opt_summary: false,
             opt_omit_ditto: config.opt_omit_ditto,
+            opt_prune_empty: false,
+            opt_omit_identical_across_datasets: config.opt_omit_identical_across_datasets,
+            opt_dedup_global: config.opt_dedup_global,
+            opt_no_dedup_input: false,
+            opt_dataset_snaps: false,
+            opt_common_snap: false,
             requested_utc_offset: config.requested_utc_offset,
+            opt_date_format: config.opt_date_format.clone(),
+            opt_relative_dates: config.opt_relative_dates,
+            opt_show_source: config.opt_show_source,
+            opt_with_fstype: false,
+            opt_newer_than: config.opt_newer_than,
+            opt_older_than: config.opt_older_than,
+            opt_min_size: config.opt_min_size,
+            opt_max_size: config.opt_max_size,
             exec_mode: ExecMode::BasicDisplay,
             print_mode: PrintMode::FormattedDefault,
             dataset_collection: config.dataset_collection.clone(),
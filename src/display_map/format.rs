@@ -15,24 +15,33 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use crate::config::generate::PrintMode;
+use crate::config::generate::{ExecMode, JsonFormat, PrintMode};
 use crate::data::paths::PathData;
 use crate::data::paths::ZfsSnapPathGuard;
-use crate::display_versions::format::{NOT_SO_PRETTY_FIXED_WIDTH_PADDING, QUOTATION_MARKS_LEN};
+use crate::display_versions::format::{
+    DisplaySetType, NOT_SO_PRETTY_FIXED_WIDTH_PADDING, QUOTATION_MARKS_LEN,
+};
 use crate::library::utility::delimiter;
+use crate::lookup::file_mounts::DatasetUsage;
 use crate::{MountsForFiles, SnapNameMap, VersionsMap, GLOBAL_CONFIG};
+use indexmap::IndexMap;
 use serde::ser::SerializeMap;
 use serde::{Serialize, Serializer};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::ops::Deref;
 
 #[derive(Debug)]
 pub struct PrintAsMap {
-    inner: BTreeMap<String, Vec<String>>,
+    // in the order of the paths requested
+    inner: IndexMap<String, Vec<String>>,
+    // keys which are phantom, deleted, live paths
+    phantoms: BTreeSet<String>,
+    // whether the keys are live versions, and not merely the files for which the values were found
+    has_live_keys: bool,
 }
 
 impl Deref for PrintAsMap {
-    type Target = BTreeMap<String, Vec<String>>;
+    type Target = IndexMap<String, Vec<String>>;
 
     fn deref(&self) -> &Self::Target {
         &self.inner
@@ -41,7 +50,17 @@ impl Deref for PrintAsMap {
 
 impl From<BTreeMap<String, Vec<String>>> for PrintAsMap {
     fn from(map: BTreeMap<String, Vec<String>>) -> Self {
-        Self { inner: map }
+        Self::from(map.into_iter().collect::<IndexMap<String, Vec<String>>>())
+    }
+}
+
+impl From<IndexMap<String, Vec<String>>> for PrintAsMap {
+    fn from(map: IndexMap<String, Vec<String>>) -> Self {
+        Self {
+            inner: map,
+            phantoms: BTreeSet::new(),
+            has_live_keys: true,
+        }
     }
 }
 
@@ -50,43 +69,230 @@ impl Serialize for PrintAsMap {
     where
         S: Serializer,
     {
+        let values_name = Self::values_name();
+
         let mut state = serializer.serialize_map(Some(self.inner.len()))?;
-        self.inner
-            .iter()
-            .try_for_each(|(k, v)| state.serialize_entry(k, v))?;
+        self.inner.iter().try_for_each(|(k, v)| {
+            state.serialize_entry(
+                k,
+                &PrintAsMapEntry {
+                    is_phantom: self.phantoms.contains(k),
+                    values_name,
+                    values: v,
+                },
+            )
+        })?;
+        state.end()
+    }
+}
+
+struct PrintAsMapEntry<'a> {
+    is_phantom: bool,
+    values_name: &'static str,
+    values: &'a [String],
+}
+
+impl<'a> Serialize for PrintAsMapEntry<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(2))?;
+        state.serialize_entry("is_phantom", &self.is_phantom)?;
+        state.serialize_entry(self.values_name, self.values)?;
+        state.end()
+    }
+}
+
+// bump whenever the structure of the JSON output changes, so downstream parsers may detect the change
+pub const JSON_SCHEMA_VERSION: u64 = 3;
+
+// the top level JSON object, its values named for what they are in each exec mode
+pub struct JsonOutput<'a, T: Serialize + ?Sized> {
+    values_name: &'static str,
+    values: &'a T,
+}
+
+impl<'a, T: Serialize + ?Sized> JsonOutput<'a, T> {
+    pub fn new(values_name: &'static str, values: &'a T) -> Self {
+        Self {
+            values_name,
+            values,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        let s = match GLOBAL_CONFIG.json_format {
+            JsonFormat::Compact => serde_json::to_string(self)?,
+            JsonFormat::Pretty => serde_json::to_string_pretty(self)?,
+        };
+
+        let delimiter = delimiter();
+
+        Ok(format!("{s}{delimiter}"))
+    }
+}
+
+impl<'a, T: Serialize + ?Sized> Serialize for JsonOutput<'a, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(2))?;
+        state.serialize_entry("schema_version", &JSON_SCHEMA_VERSION)?;
+        state.serialize_entry(self.values_name, self.values)?;
+        state.end()
+    }
+}
+
+pub fn json_output_schema(values_names: &[&str], values_ref: &str) -> serde_json::Value {
+    let mut properties = serde_json::Map::new();
+
+    properties.insert(
+        "schema_version".to_owned(),
+        serde_json::json!({ "type": "integer", "const": JSON_SCHEMA_VERSION }),
+    );
+
+    values_names.iter().for_each(|values_name| {
+        properties.insert(
+            (*values_name).to_owned(),
+            serde_json::json!({ "$ref": values_ref }),
+        );
+    });
+
+    serde_json::json!({
+        "type": "object",
+        "properties": properties,
+        "required": ["schema_version"],
+        "minProperties": 2,
+        "maxProperties": 2,
+        "additionalProperties": false
+    })
+}
+
+// one self-contained JSON object, for each key, on each line of newline delimited JSON
+pub struct JsonLine<'a, T: Serialize> {
+    path: &'a str,
+    is_phantom: bool,
+    values_name: &'static str,
+    values: &'a [T],
+}
+
+impl<'a, T: Serialize> JsonLine<'a, T> {
+    pub fn new(
+        path: &'a str,
+        is_phantom: bool,
+        values_name: &'static str,
+        values: &'a [T],
+    ) -> Self {
+        Self {
+            path,
+            is_phantom,
+            values_name,
+            values,
+        }
+    }
+
+    pub fn to_line(&self) -> String {
+        match serde_json::to_string(self) {
+            Ok(s) => format!("{s}\n"),
+            Err(error) => {
+                eprintln!("Error: {error}");
+                std::process::exit(1)
+            }
+        }
+    }
+}
+
+impl<'a, T: Serialize> Serialize for JsonLine<'a, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(3))?;
+        state.serialize_entry("path", self.path)?;
+        state.serialize_entry("is_phantom", &self.is_phantom)?;
+        state.serialize_entry(self.values_name, self.values)?;
         state.end()
     }
 }
 
+pub const PRINT_AS_MAP_VALUES_NAMES: [&str; 7] = [
+    "mounts",
+    "snapshot_names",
+    "age_secs",
+    "paths",
+    "last_snap",
+    "common_snap",
+    "values",
+];
+
+impl PrintAsMap {
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "description": "map of each input path to whether it is a phantom, deleted, live path, and a list of strings: its mounts, its snapshot names, or its last snapshot",
+            "type": "object",
+            "additionalProperties": {
+                "type": "object",
+                "properties": {
+                    "is_phantom": { "type": "boolean" }
+                },
+                "additionalProperties": {
+                    "type": "array",
+                    "items": { "type": "string" }
+                },
+                "required": ["is_phantom"],
+                "minProperties": 2,
+                "maxProperties": 2
+            }
+        })
+    }
+}
+
 impl<'a> From<&MountsForFiles<'a>> for PrintAsMap {
     fn from(mounts_for_files: &MountsForFiles) -> Self {
         let mount_display = mounts_for_files.mount_display();
 
-        let inner = mounts_for_files
+        let inner: IndexMap<String, Vec<String>> = mounts_for_files
             .iter()
             .map(|prox| {
                 let pathdata = prox.pathdata;
 
                 let res = prox
                     .datasets_of_interest()
-                    .map(PathData::from)
-                    .filter_map(|mount| match &ZfsSnapPathGuard::new(prox.pathdata) {
-                        Some(spg) => mount_display.display(spg, &mount),
-                        None => mount_display.display(pathdata, &mount),
+                    .filter_map(|dataset| {
+                        let mount = PathData::from(dataset);
+
+                        let display_path = match &ZfsSnapPathGuard::new(prox.pathdata) {
+                            Some(spg) => mount_display.display(spg, &mount),
+                            None => mount_display.display(pathdata, &mount),
+                        }?;
+
+                        let display_string = display_path.to_string_lossy().to_string();
+
+                        if !GLOBAL_CONFIG.opt_with_usage {
+                            return Some(display_string);
+                        }
+
+                        match DatasetUsage::display(dataset) {
+                            Some(usage) => Some(format!("{display_string} ({usage})")),
+                            None => Some(display_string),
+                        }
                     })
-                    .map(|path| path.to_string_lossy().to_string())
                     .collect();
 
                 (pathdata.path().to_string_lossy().to_string(), res)
             })
             .collect();
-        Self { inner }
+        Self::from(inner)
+            .with_phantoms(mounts_for_files.iter().map(|prox| prox.pathdata))
+            .without_live_keys()
     }
 }
 
 impl From<&VersionsMap> for PrintAsMap {
     fn from(map: &VersionsMap) -> Self {
-        let inner = map
+        let inner: IndexMap<String, Vec<String>> = map
             .iter()
             .map(|(key, values)| {
                 let res = values
@@ -96,17 +302,17 @@ impl From<&VersionsMap> for PrintAsMap {
                 (key.path().to_string_lossy().to_string(), res)
             })
             .collect();
-        Self { inner }
+        Self::from(inner).with_phantoms(map.live_paths())
     }
 }
 
 impl From<&SnapNameMap> for PrintAsMap {
     fn from(map: &SnapNameMap) -> Self {
-        let inner = map
+        let inner: IndexMap<String, Vec<String>> = map
             .iter()
             .map(|(key, value)| (key.path().to_string_lossy().to_string(), value.clone()))
             .collect();
-        Self { inner }
+        Self::from(inner).with_phantoms(map.keys())
     }
 }
 
@@ -119,21 +325,69 @@ impl std::string::ToString for PrintAsMap {
         let delimiter = delimiter();
 
         match &GLOBAL_CONFIG.print_mode {
-            PrintMode::RawNewline | PrintMode::RawZero => {
-                self.values()
-                    .flatten()
-                    .fold(String::new(), |mut buffer, value| {
+            // each value is paired with its key, key first, then value, one pair per value,
+            // so a consumer reading two NUL delimited fields at a time never loses which
+            // mount or snapshot belongs to which file
+            PrintMode::RawZero => self
+                .iter()
+                .fold(String::new(), |mut buffer, (key, values)| {
+                    values.iter().for_each(|value| {
+                        buffer.push_str(key);
+                        buffer.push(delimiter);
                         buffer.push_str(value);
                         buffer.push(delimiter);
-                        buffer
-                    })
-            }
+                    });
+
+                    buffer
+                }),
+            PrintMode::RawNewline => self.raw_newline(
+                DisplaySetType::IsLive.is_displayed(&GLOBAL_CONFIG),
+                delimiter,
+            ),
             PrintMode::FormattedDefault | PrintMode::FormattedNotPretty => self.format(),
         }
     }
 }
 
 impl PrintAsMap {
+    // mark those keys which are phantom, deleted, live paths
+    pub fn with_phantoms<'b>(mut self, path_set: impl Iterator<Item = &'b PathData>) -> Self {
+        self.phantoms.extend(
+            path_set
+                .filter(|pathdata| pathdata.opt_metadata().is_none())
+                .map(|pathdata| pathdata.path().to_string_lossy().to_string()),
+        );
+
+        self
+    }
+
+    // the keys of a mounts map are the files for which mounts were found, never live versions
+    pub fn without_live_keys(mut self) -> Self {
+        self.has_live_keys = false;
+
+        self
+    }
+
+    // as with versions, the live path key is only displayed if requested
+    fn raw_newline(&self, is_live_requested: bool, delimiter: char) -> String {
+        let is_live_displayed = self.has_live_keys && is_live_requested;
+
+        self.iter()
+            .fold(String::new(), |mut buffer, (key, values)| {
+                values.iter().for_each(|value| {
+                    buffer.push_str(value);
+                    buffer.push(delimiter);
+                });
+
+                if is_live_displayed {
+                    buffer.push_str(key);
+                    buffer.push(delimiter);
+                }
+
+                buffer
+            })
+    }
+
     pub fn map_padding(&self) -> usize {
         self.keys().max_by_key(|key| key.len()).map_or_else(
             || QUOTATION_MARKS_LEN,
@@ -142,18 +396,12 @@ impl PrintAsMap {
     }
 
     pub fn to_json(&self) -> String {
-        let res = match GLOBAL_CONFIG.print_mode {
-            PrintMode::FormattedNotPretty | PrintMode::RawNewline | PrintMode::RawZero => {
-                serde_json::to_string(&self)
-            }
-            PrintMode::FormattedDefault => serde_json::to_string_pretty(&self),
-        };
+        if GLOBAL_CONFIG.opt_ndjson {
+            return self.to_ndjson();
+        }
 
-        match res {
-            Ok(s) => {
-                let delimiter = delimiter();
-                format!("{s}{delimiter}")
-            }
+        match JsonOutput::new(Self::values_name(), self).to_json() {
+            Ok(s) => s,
             Err(error) => {
                 eprintln!("Error: {error}");
                 std::process::exit(1)
@@ -161,8 +409,32 @@ impl PrintAsMap {
         }
     }
 
+    // keep in sync with PRINT_AS_MAP_VALUES_NAMES
+    fn values_name() -> &'static str {
+        match &GLOBAL_CONFIG.exec_mode {
+            ExecMode::MountsForFiles(_) => "mounts",
+            ExecMode::SnapsForFiles(_) | ExecMode::Prune(_) => "snapshot_names",
+            ExecMode::StaleThan(_) => "age_secs",
+            ExecMode::CompareDatasets(_, _) => "paths",
+            _ if GLOBAL_CONFIG.opt_last_snap.is_some() => "last_snap",
+            _ if GLOBAL_CONFIG.opt_common_snap => "common_snap",
+            _ => "values",
+        }
+    }
+
+    fn to_ndjson(&self) -> String {
+        let values_name = Self::values_name();
+
+        self.iter()
+            .map(|(key, values)| {
+                JsonLine::new(key, self.phantoms.contains(key), values_name, values).to_line()
+            })
+            .collect()
+    }
+
     pub fn format(&self) -> String {
         let padding = self.map_padding();
+        let is_live_displayed = DisplaySetType::IsLive.is_displayed(&GLOBAL_CONFIG);
 
         let write_out_buffer = self
             .iter()
@@ -174,6 +446,20 @@ impl PrintAsMap {
                 }
             })
             .map(|(key, values)| {
+                // no live path key, so no key labels, only the values
+                if !is_live_displayed {
+                    return values
+                        .iter()
+                        .map(|value| {
+                            if matches!(&GLOBAL_CONFIG.print_mode, PrintMode::FormattedNotPretty) {
+                                format!("{value}\n")
+                            } else {
+                                format!("\"{value}\"\n")
+                            }
+                        })
+                        .collect::<String>();
+                }
+
                 let display_path =
                     if matches!(&GLOBAL_CONFIG.print_mode, PrintMode::FormattedNotPretty) {
                         key.clone()
@@ -211,3 +497,31 @@ impl PrintAsMap {
         write_out_buffer
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> BTreeMap<String, Vec<String>> {
+        BTreeMap::from([("/tank/ds/file".to_owned(), vec!["/tank/ds".to_owned()])])
+    }
+
+    #[test]
+    fn mounts_never_emit_a_live_path_row() {
+        let mounts = PrintAsMap::from(map()).without_live_keys();
+
+        assert_eq!(mounts.raw_newline(true, '\n'), "/tank/ds\n");
+        assert_eq!(mounts.raw_newline(false, '\n'), "/tank/ds\n");
+    }
+
+    #[test]
+    fn versions_emit_a_live_path_row_only_when_requested() {
+        let versions = PrintAsMap::from(map());
+
+        assert_eq!(
+            versions.raw_newline(true, '\n'),
+            "/tank/ds\n/tank/ds/file\n"
+        );
+        assert_eq!(versions.raw_newline(false, '\n'), "/tank/ds\n");
+    }
+}
@@ -0,0 +1,37 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use crate::library::utility::delimiter;
+use crate::VersionsDisplayWrapper;
+
+impl<'a> VersionsDisplayWrapper<'a> {
+    // count first, so the path, which may contain tabs or spaces, is always the remainder of the line
+    pub fn format_as_count_only(&self) -> String {
+        let delimiter = delimiter();
+
+        self.iter()
+            .map(|(live_version, snaps)| {
+                format!(
+                    "{}\t{}{}",
+                    snaps.len(),
+                    live_version.path().display(),
+                    delimiter
+                )
+            })
+            .collect()
+    }
+}
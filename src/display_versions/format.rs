@@ -15,16 +15,19 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use crate::config::generate::{BulkExclusion, Config, PrintMode};
+use crate::config::generate::{BulkExclusion, Config, PrintMode, SortBy};
 use crate::data::paths::{PathData, PHANTOM_DATE, PHANTOM_SIZE};
 use crate::library::utility::{
-    date_string, delimiter, display_human_size, paint_string, DateFormat,
+    date_string, delimiter, display_human_size, humanize_path, paint_string, relative_date_string,
+    DateFormat,
 };
-use crate::lookup::versions::ProximateDatasetAndOptAlts;
-use crate::parse::mounts::IsFilterDir;
-use crate::VersionsDisplayWrapper;
+use crate::lookup::versions::{ProximateDatasetAndOptAlts, VersionsMap};
+use crate::parse::mounts::{FilesystemType, IsFilterDir};
+use crate::{VersionsDisplayWrapper, GLOBAL_CONFIG};
 use std::borrow::Cow;
 use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use terminal_size::{terminal_size, Height, Width};
 
 // 2 space wide padding - used between date and size, and size and path
@@ -39,10 +42,12 @@ pub const QUOTATION_MARKS_LEN: usize = 2;
 impl<'a> VersionsDisplayWrapper<'a> {
     pub fn format(&self) -> String {
         let keys: Vec<&PathData> = self.keys().collect();
-        let values: Vec<&PathData> = self.values().flatten().collect();
+        let mut values: Vec<&PathData> = self.values().flatten().collect();
+        DisplaySet::sort_versions(self.config, &mut values);
 
         let global_display_set = DisplaySet::from((keys, values));
-        let padding_collection = PaddingCollection::new(self.config, &global_display_set);
+        let padding_collection =
+            PaddingCollection::new(self.config, &global_display_set, &self.map);
 
         // if a single instance immediately return the global we already prepared
         if matches!(
@@ -50,20 +55,40 @@ impl<'a> VersionsDisplayWrapper<'a> {
             PrintMode::FormattedDefault | PrintMode::FormattedNotPretty
         ) && self.len() == 1
         {
-            return global_display_set.format(self.config, &padding_collection);
+            let mut buffer = global_display_set.format(self.config, &padding_collection, &self.map);
+
+            if self.config.opt_summary {
+                buffer += &Self::summary("Summary", &global_display_set[0]);
+            }
+
+            return buffer;
         }
 
+        let opt_grand_total = (self.config.opt_summary
+            && matches!(
+                self.config.print_mode,
+                PrintMode::FormattedDefault | PrintMode::FormattedNotPretty
+            ))
+        .then(|| Self::summary("Grand total", &global_display_set[0]));
+
         // else re compute for each instance and print per instance, now with uniform padding
         self.iter()
             .map(|(key, values)| {
                 let keys: Vec<&PathData> = vec![key];
-                let values: Vec<&PathData> = values.iter().collect();
+                let mut values: Vec<&PathData> = values.iter().collect();
+                DisplaySet::sort_versions(self.config, &mut values);
 
                 let display_set = DisplaySet::from((keys, values));
 
                 match &self.config.print_mode {
+                    PrintMode::FormattedDefault | PrintMode::FormattedNotPretty
+                        if self.config.opt_summary =>
+                    {
+                        display_set.format(self.config, &padding_collection, &self.map)
+                            + &Self::summary("Summary", &display_set[0])
+                    }
                     PrintMode::FormattedDefault | PrintMode::FormattedNotPretty => {
-                        display_set.format(self.config, &padding_collection)
+                        display_set.format(self.config, &padding_collection, &self.map)
                     }
                     PrintMode::RawNewline | PrintMode::RawZero => {
                         let delimiter = delimiter();
@@ -75,7 +100,7 @@ impl<'a> VersionsDisplayWrapper<'a> {
                                 (DisplaySetType::from(idx), snap_or_live_set)
                             })
                             .filter(|(display_set_type, _snap_or_live_set)| {
-                                display_set_type.filter_bulk_exclusions(self.config)
+                                display_set_type.is_displayed(self.config)
                             })
                             .flat_map(|(_idx, snap_or_live_set)| snap_or_live_set)
                             .fold(String::new(), |mut buffer, pathdata| {
@@ -86,8 +111,25 @@ impl<'a> VersionsDisplayWrapper<'a> {
                     }
                 }
             })
+            // a grand total is only interesting when there is more than one live file
+            .chain(opt_grand_total)
             .collect::<String>()
     }
+
+    fn summary(label: &str, snaps: &[&PathData]) -> String {
+        let total_size: u64 = snaps
+            .iter()
+            .map(|pathdata| pathdata.metadata_infallible().size())
+            .sum();
+
+        let num_versions = snaps.len();
+        let plural = if num_versions == 1 { "" } else { "s" };
+
+        format!(
+            "{label}: {num_versions} unique version{plural}, {} total\n",
+            display_human_size(total_size)
+        )
+    }
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -129,6 +171,30 @@ impl From<usize> for DisplaySetType {
 }
 
 impl DisplaySetType {
+    // one rule for whether a set is displayed, shared by every display of live paths,
+    // see also PrintAsMap
+    #[inline]
+    pub fn is_displayed(&self, config: &Config) -> bool {
+        match config.print_mode {
+            PrintMode::RawNewline | PrintMode::RawZero => self.filter_raw_exclusions(config),
+            PrintMode::FormattedDefault | PrintMode::FormattedNotPretty => {
+                self.filter_bulk_exclusions(config)
+            }
+        }
+    }
+
+    // raw modes display snapshot locations only, unless live versions are requested
+    #[inline]
+    fn filter_raw_exclusions(&self, config: &Config) -> bool {
+        match &self {
+            DisplaySetType::IsLive => {
+                config.opt_include_live
+                    || matches!(config.opt_bulk_exclusion, Some(BulkExclusion::NoSnap))
+            }
+            DisplaySetType::IsSnap => self.filter_bulk_exclusions(config),
+        }
+    }
+
     #[inline]
     fn filter_bulk_exclusions(&self, config: &Config) -> bool {
         match &self {
@@ -148,23 +214,83 @@ impl DisplaySetType {
 }
 
 impl<'a> DisplaySet<'a> {
-    pub fn format(&self, config: &Config, padding_collection: &PaddingCollection) -> String {
+    // versions arrive in date order, so a stable sort keeps date order among equals
+    fn sort_versions(config: &Config, values: &mut [&'a PathData]) {
+        match config.sort_by {
+            SortBy::Date => {}
+            SortBy::Size => values
+                .sort_by_key(|pathdata| std::cmp::Reverse(pathdata.metadata_infallible().size())),
+            SortBy::Path => values.sort_by(|a, b| a.path().cmp(b.path())),
+        }
+
+        if config.opt_reverse {
+            values.reverse();
+        }
+    }
+
+    pub fn format(
+        &self,
+        config: &Config,
+        padding_collection: &PaddingCollection,
+        versions_map: &VersionsMap,
+    ) -> String {
         let mut border: String = padding_collection.fancy_border_string.to_string();
 
+        // each display set formatted here has only a single live path
+        let opt_source_tags = (config.opt_show_source || config.opt_with_fstype)
+            .then(|| self.inner[1].first().map(|live| SourceTags::new(live)))
+            .flatten();
+
         // get the display buffer for each set snaps and live
         self.iter()
             .enumerate()
             .map(|(idx, snap_or_live_set)| (DisplaySetType::from(idx), snap_or_live_set))
-            .filter(|(display_set_type, _snap_or_live_set)| {
-                display_set_type.filter_bulk_exclusions(config)
-            })
+            .filter(|(display_set_type, _snap_or_live_set)| display_set_type.is_displayed(config))
             .fold(
                 String::new(),
                 |mut display_set_buffer, (display_set_type, snap_or_live_set)| {
                     let mut component_buffer: String = snap_or_live_set
                         .iter()
                         .map(|pathdata| {
-                            pathdata.format(config, &display_set_type, padding_collection)
+                            let opt_source_tag = match display_set_type {
+                                DisplaySetType::IsSnap if config.opt_show_source => opt_source_tags
+                                    .as_ref()
+                                    .and_then(|source_tags| source_tags.tag(pathdata)),
+                                // an empty field keeps the fs type in the same column as the snaps
+                                DisplaySetType::IsLive
+                                    if config.opt_show_source && config.opt_with_fstype =>
+                                {
+                                    Some("")
+                                }
+                                _ => None,
+                            };
+
+                            // always a field, even when unknown, so the columns of each line line up
+                            let opt_fs_type = config.opt_with_fstype.then(|| {
+                                opt_source_tags
+                                    .as_ref()
+                                    .and_then(|source_tags| {
+                                        source_tags.fs_type(pathdata, &display_set_type)
+                                    })
+                                    .unwrap_or("unknown")
+                            });
+
+                            // where else identical versions, omitted from display, were found
+                            let opt_note_tag = match display_set_type {
+                                DisplaySetType::IsSnap => {
+                                    versions_map.note(pathdata).and_then(|note| note.tag())
+                                }
+                                DisplaySetType::IsLive => None,
+                            };
+
+                            pathdata.format(
+                                config,
+                                &display_set_type,
+                                padding_collection,
+                                opt_source_tag,
+                                opt_fs_type,
+                                opt_note_tag.as_deref(),
+                            )
                         })
                         .collect();
 
@@ -209,6 +335,9 @@ impl PathData {
         config: &Config,
         display_set_type: &DisplaySetType,
         padding_collection: &PaddingCollection,
+        opt_source_tag: Option<&str>,
+        opt_fs_type: Option<&str>,
+        opt_note_tag: Option<&str>,
     ) -> String {
         // obtain metadata for timestamp and size
         let metadata = self.metadata_infallible();
@@ -225,7 +354,7 @@ impl PathData {
                 } else {
                     Cow::Borrowed(&padding_collection.phantom_size_pad_str)
                 };
-                let path = self.path().to_string_lossy();
+                let path = self.display_path(config);
                 let padding = NOT_SO_PRETTY_FIXED_WIDTH_PADDING;
                 (size, path, padding)
             }
@@ -244,14 +373,12 @@ impl PathData {
                     ))
                 };
                 let path = {
-                    let path_buf = &self.path();
+                    let display_path = self.display_path(config);
 
                     // paint the live strings with ls colors - idx == 1 is 2nd or live set
                     let painted_path_str = match display_set_type {
-                        DisplaySetType::IsLive => {
-                            paint_string(self, path_buf.to_str().unwrap_or_default())
-                        }
-                        DisplaySetType::IsSnap => path_buf.to_string_lossy(),
+                        DisplaySetType::IsLive => paint_string(self, &display_path),
+                        DisplaySetType::IsSnap => Cow::Borrowed(display_path.as_ref()),
                     };
 
                     Cow::Owned(format!(
@@ -267,19 +394,53 @@ impl PathData {
         };
 
         let display_date = if self.opt_metadata().is_some() {
-            Cow::Owned(date_string(
-                config.requested_utc_offset,
-                &metadata.mtime(),
-                DateFormat::Display,
+            // relative dates vary in width, so always right pad to the widest of the set
+            Cow::Owned(format!(
+                "{:<width$}",
+                padding_collection.display_date(config, &metadata.mtime()),
+                width = padding_collection.date_padding_len
             ))
         } else {
             Cow::Borrowed(&padding_collection.phantom_date_pad_str)
         };
 
-        format!(
-            "{}{}{}{}{}\n",
+        let mut line = format!(
+            "{}{}{}{}{}",
             display_date, display_padding, display_size, display_padding, display_path
-        )
+        );
+
+        // trailing fields, so parsers which ignore trailing columns keep working
+        [opt_source_tag, opt_fs_type, opt_note_tag]
+            .into_iter()
+            .flatten()
+            .for_each(|field| {
+                line += display_padding;
+                line += field;
+            });
+
+        line.push('\n');
+
+        line
+    }
+
+    fn display_path(&self, config: &Config) -> Cow<'_, str> {
+        if let Some(relative) = config
+            .opt_strip_prefix
+            .as_deref()
+            .and_then(|prefix| self.path().strip_prefix(prefix).ok())
+        {
+            if relative.as_os_str().is_empty() {
+                return Cow::Borrowed(".");
+            }
+
+            return relative.to_string_lossy();
+        }
+
+        if config.opt_humanize_paths {
+            return humanize_path(self.path());
+        }
+
+        self.path().to_string_lossy()
     }
 
     fn warning_underlying_snaps<'a>(&'a self, config: &Config) -> &'a str {
@@ -301,6 +462,8 @@ impl PathData {
 }
 
 pub struct PaddingCollection {
+    pub now: SystemTime,
+    pub date_padding_len: usize,
     pub size_padding_len: usize,
     pub fancy_border_string: String,
     pub phantom_date_pad_str: String,
@@ -308,55 +471,89 @@ pub struct PaddingCollection {
 }
 
 impl PaddingCollection {
-    pub fn new(config: &Config, display_set: &DisplaySet) -> PaddingCollection {
-        // calculate padding and borders for display later
-        let (size_padding_len, fancy_border_len) = display_set.iter().flatten().fold(
-            (0usize, 0usize),
-            |(mut size_padding_len, mut fancy_border_len), pathdata| {
-                let metadata = pathdata.metadata_infallible();
-
-                let (display_date, display_size, display_path) = {
-                    let date = date_string(
-                        config.requested_utc_offset,
-                        &metadata.mtime(),
-                        DateFormat::Display,
-                    );
-                    let size = format!(
-                        "{:>width$}",
-                        display_human_size(metadata.size()),
-                        width = size_padding_len
-                    );
-                    let path = pathdata.path().to_string_lossy();
+    pub fn new(
+        config: &Config,
+        display_set: &DisplaySet,
+        versions_map: &VersionsMap,
+    ) -> PaddingCollection {
+        // relative dates are all measured from the same instant, so the widths calculated
+        // here are the widths displayed
+        let now = SystemTime::now();
 
-                    (date, size, path)
-                };
+        // calculate padding and borders for display later
+        let (date_padding_len, size_padding_len, fancy_border_len) =
+            display_set.iter().flatten().fold(
+                (0usize, 0usize, 0usize),
+                |(mut date_padding_len, mut size_padding_len, mut fancy_border_len), pathdata| {
+                    let metadata = pathdata.metadata_infallible();
+
+                    let (display_date, display_size, display_path) = {
+                        let date = Self::date(config, &now, &metadata.mtime());
+                        let size = format!(
+                            "{:>width$}",
+                            display_human_size(metadata.size()),
+                            width = size_padding_len
+                        );
+                        let path = pathdata.display_path(config);
+
+                        (date, size, path)
+                    };
 
-                let display_size_len = display_human_size(metadata.size()).chars().count();
-                let formatted_line_len = display_date.chars().count()
-                    + display_size.chars().count()
-                    + display_path.chars().count()
-                    + PRETTY_FIXED_WIDTH_PADDING_LEN_X2
-                    + QUOTATION_MARKS_LEN;
-
-                size_padding_len = display_size_len.max(size_padding_len);
-                fancy_border_len = formatted_line_len.max(fancy_border_len);
-                (size_padding_len, fancy_border_len)
-            },
-        );
+                    let display_size_len = display_human_size(metadata.size()).chars().count();
+                    let note_tag_len = versions_map
+                        .note(pathdata)
+                        .and_then(|note| note.tag())
+                        .map(|tag| tag.chars().count() + PRETTY_FIXED_WIDTH_PADDING.len())
+                        .unwrap_or_default();
+                    let formatted_line_len = display_date.chars().count()
+                        + display_size.chars().count()
+                        + display_path.chars().count()
+                        + PRETTY_FIXED_WIDTH_PADDING_LEN_X2
+                        + QUOTATION_MARKS_LEN
+                        + note_tag_len;
+
+                    // phantom dates are blanks, and never widen the date column
+                    if pathdata.opt_metadata().is_some() {
+                        date_padding_len = display_date.chars().count().max(date_padding_len);
+                    }
+                    size_padding_len = display_size_len.max(size_padding_len);
+                    fancy_border_len = formatted_line_len.max(fancy_border_len);
+                    (date_padding_len, size_padding_len, fancy_border_len)
+                },
+            );
+
+        // the longest source tag of any live path's datasets widens every line it is appended to
+        let fancy_border_len = if config.opt_show_source {
+            let max_source_tag_len = display_set[1]
+                .iter()
+                .map(|live| SourceTags::new(live).max_len())
+                .max()
+                .unwrap_or_default();
+
+            if max_source_tag_len > 0 {
+                fancy_border_len + max_source_tag_len + PRETTY_FIXED_WIDTH_PADDING.len()
+            } else {
+                fancy_border_len
+            }
+        } else {
+            fancy_border_len
+        };
 
         let fancy_border_string: String = Self::fancy_border_string(fancy_border_len);
 
-        let phantom_date_pad_str = format!(
-            "{:<width$}",
-            "",
-            width = date_string(
+        let phantom_date_pad_len = if config.opt_relative_dates {
+            date_padding_len
+        } else {
+            date_string(
                 config.requested_utc_offset,
                 &PHANTOM_DATE,
-                DateFormat::Display
+                DateFormat::Display,
             )
             .chars()
             .count()
-        );
+        };
+
+        let phantom_date_pad_str = format!("{:<width$}", "", width = phantom_date_pad_len);
         let phantom_size_pad_str = format!(
             "{:<width$}",
             "",
@@ -364,6 +561,8 @@ impl PaddingCollection {
         );
 
         PaddingCollection {
+            now,
+            date_padding_len,
             size_padding_len,
             fancy_border_string,
             phantom_date_pad_str,
@@ -371,6 +570,22 @@ impl PaddingCollection {
         }
     }
 
+    pub fn display_date(&self, config: &Config, system_time: &SystemTime) -> String {
+        Self::date(config, &self.now, system_time)
+    }
+
+    fn date(config: &Config, now: &SystemTime, system_time: &SystemTime) -> String {
+        if config.opt_relative_dates {
+            return relative_date_string(now, system_time);
+        }
+
+        date_string(
+            config.requested_utc_offset,
+            system_time,
+            DateFormat::Display,
+        )
+    }
+
     fn fancy_border_string(fancy_border_len: usize) -> String {
         if let Some((Width(width), Height(_height))) = terminal_size() {
             let width_as_usize = width as usize;
@@ -388,3 +603,99 @@ impl PaddingCollection {
         format!("{:─<fancy_border_len$}\n", "")
     }
 }
+
+type SourceTagEntry = (&'static [PathBuf], String, Option<&'static str>);
+
+// which dataset, the most proximate or a locally replicated alternative, a snapshot version was found upon,
+// and the filesystem type of that dataset
+pub struct SourceTags {
+    inner: Vec<SourceTagEntry>,
+    opt_live_fs_type: Option<&'static str>,
+}
+
+impl SourceTags {
+    fn new(live: &PathData) -> Self {
+        let Ok(prox_opt_alts) = ProximateDatasetAndOptAlts::new(live) else {
+            return Self {
+                inner: Vec::new(),
+                opt_live_fs_type: None,
+            };
+        };
+
+        let inner = prox_opt_alts
+            .datasets_of_interest()
+            .filter_map(|dataset| {
+                let snap_mounts = GLOBAL_CONFIG.dataset_collection.map_of_snaps.get(dataset)?;
+
+                let tag = if dataset == prox_opt_alts.proximate_dataset {
+                    "[local]".to_owned()
+                } else {
+                    format!("[alt:{}]", Self::pool_name(dataset))
+                };
+
+                Some((snap_mounts.as_slice(), tag, Self::dataset_fs_type(dataset)))
+            })
+            .collect();
+
+        Self {
+            inner,
+            opt_live_fs_type: Self::dataset_fs_type(prox_opt_alts.proximate_dataset),
+        }
+    }
+
+    fn dataset_fs_type(dataset: &Path) -> Option<&'static str> {
+        GLOBAL_CONFIG
+            .dataset_collection
+            .map_of_datasets
+            .get(dataset)
+            .map(|md| md.fs_type.as_str())
+    }
+
+    fn fs_type(&self, pathdata: &PathData, display_set_type: &DisplaySetType) -> Option<&str> {
+        match display_set_type {
+            DisplaySetType::IsLive => self.opt_live_fs_type,
+            DisplaySetType::IsSnap => self
+                .find(pathdata)
+                .and_then(|(_snap_mounts, _tag, opt_fs_type)| *opt_fs_type),
+        }
+    }
+
+    fn find(&self, snap: &PathData) -> Option<&SourceTagEntry> {
+        self.inner.iter().find(|(snap_mounts, _tag, _opt_fs_type)| {
+            snap_mounts
+                .iter()
+                .any(|snap_mount| snap.path().starts_with(snap_mount))
+        })
+    }
+
+    fn tag(&self, snap: &PathData) -> Option<&str> {
+        self.find(snap)
+            .map(|(_snap_mounts, tag, _opt_fs_type)| tag.as_str())
+    }
+
+    fn max_len(&self) -> usize {
+        self.inner
+            .iter()
+            .map(|(_snap_mounts, tag, _opt_fs_type)| tag.chars().count())
+            .max()
+            .unwrap_or_default()
+    }
+
+    // a ZFS pool is the first component of its dataset name, otherwise use the whole source
+    fn pool_name(dataset: &Path) -> String {
+        match GLOBAL_CONFIG
+            .dataset_collection
+            .map_of_datasets
+            .get(dataset)
+        {
+            Some(md) if matches!(md.fs_type, FilesystemType::Zfs) => md
+                .source
+                .components()
+                .next()
+                .map(|component| component.as_os_str().to_string_lossy().to_string())
+                .unwrap_or_else(|| md.source.to_string_lossy().to_string()),
+            Some(md) => md.source.to_string_lossy().to_string(),
+            None => dataset.to_string_lossy().to_string(),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use crate::config::generate::PrintMode;
+use crate::display_map::format::PrintAsMap;
+use crate::library::utility::{delimiter, display_human_duration};
+use crate::{VersionsDisplayWrapper, GLOBAL_CONFIG};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
+
+impl<'a> VersionsDisplayWrapper<'a> {
+    pub fn format_as_stale(&self, stale_than: &Duration) -> String {
+        let now = SystemTime::now();
+
+        let mut stale_files: Vec<(String, Duration)> = self
+            .iter()
+            // phantom live versions are deleted files, which we exclude
+            .filter(|(live_version, _snaps)| live_version.opt_metadata().is_some())
+            .filter_map(|(live_version, snaps)| {
+                let newest_snap = snaps
+                    .iter()
+                    .map(|snap| snap.metadata_infallible().mtime())
+                    .max()?;
+
+                // a newest snap from the future is not stale
+                let age = now.duration_since(newest_snap).ok()?;
+
+                if age < *stale_than {
+                    return None;
+                }
+
+                Some((live_version.path().display().to_string(), age))
+            })
+            .collect();
+
+        if stale_files.is_empty() {
+            eprintln!("Notification: No paths with snapshot versions older than the duration specified exist.");
+            return String::new();
+        }
+
+        if GLOBAL_CONFIG.opt_json {
+            let map: BTreeMap<String, Vec<String>> = stale_files
+                .into_iter()
+                .map(|(path, age)| (path, vec![age.as_secs().to_string()]))
+                .collect();
+
+            return PrintAsMap::from(map).to_json();
+        }
+
+        // oldest first
+        stale_files.sort_by_key(|(_path, age)| Reverse(*age));
+
+        let delimiter = delimiter();
+
+        match GLOBAL_CONFIG.print_mode {
+            PrintMode::FormattedDefault => {
+                let padding = stale_files
+                    .iter()
+                    .map(|(path, _age)| path.len())
+                    .max()
+                    .unwrap_or_default();
+
+                stale_files
+                    .iter()
+                    .map(|(path, age)| {
+                        format!(
+                            "{:<width$} : {}{}",
+                            path,
+                            display_human_duration(age),
+                            delimiter,
+                            width = padding
+                        )
+                    })
+                    .collect()
+            }
+            PrintMode::FormattedNotPretty => stale_files
+                .iter()
+                .map(|(path, age)| {
+                    format!("{}\t{}{}", path, display_human_duration(age), delimiter)
+                })
+                .collect(),
+            PrintMode::RawNewline | PrintMode::RawZero => stale_files
+                .iter()
+                .map(|(path, age)| format!("{}\t{}{}", path, age.as_secs(), delimiter))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use crate::config::generate::PrintMode;
+use crate::display_map::format::{JsonLine, JsonOutput};
+use crate::library::utility::{date_string, delimiter, display_human_duration, DateFormat};
+use crate::{VersionsDisplayWrapper, GLOBAL_CONFIG};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
+
+// the intervals between consecutive snapshot versions of a file, to audit whether a snapshot schedule ran as expected
+pub struct VersionStat {
+    is_phantom: bool,
+    num_versions: usize,
+    opt_intervals: Option<Intervals>,
+    gaps: Vec<Gap>,
+}
+
+struct Intervals {
+    min: Duration,
+    median: Duration,
+    max: Duration,
+}
+
+struct Gap {
+    from: SystemTime,
+    to: SystemTime,
+    interval: Duration,
+}
+
+impl VersionStat {
+    // without a threshold, a gap is any interval greater than twice the median interval
+    fn new(
+        is_phantom: bool,
+        mut mtimes: Vec<SystemTime>,
+        opt_gap_threshold: Option<Duration>,
+    ) -> Self {
+        mtimes.sort_unstable();
+
+        let num_versions = mtimes.len();
+
+        let intervals: Vec<(SystemTime, SystemTime, Duration)> = mtimes
+            .windows(2)
+            .map(|pair| {
+                let interval = pair[1].duration_since(pair[0]).unwrap_or_default();
+                (pair[0], pair[1], interval)
+            })
+            .collect();
+
+        let mut sorted: Vec<Duration> = intervals
+            .iter()
+            .map(|(_from, _to, interval)| *interval)
+            .collect();
+        sorted.sort_unstable();
+
+        let opt_intervals = match (sorted.first(), sorted.last()) {
+            (Some(min), Some(max)) => {
+                let mid = sorted.len() / 2;
+
+                let median = if sorted.len().is_multiple_of(2) {
+                    (sorted[mid - 1] + sorted[mid]) / 2
+                } else {
+                    sorted[mid]
+                };
+
+                Some(Intervals {
+                    min: *min,
+                    median,
+                    max: *max,
+                })
+            }
+            _ => None,
+        };
+
+        let opt_threshold = opt_gap_threshold.or_else(|| {
+            opt_intervals
+                .as_ref()
+                .filter(|_intervals| sorted.len() > 1)
+                .map(|intervals| intervals.median * 2)
+        });
+
+        let gaps = match opt_threshold {
+            Some(threshold) => intervals
+                .into_iter()
+                .filter(|(_from, _to, interval)| *interval > threshold)
+                .map(|(from, to, interval)| Gap { from, to, interval })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Self {
+            is_phantom,
+            num_versions,
+            opt_intervals,
+            gaps,
+        }
+    }
+
+    fn date(system_time: &SystemTime) -> String {
+        date_string(
+            GLOBAL_CONFIG.requested_utc_offset,
+            system_time,
+            DateFormat::Display,
+        )
+    }
+
+    fn format(&self, path: &str) -> String {
+        let mut buffer = format!("{path}\n");
+
+        buffer += &format!("  versions        : {}\n", self.num_versions);
+
+        match &self.opt_intervals {
+            Some(intervals) => {
+                buffer += &format!(
+                    "  min interval    : {}\n",
+                    display_human_duration(&intervals.min)
+                );
+                buffer += &format!(
+                    "  median interval : {}\n",
+                    display_human_duration(&intervals.median)
+                );
+                buffer += &format!(
+                    "  max interval    : {}\n",
+                    display_human_duration(&intervals.max)
+                );
+            }
+            None => buffer += "  intervals       : fewer than two snapshot versions\n",
+        }
+
+        self.gaps.iter().for_each(|gap| {
+            buffer += &format!(
+                "  gap             : {} -> {} ({})\n",
+                Self::date(&gap.from),
+                Self::date(&gap.to),
+                display_human_duration(&gap.interval)
+            );
+        });
+
+        buffer
+    }
+
+    // tab delimited, one line per file, durations are in seconds when raw
+    fn format_line(&self, path: &str, is_raw: bool, delimiter: char) -> String {
+        let display = |duration: &Duration| {
+            if is_raw {
+                duration.as_secs().to_string()
+            } else {
+                display_human_duration(duration)
+            }
+        };
+
+        let (min, median, max) = match &self.opt_intervals {
+            Some(intervals) => (
+                display(&intervals.min),
+                display(&intervals.median),
+                display(&intervals.max),
+            ),
+            None => ("-".to_owned(), "-".to_owned(), "-".to_owned()),
+        };
+
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}{}",
+            path,
+            self.num_versions,
+            min,
+            median,
+            max,
+            self.gaps.len(),
+            delimiter
+        )
+    }
+
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "description": "map of each live file path to statistics of the intervals between its snapshot versions, intervals are null when fewer than two versions exist",
+            "type": "object",
+            "additionalProperties": {
+                "type": "object",
+                "properties": {
+                    "is_phantom": { "type": "boolean" },
+                    "num_versions": { "type": "integer" },
+                    "min_secs": { "type": ["integer", "null"] },
+                    "median_secs": { "type": ["integer", "null"] },
+                    "max_secs": { "type": ["integer", "null"] },
+                    "gaps": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "from": { "type": "string" },
+                                "to": { "type": "string" },
+                                "secs": { "type": "integer" }
+                            },
+                            "required": ["from", "to", "secs"]
+                        }
+                    }
+                },
+                "required": ["is_phantom", "num_versions", "min_secs", "median_secs", "max_secs", "gaps"]
+            }
+        })
+    }
+}
+
+impl Serialize for VersionStat {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let secs = |select: fn(&Intervals) -> Duration| {
+            self.opt_intervals
+                .as_ref()
+                .map(|intervals| select(intervals).as_secs())
+        };
+
+        let mut state = serializer.serialize_struct("VersionStat", 6)?;
+
+        state.serialize_field("is_phantom", &self.is_phantom)?;
+        state.serialize_field("num_versions", &self.num_versions)?;
+        state.serialize_field("min_secs", &secs(|intervals| intervals.min))?;
+        state.serialize_field("median_secs", &secs(|intervals| intervals.median))?;
+        state.serialize_field("max_secs", &secs(|intervals| intervals.max))?;
+        state.serialize_field("gaps", &self.gaps)?;
+        state.end()
+    }
+}
+
+impl Serialize for Gap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Gap", 3)?;
+
+        state.serialize_field("from", &VersionStat::date(&self.from))?;
+        state.serialize_field("to", &VersionStat::date(&self.to))?;
+        state.serialize_field("secs", &self.interval.as_secs())?;
+        state.end()
+    }
+}
+
+impl<'a> VersionsDisplayWrapper<'a> {
+    pub fn stats(&self, opt_gap_threshold: &Option<Duration>) -> BTreeMap<String, VersionStat> {
+        self.iter()
+            .map(|(live_version, snaps)| {
+                let mtimes = snaps
+                    .iter()
+                    .map(|snap| snap.metadata_infallible().mtime())
+                    .collect();
+
+                (
+                    live_version.path().display().to_string(),
+                    VersionStat::new(
+                        live_version.opt_metadata().is_none(),
+                        mtimes,
+                        *opt_gap_threshold,
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    pub fn format_as_stat(&self, opt_gap_threshold: &Option<Duration>) -> String {
+        let stats = self.stats(opt_gap_threshold);
+
+        if GLOBAL_CONFIG.opt_ndjson {
+            return stats
+                .iter()
+                .map(|(path, stat)| {
+                    JsonLine::new(path, stat.is_phantom, "stats", std::slice::from_ref(stat))
+                        .to_line()
+                })
+                .collect();
+        }
+
+        if GLOBAL_CONFIG.opt_json {
+            return match JsonOutput::new("stats", &stats).to_json() {
+                Ok(s) => s,
+                Err(error) => {
+                    eprintln!("Error: {error}");
+                    std::process::exit(1)
+                }
+            };
+        }
+
+        let delimiter = delimiter();
+
+        match GLOBAL_CONFIG.print_mode {
+            PrintMode::FormattedDefault => stats
+                .iter()
+                .map(|(path, stat)| stat.format(path))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            PrintMode::FormattedNotPretty => stats
+                .iter()
+                .map(|(path, stat)| stat.format_line(path, false, delimiter))
+                .collect(),
+            PrintMode::RawNewline | PrintMode::RawZero => stats
+                .iter()
+                .map(|(path, stat)| stat.format_line(path, true, delimiter))
+                .collect(),
+        }
+    }
+}
@@ -15,14 +15,14 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use crate::config::generate::{BulkExclusion, Config, ExecMode, PrintMode};
+use crate::config::generate::{BulkExclusion, Config, ExecMode};
 use crate::data::paths::PathData;
-use crate::display_map::format::PrintAsMap;
-use crate::library::utility::delimiter;
-use crate::lookup::versions::VersionsMap;
+use crate::display_map::format::{JsonLine, JsonOutput, PrintAsMap};
+use crate::lookup::versions::{VersionEntry, VersionsMap};
+use indexmap::IndexMap;
 use serde::ser::SerializeMap;
 use serde::{Serialize, Serializer};
-use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::ops::Deref;
 
 pub struct VersionsDisplayWrapper<'a> {
@@ -36,7 +36,13 @@ impl<'a> std::string::ToString for VersionsDisplayWrapper<'a> {
             ExecMode::NumVersions(num_versions_mode) => {
                 self.format_as_num_versions(num_versions_mode)
             }
+            ExecMode::StaleThan(stale_than) => self.format_as_stale(stale_than),
+            ExecMode::Stat(opt_gap_threshold) => self.format_as_stat(opt_gap_threshold),
             _ => {
+                if self.config.opt_count_only {
+                    return self.format_as_count_only();
+                }
+
                 if self.config.opt_last_snap.is_some() {
                     let printable_map = PrintAsMap::from(&self.map);
                     return printable_map.to_string();
@@ -53,7 +59,7 @@ impl<'a> std::string::ToString for VersionsDisplayWrapper<'a> {
 }
 
 impl<'a> Deref for VersionsDisplayWrapper<'a> {
-    type Target = BTreeMap<PathData, Vec<PathData>>;
+    type Target = IndexMap<PathData, Vec<PathData>>;
 
     fn deref(&self) -> &Self::Target {
         &self.map
@@ -66,18 +72,12 @@ impl<'a> VersionsDisplayWrapper<'a> {
     }
 
     pub fn to_json(&self) -> String {
-        let res = match self.config.print_mode {
-            PrintMode::FormattedNotPretty | PrintMode::RawNewline | PrintMode::RawZero => {
-                serde_json::to_string(self)
-            }
-            PrintMode::FormattedDefault => serde_json::to_string_pretty(self),
-        };
+        if self.config.opt_ndjson {
+            return self.to_ndjson();
+        }
 
-        match res {
-            Ok(s) => {
-                let delimiter = delimiter();
-                format!("{s}{delimiter}")
-            }
+        match JsonOutput::new("versions", self).to_json() {
+            Ok(s) => s,
             Err(error) => {
                 eprintln!("Error: {error}");
                 std::process::exit(1)
@@ -86,31 +86,102 @@ impl<'a> VersionsDisplayWrapper<'a> {
     }
 }
 
+impl<'a> VersionsDisplayWrapper<'a> {
+    // add live file key to values if needed before serializing
+    fn with_live_values(&self) -> IndexMap<String, Vec<VersionEntry<'_>>> {
+        let entry = |pathdata| VersionEntry::new(pathdata, self.map.note(pathdata));
+
+        self.iter()
+            .map(|(key, values)| {
+                let snaps = values.iter().map(entry);
+
+                let new_values = match &self.config.opt_bulk_exclusion {
+                    Some(BulkExclusion::NoLive) => snaps.collect(),
+                    Some(BulkExclusion::NoSnap) => vec![entry(key)],
+                    None => snaps.chain(std::iter::once(entry(key))).collect(),
+                };
+
+                (key.path().display().to_string(), new_values)
+            })
+            .collect()
+    }
+
+    fn phantoms(&self) -> BTreeSet<String> {
+        self.keys()
+            .filter(|key| key.opt_metadata().is_none())
+            .map(|key| key.path().display().to_string())
+            .collect()
+    }
+
+    fn to_ndjson(&self) -> String {
+        let phantoms = self.phantoms();
+
+        self.with_live_values()
+            .iter()
+            .map(|(key, values)| {
+                JsonLine::new(key, phantoms.contains(key), "versions", values).to_line()
+            })
+            .collect()
+    }
+
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "description": "map of each live file path to whether it is a phantom, deleted, live path, and its versions, the live file is listed last unless excluded",
+            "type": "object",
+            "additionalProperties": {
+                "type": "object",
+                "properties": {
+                    "is_phantom": { "type": "boolean" },
+                    "versions": {
+                        "type": "array",
+                        "items": { "$ref": "#/$defs/path_data" }
+                    }
+                },
+                "required": ["is_phantom", "versions"],
+                "additionalProperties": false
+            }
+        })
+    }
+}
+
 impl<'a> Serialize for VersionsDisplayWrapper<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        // add live file key to values if needed before serializing
-        let new_map: BTreeMap<String, Vec<PathData>> = self
-            .deref()
-            .clone()
-            .into_iter()
-            .map(|(key, values)| match &self.config.opt_bulk_exclusion {
-                Some(BulkExclusion::NoLive) => (key.path().display().to_string(), values),
-                Some(BulkExclusion::NoSnap) => (key.path().display().to_string(), vec![key]),
-                None => {
-                    let mut new_values = values;
-                    new_values.push(key.clone());
-                    (key.path().display().to_string(), new_values)
-                }
-            })
-            .collect();
+        let new_map = self.with_live_values();
+
+        // each key notes whether it is a phantom, as when the live file is excluded from its versions,
+        // with --no-live, nothing else would
+        let phantoms = self.phantoms();
 
         let mut state = serializer.serialize_map(Some(new_map.len()))?;
-        new_map
-            .iter()
-            .try_for_each(|(k, v)| state.serialize_entry(k, v))?;
+        new_map.iter().try_for_each(|(k, v)| {
+            state.serialize_entry(
+                k,
+                &VersionsDisplayWrapperEntry {
+                    is_phantom: phantoms.contains(k),
+                    versions: v,
+                },
+            )
+        })?;
+        state.end()
+    }
+}
+
+struct VersionsDisplayWrapperEntry<'a> {
+    is_phantom: bool,
+    versions: &'a [VersionEntry<'a>],
+}
+
+impl<'a> Serialize for VersionsDisplayWrapperEntry<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(2))?;
+        state.serialize_entry("is_phantom", &self.is_phantom)?;
+        state.serialize_entry("versions", self.versions)?;
         state.end()
     }
 }
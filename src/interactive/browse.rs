@@ -15,9 +15,9 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use crate::background::recursive::RecursiveSearch;
+use crate::background::recursive::{RecursiveSearch, OPT_NO_HIDDEN};
 use crate::data::paths::PathData;
-use crate::interactive::view_mode::ViewMode;
+use crate::interactive::view_mode::{ViewMode, TOGGLE_HIDDEN_KEY};
 use crate::library::results::{HttmError, HttmResult};
 use crate::GLOBAL_CONFIG;
 use crossbeam_channel::unbounded;
@@ -84,6 +84,21 @@ impl InteractiveBrowse {
     }
 
     fn view(requested_dir: &Path) -> HttmResult<Self> {
+        let mut query = String::new();
+
+        // toggling hidden files requires we re-enumerate, so start the view again, with the same query
+        loop {
+            match Self::view_once(requested_dir, &query)? {
+                ViewResult::Selected(browse_result) => return Ok(browse_result),
+                ViewResult::ToggleHidden(previous_query) => {
+                    OPT_NO_HIDDEN.fetch_xor(true, Ordering::Relaxed);
+                    query = previous_query;
+                }
+            }
+        }
+    }
+
+    fn view_once(requested_dir: &Path, query: &str) -> HttmResult<ViewResult> {
         // prep thread spawn
         let started = Arc::new(AtomicBool::new(false));
         let hangup = Arc::new(AtomicBool::new(false));
@@ -121,6 +136,8 @@ impl InteractiveBrowse {
             .header(Some(&header))
             .multi(opt_multi)
             .regex(false)
+            .query(Some(query))
+            .expect(Some(TOGGLE_HIDDEN_KEY.to_owned()))
             .build()
             .expect("Could not initialized skim options for browse_view");
 
@@ -136,23 +153,36 @@ impl InteractiveBrowse {
                 // hangup the channel so the background recursive search can gracefully cleanup and exit
                 hangup_clone.store(true, Ordering::Relaxed);
 
+                if matches!(&output.final_event, Event::EvActAccept(Some(key)) if key == TOGGLE_HIDDEN_KEY)
+                {
+                    return Ok(ViewResult::ToggleHidden(output.query));
+                }
+
                 #[cfg(feature = "malloc_trim")]
                 #[cfg(target_os = "linux")]
                 #[cfg(target_env = "gnu")]
                 Self::malloc_trim();
 
-                let selected_pathdata: Vec<PathData> = output
+                let mut selected_pathdata: Vec<PathData> = output
                     .selected_items
                     .iter()
                     .map(|item| PathData::from(Path::new(item.output().as_ref())))
                     .collect();
 
-                Ok(Self {
+                // versions are displayed in the order of the paths requested, so in path order here
+                selected_pathdata.sort_unstable();
+
+                Ok(ViewResult::Selected(Self {
                     selected_pathdata,
                     opt_background_handle: Some(background_handle),
-                })
+                }))
             }
             None => Err(HttmError::new("httm interactive file browse session failed.").into()),
         }
     }
 }
+
+enum ViewResult {
+    Selected(InteractiveBrowse),
+    ToggleHidden(String),
+}
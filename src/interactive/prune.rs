@@ -19,9 +19,13 @@ use crate::config::generate::ListSnapsFilters;
 use crate::interactive::view_mode::MultiSelect;
 use crate::interactive::view_mode::ViewMode;
 use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{display_human_size, print_output_buf};
 use crate::lookup::snap_names::SnapNameMap;
 use crate::lookup::versions::VersionsMap;
 use crate::zfs::run_command::RunZFSCommand;
+use crate::zfs::snap_mounts::SnapshotMounts;
+use crate::GLOBAL_CONFIG;
+use std::collections::BTreeMap;
 
 pub struct PruneSnaps;
 
@@ -32,6 +36,11 @@ impl PruneSnaps {
     ) -> HttmResult<()> {
         let snap_name_map: SnapNameMap = SnapNameMap::new(versions_map, opt_filters)?;
 
+        // destroying snapshots is dangerous, so only list what would be destroyed, unless confirmed
+        if GLOBAL_CONFIG.opt_dry_run || !GLOBAL_CONFIG.opt_confirm {
+            return Self::dry_run(&snap_name_map);
+        }
+
         let select_mode = if let Some(filters) = opt_filters {
             filters.select_mode
         } else {
@@ -41,6 +50,64 @@ impl PruneSnaps {
         InteractivePrune::new(&snap_name_map, select_mode)
     }
 
+    fn dry_run(snap_name_map: &SnapNameMap) -> HttmResult<()> {
+        let snapshot_names: Vec<String> = snap_name_map.values().flatten().cloned().collect();
+
+        if snapshot_names.is_empty() {
+            return Err(HttmError::new("httm could not find any snapshots to prune.").into());
+        }
+
+        let run_zfs = RunZFSCommand::new()?;
+
+        // note: destroying many snapshots may reclaim more than the sum of each,
+        // as space shared only between the snapshots destroyed is also freed
+        let map_usage = run_zfs
+            .snapshot_usage(&snapshot_names)
+            .unwrap_or_else(|err| {
+                eprintln!("WARN: {err}");
+                BTreeMap::new()
+            });
+
+        let map_by_dataset = SnapshotMounts::group_snapshot_names(
+            snapshot_names,
+            SnapshotMounts::dataset_from_snap_name,
+        );
+
+        let listing: String = map_by_dataset
+            .iter()
+            .map(|(dataset, snapshot_names)| {
+                let snapshots: String = snapshot_names
+                    .iter()
+                    .map(|snapshot_name| {
+                        let used = map_usage
+                            .get(snapshot_name)
+                            .map(|used| display_human_size(*used))
+                            .unwrap_or_else(|| "unknown".to_owned());
+
+                        format!("\t{snapshot_name}\t{used}\n")
+                    })
+                    .collect();
+
+                format!("{dataset}:\n{snapshots}\n")
+            })
+            .collect();
+
+        let total: u64 = map_usage.values().sum();
+
+        let output_buf = format!(
+            "httm would destroy the following snapshot/s, reclaiming at least the space listed:\n\n\
+            {listing}\
+            Total: {}\n",
+            display_human_size(total)
+        );
+
+        print_output_buf(&output_buf)?;
+
+        eprintln!("NOTICE: No snapshots were destroyed.  httm will only destroy snapshots when CONFIRM is specified.");
+
+        Ok(())
+    }
+
     fn prune(snap_name_map: &SnapNameMap) -> HttmResult<()> {
         let snapshot_names: Vec<String> = snap_name_map.values().flatten().cloned().collect();
 
@@ -22,17 +22,20 @@ use crate::data::paths::ZfsSnapPathGuard;
 use crate::interactive::select::InteractiveSelect;
 use crate::interactive::view_mode::MultiSelect;
 use crate::interactive::view_mode::ViewMode;
-use crate::library::file_ops::Copy;
+use crate::library::file_ops::{Copy, MergeCount};
 use crate::library::results::{HttmError, HttmResult};
-use crate::library::utility::{date_string, DateFormat};
+use crate::library::utility::{date_string, display_human_size, find_common_path, DateFormat};
 use crate::zfs::snap_guard::SnapGuard;
-use crate::GLOBAL_CONFIG;
+use crate::{GLOBAL_CONFIG, ZFS_SNAPSHOT_DIRECTORY};
 
+use nix::sys::statvfs::{statvfs, FsFlags};
 use nu_ansi_term::Color::LightYellow;
 use terminal_size::Height;
 use terminal_size::Width;
 
+use std::fs::read_dir;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 pub struct InteractiveRestore {
     pub snap_path_strings: Vec<String>,
@@ -50,11 +53,108 @@ impl From<InteractiveSelect> for InteractiveRestore {
 
 impl InteractiveRestore {
     pub fn restore(&self) -> HttmResult<()> {
+        // when multiple files are selected, first propose their common live ancestor as the restore root,
+        // if the paths are disjoint, or the user declines, fall back to prompting per file
+        if self.snap_path_strings.len() > 1
+            && !Self::is_overwrite()
+            && GLOBAL_CONFIG.opt_restore_to.is_none()
+        {
+            if let Some(common_ancestor) = self.common_live_ancestor() {
+                if self.restore_to_common_ancestor(&common_ancestor)? {
+                    return Ok(());
+                }
+            }
+        }
+
         self.snap_path_strings
             .iter()
             .try_for_each(|snap_path_string| self.restore_per_path(snap_path_string))
     }
 
+    fn common_live_ancestor(&self) -> Option<PathBuf> {
+        let live_dirs: Vec<PathBuf> = self
+            .snap_path_strings
+            .iter()
+            .map(|snap_path_string| {
+                let snap_pathdata = PathData::from(Path::new(snap_path_string));
+
+                self.opt_live_version(&snap_pathdata)
+                    .ok()
+                    .and_then(|live_path| live_path.parent().map(|parent| parent.to_path_buf()))
+            })
+            .collect::<Option<Vec<PathBuf>>>()?;
+
+        find_common_path(live_dirs)
+    }
+
+    // returns whether the user consented, and the restore was performed
+    fn restore_to_common_ancestor(&self, common_ancestor: &Path) -> HttmResult<bool> {
+        let should_preserve = Self::should_preserve_attributes();
+
+        // each file is restored beside its live version, which lies beneath the common ancestor
+        let pairs = self
+            .snap_path_strings
+            .iter()
+            .map(|snap_path_string| {
+                let snap_pathdata = PathData::from(Path::new(snap_path_string));
+                let live_path = self.opt_live_version(&snap_pathdata)?;
+                let new_file_dir = live_path.parent().unwrap_or(common_ancestor);
+                let new_file_path_buf = Self::restored_file_path(&snap_pathdata, new_file_dir)?;
+
+                Ok((snap_pathdata, new_file_path_buf))
+            })
+            .collect::<HttmResult<Vec<(PathData, PathBuf)>>>()?;
+
+        let pairs_buffer: String = pairs
+            .iter()
+            .map(|(snap_pathdata, new_file_path_buf)| {
+                format!(
+                    "\tsource:\t{:?}\n\
+                    \ttarget:\t{new_file_path_buf:?}\n\n",
+                    snap_pathdata.path()
+                )
+            })
+            .collect();
+
+        let restore_buffer = format!(
+            "httm proposes restoring all {} selected files beneath their common live ancestor:\n\n\
+            \t{common_ancestor:?}\n\n\
+            httm will perform the following copies from snapshot:\n\n\
+            {pairs_buffer}\
+            Before httm performs a restore, it would like your consent. Continue? (YES/NO)\n\
+            Answering NO will instead prompt for each file, restoring each to the working directory.\n\
+            ─────────────────────────────────────────────────────────────────────────────────────────\n\
+            YES\n\
+            NO",
+            pairs.len()
+        );
+
+        loop {
+            let view_mode = ViewMode::Restore;
+
+            let selection = view_mode.view_buffer(&restore_buffer, MultiSelect::Off)?;
+
+            let user_consent = selection
+                .first()
+                .ok_or_else(|| HttmError::new("Could not obtain the first match selected."))?;
+
+            match user_consent.to_ascii_uppercase().as_ref() {
+                "YES" | "Y" => break,
+                "NO" | "N" => return Ok(false),
+                // if not yes or no, then noop and continue to the next iter of loop
+                _ => {}
+            }
+        }
+
+        pairs
+            .iter()
+            .try_for_each(|(snap_pathdata, new_file_path_buf)| {
+                Self::copy_from_snap(snap_pathdata, new_file_path_buf, should_preserve)
+            })?;
+
+        Ok(true)
+    }
+
     fn restore_per_path(&self, snap_path_string: &str) -> HttmResult<()> {
         // build pathdata from selection buffer parsed string
         //
@@ -91,59 +191,11 @@ impl InteractiveRestore {
 
             match user_consent.to_ascii_uppercase().as_ref() {
                 "YES" | "Y" => {
-                    match GLOBAL_CONFIG.exec_mode {
-                        ExecMode::Interactive(InteractiveMode::Restore(
-                            RestoreMode::Overwrite(RestoreSnapGuard::Guarded),
-                        )) => {
-                            let snap_guard: SnapGuard =
-                                SnapGuard::try_from(new_file_path_buf.as_path())?;
-
-                            if let Err(err) = Copy::recursive(
-                                &snap_pathdata.path(),
-                                &new_file_path_buf,
-                                should_preserve,
-                            ) {
-                                let msg = format!(
-                                    "httm restore failed for the following reason: {}.\n\
-                            Attempting roll back to precautionary pre-execution snapshot.",
-                                    err
-                                );
-
-                                eprintln!("{}", msg);
-
-                                snap_guard
-                                    .rollback()
-                                    .map(|_| println!("Rollback succeeded."))?;
-
-                                std::process::exit(1);
-                            }
-                        }
-                        _ => {
-                            if let Err(err) = Copy::recursive(
-                                &snap_pathdata.path(),
-                                &new_file_path_buf,
-                                should_preserve,
-                            ) {
-                                let msg = format!(
-                                    "httm restore failed for the following reason: {}.",
-                                    err
-                                );
-                                return Err(HttmError::new(&msg).into());
-                            }
-                        }
-                    }
-
-                    let result_buffer = format!(
-                        "httm copied from snapshot:\n\n\
-                            \tsource:\t{:?}\n\
-                            \ttarget:\t{new_file_path_buf:?}\n\n\
-                            Restore completed successfully.",
-                        snap_pathdata.path()
-                    );
-
-                    let summary_string = LightYellow.paint(Self::summary_string());
-
-                    break println!("{summary_string}{result_buffer}");
+                    break Self::copy_from_snap(
+                        &snap_pathdata,
+                        &new_file_path_buf,
+                        should_preserve,
+                    )?
                 }
                 "NO" | "N" => {
                     break println!("User declined restore of: {:?}", snap_pathdata.path())
@@ -156,6 +208,349 @@ impl InteractiveRestore {
         Ok(())
     }
 
+    pub fn from_named_snapshot(snap_name: &str) -> HttmResult<Self> {
+        let snap_path_strings = GLOBAL_CONFIG
+            .paths
+            .iter()
+            .map(|pathdata| Self::named_snap_path(pathdata, snap_name))
+            .map(|res| res.map(|snap_path| snap_path.to_string_lossy().into_owned()))
+            .collect::<HttmResult<Vec<String>>>()?;
+
+        Ok(Self {
+            snap_path_strings,
+            opt_live_version: None,
+        })
+    }
+
+    pub fn restore_named_snapshot(&self) -> HttmResult<()> {
+        let should_preserve = Self::should_preserve_attributes();
+
+        // user has already named the snapshot version, so no need to ask for consent
+        self.snap_path_strings
+            .iter()
+            .try_for_each(|snap_path_string| {
+                let snap_pathdata = PathData::from(Path::new(snap_path_string));
+                let new_file_path_buf = self.build_new_file_path(&snap_pathdata)?;
+
+                Self::copy_from_snap(&snap_pathdata, &new_file_path_buf, should_preserve)
+            })
+    }
+
+    fn named_snap_path(pathdata: &PathData, snap_name: &str) -> HttmResult<PathBuf> {
+        let proximate_dataset = pathdata.proximate_dataset()?;
+        let relative_path = pathdata.relative_path(proximate_dataset)?;
+        let snap_dir = proximate_dataset.join(ZFS_SNAPSHOT_DIRECTORY);
+        let snap_mount = snap_dir.join(snap_name);
+
+        if !snap_mount.exists() {
+            let mut available: Vec<String> = read_dir(&snap_dir)
+                .map(|read_dir| {
+                    read_dir
+                        .flatten()
+                        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            available.sort_unstable();
+
+            let msg = format!(
+                "Snapshot named {snap_name:?} could not be found for dataset {proximate_dataset:?}. Available snapshots are: {}",
+                available.join(", ")
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let snap_path = snap_mount.join(relative_path);
+
+        if snap_path.symlink_metadata().is_err() {
+            let msg = format!(
+                "Path {:?} does not exist on snapshot {snap_name:?}. Quitting.",
+                pathdata.path()
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(snap_path)
+    }
+
+    fn copy_from_snap(
+        snap_pathdata: &PathData,
+        new_file_path_buf: &Path,
+        should_preserve: bool,
+    ) -> HttmResult<()> {
+        // check before any precautionary snapshot, backup, or copy is made
+        Self::preflight(snap_pathdata.path(), new_file_path_buf)?;
+
+        if GLOBAL_CONFIG.opt_dry_run {
+            return Self::dry_run(snap_pathdata, new_file_path_buf);
+        }
+
+        // the relative path beneath the requested dir is only created once the user has consented
+        if GLOBAL_CONFIG.opt_restore_to.is_some() {
+            Copy::generate_dst_parent(new_file_path_buf)?;
+        }
+
+        let opt_backup: Option<PathBuf>;
+
+        match GLOBAL_CONFIG.exec_mode {
+            ExecMode::Interactive(InteractiveMode::Restore(RestoreMode::Overwrite(
+                RestoreSnapGuard::Guarded,
+            ))) => {
+                let snap_guard: SnapGuard = SnapGuard::try_from(new_file_path_buf)?;
+
+                // move aside only after the snap guard, so a rollback also restores the live file in place
+                opt_backup = Self::backup_existing(new_file_path_buf)?;
+
+                if let Err(err) =
+                    Self::copy(&snap_pathdata.path(), new_file_path_buf, should_preserve)
+                {
+                    let msg = format!(
+                        "httm restore failed for the following reason: {}.\n\
+                    Attempting roll back to precautionary pre-execution snapshot.",
+                        err
+                    );
+
+                    eprintln!("{}", msg);
+
+                    snap_guard
+                        .rollback()
+                        .map(|_| println!("Rollback succeeded."))?;
+
+                    std::process::exit(1);
+                }
+            }
+            _ => {
+                opt_backup = Self::backup_existing(new_file_path_buf)?;
+
+                if let Err(err) =
+                    Self::copy(&snap_pathdata.path(), new_file_path_buf, should_preserve)
+                {
+                    let msg = match &opt_backup {
+                        Some(backup) => format!("httm restore failed for the following reason: {}.  The previously existing file was moved to: {backup:?}", err),
+                        None => format!("httm restore failed for the following reason: {}.", err),
+                    };
+
+                    return Err(HttmError::new(&msg).into());
+                }
+            }
+        }
+
+        let backup_buffer = match &opt_backup {
+            Some(backup) => format!("\tbackup:\t{backup:?}\n"),
+            None => String::new(),
+        };
+
+        let result_buffer = format!(
+            "httm copied from snapshot:\n\n\
+                \tsource:\t{:?}\n\
+                \ttarget:\t{new_file_path_buf:?}\n\
+                {backup_buffer}\n\
+                Restore completed successfully.",
+            snap_pathdata.path()
+        );
+
+        let summary_string = LightYellow.paint(Self::summary_string());
+
+        println!("{summary_string}{result_buffer}");
+
+        Ok(())
+    }
+
+    fn dry_run(snap_pathdata: &PathData, new_file_path_buf: &Path) -> HttmResult<()> {
+        // prints the precautionary snapshot command only
+        if matches!(
+            GLOBAL_CONFIG.exec_mode,
+            ExecMode::Interactive(InteractiveMode::Restore(RestoreMode::Overwrite(
+                RestoreSnapGuard::Guarded,
+            )))
+        ) {
+            let _snap_guard: SnapGuard = SnapGuard::try_from(new_file_path_buf)?;
+        }
+
+        let result_buffer = format!(
+            "httm would copy from snapshot:\n\n\
+                \tsource:\t{:?}\n\
+                \ttarget:\t{new_file_path_buf:?}\n\n\
+                Dry run completed successfully.  Nothing was restored.",
+            snap_pathdata.path()
+        );
+
+        let summary_string = LightYellow.paint(Self::summary_string());
+
+        println!("{summary_string}{result_buffer}");
+
+        Ok(())
+    }
+
+    // refuse a restore larger than the limit requested, or larger than the space available at the destination
+    fn preflight(src: &Path, dst: &Path) -> HttmResult<()> {
+        Copy::deny_snapshot_dst(src, dst)?;
+
+        // a network snapshot source may be visible, but not readable
+        if let Err(err) = Self::is_readable(src) {
+            let msg = format!(
+                "httm will not restore {src:?}, as the snapshot version could not be read: {err}"
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let total_size = Self::source_size(src);
+
+        if let Some(max_restore_size) = GLOBAL_CONFIG.opt_max_restore_size {
+            if total_size > max_restore_size {
+                let msg = format!(
+                    "httm will not restore {src:?}, as its total size ({}) exceeds the MAX_RESTORE_SIZE specified ({}).",
+                    display_human_size(total_size),
+                    display_human_size(max_restore_size)
+                );
+                return Err(HttmError::new(&msg).into());
+            }
+        }
+
+        // the destination may not yet exist, so check the nearest ancestor which does
+        let Some(existing_ancestor) = dst.ancestors().find(|ancestor| ancestor.exists()) else {
+            return Ok(());
+        };
+
+        Self::deny_readonly(src, dst)?;
+
+        if let Ok(stat) = statvfs(existing_ancestor) {
+            // the widths of these values differ between platforms
+            #[allow(clippy::unnecessary_cast)]
+            let available =
+                (stat.blocks_available() as u64).saturating_mul(stat.fragment_size() as u64);
+
+            if total_size > available {
+                let msg = format!(
+                    "httm will not restore {src:?}, as its total size ({}) exceeds the space available at the destination ({}).",
+                    display_human_size(total_size),
+                    display_human_size(available)
+                );
+                return Err(HttmError::new(&msg).into());
+            }
+        }
+
+        Ok(())
+    }
+
+    // fail up front, rather than midway through a copy
+    fn deny_readonly(src: &Path, dst: &Path) -> HttmResult<()> {
+        let is_readonly = dst
+            .ancestors()
+            .find(|ancestor| ancestor.exists())
+            .and_then(|existing_ancestor| statvfs(existing_ancestor).ok())
+            .is_some_and(|stat| stat.flags().contains(FsFlags::ST_RDONLY));
+
+        if is_readonly {
+            let msg = format!(
+                "httm will not restore {src:?}, as the destination {dst:?} is upon a read-only mount.  \
+                Remount the destination read-write, or choose another destination, and try again."
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(())
+    }
+
+    fn is_readable(src: &Path) -> std::io::Result<()> {
+        let md = src.symlink_metadata()?;
+
+        if md.is_dir() {
+            read_dir(src).map(|_| ())
+        } else if md.is_file() {
+            std::fs::File::open(src).map(|_| ())
+        } else {
+            Ok(())
+        }
+    }
+
+    // never follow symlinks, a link is restored as a link
+    fn source_size(path: &Path) -> u64 {
+        let Ok(md) = path.symlink_metadata() else {
+            return 0;
+        };
+
+        if !md.is_dir() {
+            return md.len();
+        }
+
+        read_dir(path)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| Self::source_size(&entry.path()))
+            .sum()
+    }
+
+    fn copy(src: &Path, dst: &Path, should_preserve: bool) -> HttmResult<()> {
+        if GLOBAL_CONFIG.opt_no_clobber {
+            let mut no_clobber_count = MergeCount::default();
+
+            Copy::no_clobber(src, dst, should_preserve, &mut no_clobber_count)?;
+
+            eprintln!(
+                "httm copied {} file/s from the snapshot, and skipped {} path/s which already existed.",
+                no_clobber_count.copied, no_clobber_count.skipped
+            );
+
+            return Ok(());
+        }
+
+        if !GLOBAL_CONFIG.opt_merge {
+            // single regular files are replaced atomically, never written in place
+            if src.symlink_metadata()?.is_file() {
+                return Copy::atomic(src, dst, should_preserve);
+            }
+
+            return Copy::recursive(src, dst, should_preserve);
+        }
+
+        let mut merge_count = MergeCount::default();
+
+        Copy::merge(src, dst, should_preserve, &mut merge_count)?;
+
+        eprintln!(
+            "httm merge copied {} file/s from the snapshot, and skipped {} live file/s which were newer or the same.",
+            merge_count.copied, merge_count.skipped
+        );
+
+        Ok(())
+    }
+
+    fn backup_existing(new_file_path_buf: &Path) -> HttmResult<Option<PathBuf>> {
+        if !GLOBAL_CONFIG.opt_backup_existing || new_file_path_buf.symlink_metadata().is_err() {
+            return Ok(None);
+        }
+
+        let timestamp = date_string(
+            GLOBAL_CONFIG.requested_utc_offset,
+            &SystemTime::now(),
+            DateFormat::Timestamp,
+        );
+
+        let mut backup = new_file_path_buf.as_os_str().to_os_string();
+        backup.push(".httm-backup-");
+        backup.push(timestamp);
+
+        let backup = PathBuf::from(backup);
+
+        if backup.symlink_metadata().is_ok() {
+            let msg = format!("httm will not overwrite an existing backup: {backup:?}. Quitting.");
+            return Err(HttmError::new(&msg).into());
+        }
+
+        // same dir, same filesystem, so a rename is all we need
+        if let Err(err) = std::fs::rename(new_file_path_buf, &backup) {
+            let msg = format!(
+                "httm could not move the existing file {new_file_path_buf:?} aside: {err}. Quitting."
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(Some(backup))
+    }
+
     fn summary_string() -> String {
         let width = match terminal_size::terminal_size() {
             Some((Width(width), Height(_height))) => width as usize,
@@ -184,12 +579,16 @@ impl InteractiveRestore {
         .ok_or_else(|| HttmError::new("Could not determine a possible live version.").into())
     }
 
-    fn build_new_file_path(&self, snap_pathdata: &PathData) -> HttmResult<PathBuf> {
-        // build new place to send file
-        if matches!(
+    fn is_overwrite() -> bool {
+        matches!(
             GLOBAL_CONFIG.exec_mode,
             ExecMode::Interactive(InteractiveMode::Restore(RestoreMode::Overwrite(_)))
-        ) {
+        )
+    }
+
+    fn build_new_file_path(&self, snap_pathdata: &PathData) -> HttmResult<PathBuf> {
+        // build new place to send file
+        if Self::is_overwrite() {
             // instead of just not naming the new file with extra info (date plus "httm_restored") and shoving that new file
             // into the pwd, here, we actually look for the original location of the file to make sure we overwrite it.
             // so, if you were in /etc and wanted to restore /etc/samba/smb.conf, httm will make certain to overwrite
@@ -198,6 +597,36 @@ impl InteractiveRestore {
             return self.opt_live_version(snap_pathdata);
         }
 
+        if let Some(restore_to) = &GLOBAL_CONFIG.opt_restore_to {
+            return self.restore_to_path(snap_pathdata, restore_to);
+        }
+
+        Self::restored_file_path(snap_pathdata, GLOBAL_CONFIG.pwd.as_path())
+    }
+
+    // recreate the path of the live version, relative to its dataset, beneath the requested dir
+    fn restore_to_path(&self, snap_pathdata: &PathData, restore_to: &Path) -> HttmResult<PathBuf> {
+        let live_pathdata = PathData::from(self.opt_live_version(snap_pathdata)?);
+        let proximate_dataset = live_pathdata.proximate_dataset()?;
+        let relative_path = live_pathdata.relative_path(proximate_dataset)?;
+
+        let new_file_path_buf = restore_to.join(relative_path);
+
+        Copy::deny_snapshot_dst(snap_pathdata.path(), &new_file_path_buf)?;
+
+        if new_file_path_buf.symlink_metadata().is_ok() {
+            let msg = format!(
+                "httm will not restore to that file location, as a file with the same path name already exists: {new_file_path_buf:?}. Quitting."
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Self::deny_readonly(snap_pathdata.path(), &new_file_path_buf)?;
+
+        Ok(new_file_path_buf)
+    }
+
+    fn restored_file_path(snap_pathdata: &PathData, new_file_dir: &Path) -> HttmResult<PathBuf> {
         let snap_filename = snap_pathdata
             .path()
             .file_name()
@@ -224,7 +653,6 @@ impl InteractiveRestore {
                 &snap_metadata.mtime(),
                 DateFormat::Timestamp,
             );
-        let new_file_dir = GLOBAL_CONFIG.pwd.as_path();
         let new_file_path_buf: PathBuf = new_file_dir.join(new_filename);
 
         // don't let the user rewrite one restore over another in non-overwrite mode
@@ -16,12 +16,13 @@
 // that was distributed with this source code.
 
 use crate::config::generate::{PrintMode, SelectMode};
+use crate::data::paths::{PathData, PathDeconstruction, ZfsSnapPathGuard};
 use crate::display_versions::wrapper::VersionsDisplayWrapper;
 use crate::interactive::preview::PreviewSelection;
 use crate::interactive::view_mode::MultiSelect;
-use crate::interactive::view_mode::ViewMode;
+use crate::interactive::view_mode::{quoted_path, ViewMode};
 use crate::library::results::{HttmError, HttmResult};
-use crate::library::utility::{delimiter, print_output_buf};
+use crate::library::utility::{delimiter, diff_metadata, print_output_buf};
 use crate::lookup::versions::VersionsMap;
 use crate::Config;
 use crate::GLOBAL_CONFIG;
@@ -74,7 +75,10 @@ impl TryFrom<&mut InteractiveBrowse> for InteractiveSelect {
             Self::last_snap(&versions_map)
         } else {
             // same stuff we do at fn exec, snooze...
-            let display_config = Config::from(interactive_browse.selected_pathdata.clone());
+            let mut display_config = Config::from(interactive_browse.selected_pathdata.clone());
+
+            // select parses, and copies, full snapshot paths from this display, never "~" paths
+            display_config.opt_humanize_paths = false;
 
             let display_map = VersionsDisplayWrapper::from(&display_config, versions_map);
 
@@ -100,18 +104,13 @@ impl TryFrom<&mut InteractiveBrowse> for InteractiveSelect {
 
                 let requested_file_names = selected_line
                     .iter()
-                    .filter_map(|selection| {
-                        // ... we want everything between the quotes
-                        selection
-                            .split_once("\"")
-                            .and_then(|(_lhs, rhs)| rhs.rsplit_once("\""))
-                            .map(|(lhs, _rhs)| lhs)
-                    })
+                    .filter_map(|selection| quoted_path(selection))
                     .filter(|selection_buffer| {
                         // and cannot select a 'live' version or other invalid value.
                         display_map
-                            .keys()
-                            .all(|key| key.path() != Path::new(selection_buffer))
+                            .map
+                            .versions_for(Path::new(selection_buffer))
+                            .is_none()
                     })
                     .map(|selection_buffer| selection_buffer.to_string())
                     .collect::<Vec<String>>();
@@ -235,6 +234,65 @@ impl InteractiveSelect {
                     },
                 }
             }
+            SelectMode::Diff => self.diff(snap_path),
+        }
+    }
+
+    fn diff(&self, snap_path: &Path) -> HttmResult<()> {
+        let live_path = match &self.opt_live_version {
+            Some(live_version) => Some(PathBuf::from(live_version)),
+            None => ZfsSnapPathGuard::new(&PathData::from(snap_path))
+                .and_then(|snap_guard| snap_guard.live_path()),
+        }
+        .ok_or_else(|| HttmError::new("Could not determine a possible live version."))?;
+
+        let path_diff = diff_metadata(&snap_path, &live_path.as_path());
+
+        if path_diff.is_dst_missing {
+            let output_buf = format!(
+                "No live version exists, so the snapshot version would be a new file: {:?}\n",
+                live_path
+            );
+            return print_output_buf(&output_buf);
+        }
+
+        // summarize the metadata, before the differ output, which may be empty
+        let output_buf = format!("Since the snapshot version: {}\n", path_diff.summary());
+        print_output_buf(&output_buf)?;
+
+        let differ = std::env::var("HTTM_DIFFER").unwrap_or_else(|_| "diff -u".to_owned());
+
+        let mut differ_args = differ.split_whitespace();
+
+        let Some(differ_command) = differ_args.next() else {
+            return Err(HttmError::new("The differ specified via HTTM_DIFFER is empty.").into());
+        };
+
+        let Ok(differ_path) = which::which(differ_command) else {
+            let msg = format!(
+                "httm could not find the differ requested: {:?}.  Please install it, or specify another via HTTM_DIFFER.",
+                differ_command
+            );
+            return Err(HttmError::new(&msg).into());
+        };
+
+        // stream the differ output, rather than buffering it
+        let status = ExecProcess::new(differ_path)
+            .args(differ_args)
+            .arg(snap_path)
+            .arg(&live_path)
+            .status()?;
+
+        // like diff, an exit code of 1 only means the files differ
+        match status.code() {
+            Some(0 | 1) => Ok(()),
+            _ => {
+                let msg = format!(
+                    "The differ {:?} exited with an error ({}) for path: {:?}",
+                    differ_command, status, snap_path
+                );
+                Err(HttmError::new(&msg).into())
+            }
         }
     }
 }
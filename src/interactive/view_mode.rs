@@ -15,12 +15,42 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
+use crate::background::recursive::OPT_NO_HIDDEN;
 use crate::interactive::preview::PreviewSelection;
 use crate::library::results::HttmError;
 use crate::HttmResult;
 use crate::GLOBAL_CONFIG;
 use skim::prelude::*;
-use std::io::Cursor;
+use std::io::{Cursor, Write};
+use std::process::{Command as ExecProcess, Stdio};
+use std::sync::LazyLock;
+use which::which;
+
+const COPY_PATH_KEY: &str = "ctrl-y";
+pub const TOGGLE_HIDDEN_KEY: &str = "alt-h";
+
+// first clipboard tool found is the one we use, with its args
+static OPT_CLIPBOARD_COMMAND: LazyLock<Option<(&'static str, &'static [&'static str])>> =
+    LazyLock::new(|| {
+        let is_wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+
+        [
+            ("wl-copy", &[][..]),
+            ("xclip", &["-selection", "clipboard"][..]),
+            ("xsel", &["--clipboard", "--input"][..]),
+            ("pbcopy", &[][..]),
+        ]
+        .into_iter()
+        .filter(|(executable, _args)| is_wayland || *executable != "wl-copy")
+        .find(|(executable, _args)| which(executable).is_ok())
+    });
+
+// ... we want everything between the quotes
+pub fn quoted_path(line: &str) -> Option<&str> {
+    line.split_once("\"")
+        .and_then(|(_lhs, rhs)| rhs.rsplit_once("\""))
+        .map(|(lhs, _rhs)| lhs)
+}
 
 pub enum ViewMode {
     Browse,
@@ -29,6 +59,7 @@ pub enum ViewMode {
     Prune,
 }
 
+#[derive(Clone, Copy)]
 pub enum MultiSelect {
     On,
     Off,
@@ -40,11 +71,37 @@ impl ViewMode {
             "PREVIEW UP: shift+up | PREVIEW DOWN: shift+down | {}\n\
         PAGE UP:    page up  | PAGE DOWN:    page down \n\
         EXIT:       esc      | SELECT:       enter      | SELECT, MULTIPLE: shift+tab\n\
+        {}{}\
         ──────────────────────────────────────────────────────────────────────────────",
-            self.print_mode()
+            self.print_mode(),
+            self.print_copy_path(),
+            self.print_toggle_hidden()
         )
     }
 
+    fn print_toggle_hidden(&self) -> String {
+        match self {
+            ViewMode::Browse if OPT_NO_HIDDEN.load(Ordering::Relaxed) => {
+                format!("SHOW HIDDEN: {TOGGLE_HIDDEN_KEY}\n")
+            }
+            ViewMode::Browse => format!("HIDE HIDDEN: {TOGGLE_HIDDEN_KEY}\n"),
+            ViewMode::Select(_) | ViewMode::Restore | ViewMode::Prune => String::new(),
+        }
+    }
+
+    fn print_copy_path(&self) -> String {
+        match self {
+            ViewMode::Select(_) if OPT_CLIPBOARD_COMMAND.is_some() => {
+                format!("COPY PATH:  {COPY_PATH_KEY}\n")
+            }
+            ViewMode::Select(_) => {
+                "COPY PATH:  unavailable, no clipboard tool (wl-copy, xclip, xsel, pbcopy) found\n"
+                    .to_owned()
+            }
+            ViewMode::Browse | ViewMode::Restore | ViewMode::Prune => String::new(),
+        }
+    }
+
     fn print_mode(&self) -> &str {
         match self {
             ViewMode::Browse => "====> [ Browse Mode ] <====",
@@ -55,15 +112,91 @@ impl ViewMode {
     }
 
     pub fn view_buffer(&self, buffer: &str, opt_multi: MultiSelect) -> HttmResult<Vec<String>> {
+        let mut query = String::new();
+        let mut opt_copied: Option<String> = None;
+
+        // copying a path returns us from the view, so start the view again, with the same query
+        loop {
+            match self.view_buffer_once(buffer, opt_multi, &query, opt_copied.as_deref())? {
+                ViewBufferResult::Selected(selected) => return Ok(selected),
+                ViewBufferResult::CopyPath(lines, previous_query) => {
+                    let copied = match Self::copy_paths(&lines) {
+                        Ok(paths) => format!("COPIED:     {paths}"),
+                        Err(err) => format!("COPY FAILED: {err}"),
+                    };
+
+                    opt_copied = Some(copied);
+                    query = previous_query;
+                }
+            }
+        }
+    }
+
+    // the paths are parsed from the display lines, and written directly to the clipboard tool's stdin,
+    // never via a shell, so, whatever its characters, the path copied is exactly the snapshot path
+    fn copy_paths(lines: &[String]) -> HttmResult<String> {
+        let Some((program, args)) = *OPT_CLIPBOARD_COMMAND else {
+            return Err(
+                HttmError::new("No clipboard tool (wl-copy, xclip, xsel, pbcopy) found.").into(),
+            );
+        };
+
+        let paths: Vec<&str> = lines.iter().filter_map(|line| quoted_path(line)).collect();
+
+        if paths.is_empty() {
+            return Err(HttmError::new("No snapshot path found to copy.").into());
+        }
+
+        let joined = paths.join("\n");
+
+        let mut child = ExecProcess::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        // dropping stdin closes the pipe, so the clipboard tool may finish
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(joined.as_bytes())?;
+        }
+
+        let status = child.wait()?;
+
+        if !status.success() {
+            let msg = format!("The clipboard tool {program:?} exited with an error ({status}).");
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(joined)
+    }
+
+    fn view_buffer_once(
+        &self,
+        buffer: &str,
+        opt_multi: MultiSelect,
+        query: &str,
+        opt_copied: Option<&str>,
+    ) -> HttmResult<ViewBufferResult> {
         let preview_selection = PreviewSelection::new(&self)?;
 
-        let header = self.print_header();
+        let header = match opt_copied {
+            Some(copied) => format!("{}\n{copied}", self.print_header()),
+            None => self.print_header(),
+        };
 
         let opt_multi = match opt_multi {
             MultiSelect::On => true,
             MultiSelect::Off => false,
         };
 
+        let opt_expect = match self {
+            ViewMode::Select(_) if OPT_CLIPBOARD_COMMAND.is_some() => {
+                Some(COPY_PATH_KEY.to_owned())
+            }
+            ViewMode::Browse | ViewMode::Select(_) | ViewMode::Restore | ViewMode::Prune => None,
+        };
+
         // build our browse view - less to do than before - no previews, looking through one 'lil buffer
         let skim_opts = SkimOptionsBuilder::default()
             .preview_window(preview_selection.opt_preview_window.as_deref())
@@ -77,6 +210,8 @@ impl ViewMode {
             .regex(false)
             .tiebreak(Some("length,index".to_string()))
             .header(Some(&header))
+            .query(Some(query))
+            .expect(opt_expect)
             .build()
             .expect("Could not initialized skim options for select_restore_view");
 
@@ -92,11 +227,20 @@ impl ViewMode {
                 eprintln!("httm select/restore/prune session was aborted.  Quitting.");
                 std::process::exit(0);
             }
-            Some(output) => output
-                .selected_items
-                .iter()
-                .map(|i| i.output().into_owned())
-                .collect(),
+            Some(output) => {
+                let selected: Vec<String> = output
+                    .selected_items
+                    .iter()
+                    .map(|i| i.output().into_owned())
+                    .collect();
+
+                if matches!(&output.final_event, Event::EvActAccept(Some(key)) if key == COPY_PATH_KEY)
+                {
+                    ViewBufferResult::CopyPath(selected, output.query)
+                } else {
+                    ViewBufferResult::Selected(selected)
+                }
+            }
             None => {
                 return Err(HttmError::new("httm select/restore/prune session failed.").into());
             }
@@ -115,3 +259,25 @@ impl ViewMode {
         Ok(res)
     }
 }
+
+enum ViewBufferResult {
+    Selected(Vec<String>),
+    CopyPath(Vec<String>, String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_path_is_everything_between_the_first_and_last_quotes() {
+        let line =
+            r#"Mon Jan 01 00:00:00 2024  1.0 KiB  "/tank/.zfs/snapshot/snap1/a "quoted" file""#;
+
+        assert_eq!(
+            quoted_path(line),
+            Some(r#"/tank/.zfs/snapshot/snap1/a "quoted" file"#)
+        );
+        assert_eq!(quoted_path("no quotes at all"), None);
+    }
+}
@@ -19,17 +19,23 @@ use crate::data::paths::PathData;
 use crate::data::paths::PathDeconstruction;
 use crate::library::diff_copy::HttmCopy;
 use crate::library::results::{HttmError, HttmResult};
-use crate::GLOBAL_CONFIG;
+use crate::library::utility::{make_tmp_path, ComparePathMetadata};
+use crate::parse::mounts::OPT_SNAP_DIR_NAME;
 use crate::IN_BUFFER_SIZE;
+use crate::{BTRFS_SNAPPER_HIDDEN_DIRECTORY, GLOBAL_CONFIG, ZFS_SNAPSHOT_DIRECTORY};
+use indicatif::{ProgressBar, ProgressStyle};
 use nix::sys::stat::SFlag;
-use nu_ansi_term::Color::{Blue, Red};
+use nu_ansi_term::Color::Blue;
 use std::os::unix::fs::chown;
 use std::os::unix::fs::FileTypeExt;
 use std::os::unix::fs::MetadataExt;
 
+use std::cell::Cell;
 use std::fs::{create_dir_all, read_dir, set_permissions};
+use std::io::IsTerminal;
 use std::iter::Iterator;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
 
 const CHAR_KIND: SFlag = nix::sys::stat::SFlag::S_IFCHR;
 const BLK_KIND: SFlag = nix::sys::stat::SFlag::S_IFBLK;
@@ -47,14 +53,64 @@ impl Copy {
         }
     }
 
-    pub fn direct(src: &Path, dst: &Path, should_preserve: bool) -> HttmResult<()> {
-        Self::direct_quiet(src, dst, should_preserve)?;
+    // snapshots are read-only, so writing into one only fails obscurely, midway through a copy,
+    // and a copy of a path onto itself is, at best, a no-op
+    pub fn deny_snapshot_dst(src: &Path, dst: &Path) -> HttmResult<()> {
+        let zfs_snapshot_dir = Path::new(ZFS_SNAPSHOT_DIRECTORY);
+
+        let is_snapshot_dst = dst.ancestors().any(|ancestor| {
+            ancestor.ends_with(zfs_snapshot_dir)
+                || ancestor
+                    .file_name()
+                    .is_some_and(|file_name| file_name == BTRFS_SNAPPER_HIDDEN_DIRECTORY)
+                || OPT_SNAP_DIR_NAME
+                    .get()
+                    .is_some_and(|snap_dir_name| ancestor.ends_with(snap_dir_name))
+        });
+
+        if is_snapshot_dst {
+            let msg = format!(
+                "httm will not copy {src:?}, as the destination {dst:?} is within a snapshot directory.  \
+                Snapshots are read-only.  Choose a destination outside of any snapshot directory, and try again."
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let is_same_path = match (src.canonicalize(), dst.canonicalize()) {
+            (Ok(canonical_src), Ok(canonical_dst)) => canonical_src == canonical_dst,
+            _ => src == dst,
+        };
+
+        if is_same_path {
+            let msg = format!(
+                "httm will not copy {src:?}, as the source and the destination are the same path."
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(())
+    }
+
+    pub fn direct(
+        src: &Path,
+        dst: &Path,
+        dst_root: &Path,
+        should_preserve: bool,
+    ) -> HttmResult<()> {
+        Self::direct_quiet(src, dst, dst_root, should_preserve)?;
         eprintln!("{}: {:?} -> {:?}", Blue.paint("Restored "), src, dst);
 
         Ok(())
     }
 
-    pub fn direct_quiet(src: &Path, dst: &Path, should_preserve: bool) -> HttmResult<()> {
+    // dst_root is the destination the caller means to copy into, such as the restore destination,
+    // beneath which any path in the way of the copy may be removed
+    pub fn direct_quiet(
+        src: &Path,
+        dst: &Path,
+        dst_root: &Path,
+        should_preserve: bool,
+    ) -> HttmResult<()> {
         if src.is_dir() {
             create_dir_all(&dst)?;
         } else {
@@ -62,9 +118,14 @@ impl Copy {
 
             if src.is_file() {
                 HttmCopy::new(&src, &dst)?;
+
+                if GLOBAL_CONFIG.opt_verify {
+                    Self::verify(src, dst)?;
+                }
             } else {
+                // only ever remove the destination itself, and only from beneath the caller's root
                 if dst.exists() {
-                    Remove::recursive_quiet(dst)?;
+                    Remove::recursive_quiet(dst, dst_root)?;
                 }
                 if src.is_symlink() {
                     let link_target = std::fs::read_link(&src)?;
@@ -82,6 +143,80 @@ impl Copy {
         Ok(())
     }
 
+    // copy to a temp path beside the destination, then rename into place, so the live file
+    // is never observed partially written
+    pub fn atomic(src: &Path, dst: &Path, should_preserve: bool) -> HttmResult<()> {
+        Self::deny_snapshot_dst(src, dst)?;
+
+        Self::generate_dst_parent(dst)?;
+
+        let tmp = make_tmp_path(dst);
+
+        if tmp.symlink_metadata().is_ok() {
+            let msg = format!(
+                "Could not restore atomically.  The temporary path for the restore already exists: {:?}",
+                tmp
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        if let Err(err) = Self::direct_quiet(src, &tmp, &tmp, should_preserve) {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(err);
+        }
+
+        // the temp path is beside the destination, and so on the same filesystem
+        if let Err(err) = std::fs::rename(&tmp, dst) {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(err.into());
+        }
+
+        eprintln!("{}: {:?} -> {:?}", Blue.paint("Restored "), src, dst);
+
+        Ok(())
+    }
+
+    fn verify(src: &Path, dst: &Path) -> HttmResult<()> {
+        // sizes are cheap to compare, so only compare bytes when the sizes match
+        let is_same =
+            src.metadata()?.len() == dst.metadata()?.len() && Self::is_same_bytes(src, dst)?;
+
+        if !is_same {
+            let msg = format!(
+                "Verification failed.  The contents of the file restored differ from the snapshot version: {:?} -> {:?}",
+                src, dst
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(())
+    }
+
+    // stream both files in buffer sized chunks, byte for byte, so that, unlike a hash,
+    // any read error on either file is an error, and never a false match
+    pub fn is_same_bytes(src: &Path, dst: &Path) -> HttmResult<bool> {
+        let mut src_reader = BufReader::with_capacity(IN_BUFFER_SIZE, std::fs::File::open(src)?);
+        let mut dst_reader = BufReader::with_capacity(IN_BUFFER_SIZE, std::fs::File::open(dst)?);
+
+        loop {
+            let src_buf = src_reader.fill_buf()?;
+            let dst_buf = dst_reader.fill_buf()?;
+
+            if src_buf.is_empty() && dst_buf.is_empty() {
+                return Ok(true);
+            }
+
+            let len = src_buf.len().min(dst_buf.len());
+
+            if len == 0 || src_buf[..len] != dst_buf[..len] {
+                return Ok(false);
+            }
+
+            src_reader.consume(len);
+            dst_reader.consume(len);
+        }
+    }
+
     fn special_file(src: &Path, dst: &Path) -> HttmResult<()> {
         let src_metadata = src.metadata()?;
         let src_file_type = src_metadata.file_type();
@@ -129,45 +264,332 @@ impl Copy {
     }
 
     pub fn recursive(src: &Path, dst: &Path, should_preserve: bool) -> HttmResult<()> {
+        Self::deny_snapshot_dst(src, dst)?;
+
+        // only draw a progress bar when someone is watching
+        if GLOBAL_CONFIG.opt_progress && std::io::stderr().is_terminal() {
+            let copy_progress = CopyProgress::new(src);
+
+            let res = Self::recursive_inner(src, dst, dst, should_preserve, Some(&copy_progress));
+
+            copy_progress.finish();
+
+            return res;
+        }
+
+        Self::recursive_inner(src, dst, dst, should_preserve, None)
+    }
+
+    fn recursive_inner(
+        src: &Path,
+        dst: &Path,
+        dst_root: &Path,
+        should_preserve: bool,
+        opt_progress: Option<&CopyProgress>,
+    ) -> HttmResult<()> {
+        let copy = |src: &Path, dst: &Path, should_preserve: bool| {
+            Self::direct_with_progress(src, dst, dst_root, should_preserve, opt_progress)
+        };
+
+        // preserve this path and its ancestors
+        let preserve = |src: &Path, dst: &Path| -> HttmResult<()> {
+            // macos likes to fail on the metadata copy
+            match Preserve::recursive(src, dst) {
+                Ok(_) => Ok(()),
+                Err(err) => {
+                    if is_metadata_same(src, dst).is_ok() {
+                        if GLOBAL_CONFIG.opt_debug {
+                            eprintln!("WARN: The OS reports an error that it was unable to copy file metadata for the following reason: {}", err.to_string().trim_end());
+                            eprintln!("NOTICE: This is most likely because such feature is unsupported by this OS.  httm confirms basic file metadata (size and mtime) are the same for transfer: {:?} -> {:?}.", src, dst)
+                        }
+                        return Ok(());
+                    }
+
+                    Err(err)
+                }
+            }
+        };
+
+        Self::post_order(src, dst, should_preserve, &copy, &preserve)
+    }
+
+    fn post_order<C, P>(
+        src: &Path,
+        dst: &Path,
+        should_preserve: bool,
+        copy: &C,
+        preserve: &P,
+    ) -> HttmResult<()>
+    where
+        C: Fn(&Path, &Path, bool) -> HttmResult<()>,
+        P: Fn(&Path, &Path) -> HttmResult<()>,
+    {
         if src.is_dir() {
-            Self::direct(src, dst, should_preserve)?;
+            // only create the dir here, as copying each entry into the dir would modify the dir's
+            // mtime, its attributes are preserved post-order, below, after all its entries are copied
+            copy(src, dst, false)?;
 
             for entry in read_dir(&src)?.flatten() {
                 let file_type = entry.file_type()?;
                 let entry_src = entry.path();
                 let entry_dst = dst.join(entry.file_name());
 
+                // each file's attributes are preserved from the file itself, as it is copied,
+                // and each dir's attributes, from the dir itself, once its recursion returns
                 if entry_src.exists() {
                     if file_type.is_dir() {
-                        Self::recursive(&entry_src, &entry_dst, should_preserve)?;
+                        Self::post_order(&entry_src, &entry_dst, should_preserve, copy, preserve)?;
                     } else {
-                        Self::direct(&entry_src, &entry_dst, should_preserve)?;
+                        copy(&entry_src, &entry_dst, should_preserve)?;
                     }
                 }
             }
         } else {
-            Self::direct(&src, dst, should_preserve)?;
+            copy(src, dst, should_preserve)?;
         }
 
+        // post-order, after all entries are copied
         if should_preserve {
-            // macos likes to fail on the metadata copy
-            match Preserve::recursive(src, dst) {
-                Ok(_) => {}
-                Err(err) => {
-                    if is_metadata_same(src, dst).is_ok() {
-                        if GLOBAL_CONFIG.opt_debug {
-                            eprintln!("WARN: The OS reports an error that it was unable to copy file metadata for the following reason: {}", err.to_string().trim_end());
-                            eprintln!("NOTICE: This is most likely because such feature is unsupported by this OS.  httm confirms basic file metadata (size and mtime) are the same for transfer: {:?} -> {:?}.", src, dst)
-                        }
-                    } else {
-                        return Err(err);
-                    }
-                }
+            preserve(src, dst)?;
+        }
+
+        Ok(())
+    }
+
+    // the progress bar replaces the line printed for each path restored
+    fn direct_with_progress(
+        src: &Path,
+        dst: &Path,
+        dst_root: &Path,
+        should_preserve: bool,
+        opt_progress: Option<&CopyProgress>,
+    ) -> HttmResult<()> {
+        let Some(copy_progress) = opt_progress else {
+            return Self::direct(src, dst, dst_root, should_preserve);
+        };
+
+        Self::direct_quiet(src, dst, dst_root, should_preserve)?;
+
+        if !src.is_dir() {
+            copy_progress.inc(src);
+        }
+
+        Ok(())
+    }
+}
+
+struct CopyProgress {
+    progress_bar: ProgressBar,
+    total_files: u64,
+    files_copied: Cell<u64>,
+}
+
+impl CopyProgress {
+    fn new(src: &Path) -> Self {
+        let (total_files, total_bytes) = Self::count(src);
+
+        let progress_bar = ProgressBar::new(total_bytes);
+
+        if let Ok(style) =
+            ProgressStyle::with_template("{bar:40} {bytes}/{total_bytes} ({percent}%) {msg}")
+        {
+            progress_bar.set_style(style);
+        }
+
+        Self {
+            progress_bar,
+            total_files,
+            files_copied: Cell::new(0),
+        }
+    }
+
+    // the counting pass only reads metadata, never file contents, and never follows symlinks
+    fn count(path: &Path) -> (u64, u64) {
+        let Ok(md) = path.symlink_metadata() else {
+            return (0, 0);
+        };
+
+        if !md.is_dir() {
+            return (1, md.len());
+        }
+
+        read_dir(path)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| Self::count(&entry.path()))
+            .fold((0, 0), |(files, bytes), (entry_files, entry_bytes)| {
+                (files + entry_files, bytes + entry_bytes)
+            })
+    }
+
+    fn inc(&self, src: &Path) {
+        let len = src
+            .symlink_metadata()
+            .map(|md| md.len())
+            .unwrap_or_default();
+
+        self.files_copied.set(self.files_copied.get() + 1);
+
+        self.progress_bar.inc(len);
+        self.progress_bar.set_message(format!(
+            "{}/{} files",
+            self.files_copied.get(),
+            self.total_files
+        ));
+    }
+
+    fn finish(&self) {
+        self.progress_bar.finish_and_clear();
+
+        eprintln!(
+            "{}: {} file/s",
+            Blue.paint("Restored "),
+            self.files_copied.get()
+        );
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MergeCount {
+    pub copied: usize,
+    pub skipped: usize,
+}
+
+impl Copy {
+    // like recursive, but leave alone any destination file which is newer or the same
+    pub fn merge(
+        src: &Path,
+        dst: &Path,
+        should_preserve: bool,
+        merge_count: &mut MergeCount,
+    ) -> HttmResult<()> {
+        Self::merge_inner(src, dst, dst, should_preserve, merge_count)
+    }
+
+    fn merge_inner(
+        src: &Path,
+        dst: &Path,
+        dst_root: &Path,
+        should_preserve: bool,
+        merge_count: &mut MergeCount,
+    ) -> HttmResult<()> {
+        if !src.is_dir() {
+            return Self::merge_file(src, dst, dst_root, should_preserve, merge_count);
+        }
+
+        // a dir which only exists on the snapshot is created, and its contents merged like any other
+        if dst.symlink_metadata().is_err() {
+            Self::direct(src, dst, dst_root, should_preserve)?;
+        }
+
+        for entry in read_dir(src)?.flatten() {
+            let file_type = entry.file_type()?;
+            let entry_src = entry.path();
+            let entry_dst = dst.join(entry.file_name());
+
+            if file_type.is_dir() {
+                Self::merge_inner(
+                    &entry_src,
+                    &entry_dst,
+                    dst_root,
+                    should_preserve,
+                    merge_count,
+                )?;
+            } else {
+                Self::merge_file(
+                    &entry_src,
+                    &entry_dst,
+                    dst_root,
+                    should_preserve,
+                    merge_count,
+                )?;
             }
         }
 
         Ok(())
     }
+
+    // like cp -n, never overwrite any path which already exists, checked for each entry
+    // in the descent, so live files recreated since the snapshot are left alone
+    pub fn no_clobber(
+        src: &Path,
+        dst: &Path,
+        should_preserve: bool,
+        merge_count: &mut MergeCount,
+    ) -> HttmResult<()> {
+        Self::no_clobber_inner(src, dst, dst, should_preserve, merge_count)
+    }
+
+    fn no_clobber_inner(
+        src: &Path,
+        dst: &Path,
+        dst_root: &Path,
+        should_preserve: bool,
+        merge_count: &mut MergeCount,
+    ) -> HttmResult<()> {
+        let is_dst_existing = dst.symlink_metadata().is_ok();
+
+        if !src.is_dir() || (is_dst_existing && !dst.is_dir()) {
+            if is_dst_existing {
+                eprintln!(
+                    "NOTICE: httm skipped a path which already exists at the destination: {:?}",
+                    dst
+                );
+                merge_count.skipped += 1;
+                return Ok(());
+            }
+
+            Self::direct(src, dst, dst_root, should_preserve)?;
+            merge_count.copied += 1;
+            return Ok(());
+        }
+
+        if !is_dst_existing {
+            Self::direct(src, dst, dst_root, should_preserve)?;
+        }
+
+        for entry in read_dir(src)?.flatten() {
+            let entry_src = entry.path();
+            let entry_dst = dst.join(entry.file_name());
+
+            Self::no_clobber_inner(
+                &entry_src,
+                &entry_dst,
+                dst_root,
+                should_preserve,
+                merge_count,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn merge_file(
+        src: &Path,
+        dst: &Path,
+        dst_root: &Path,
+        should_preserve: bool,
+        merge_count: &mut MergeCount,
+    ) -> HttmResult<()> {
+        if Self::is_dst_newer_or_same(src, dst) {
+            merge_count.skipped += 1;
+            return Ok(());
+        }
+
+        Self::direct(src, dst, dst_root, should_preserve)?;
+        merge_count.copied += 1;
+
+        Ok(())
+    }
+
+    fn is_dst_newer_or_same(src: &Path, dst: &Path) -> bool {
+        let (Some(src_md), Some(dst_md)) = (src.opt_metadata(), dst.opt_metadata()) else {
+            return false;
+        };
+
+        src_md == dst_md || dst_md.mtime() > src_md.mtime()
+    }
 }
 
 pub struct Preserve;
@@ -181,30 +603,45 @@ impl Preserve {
             .write(false)
             .open(&dst)?;
 
+        let preserve_attrs = &GLOBAL_CONFIG.preserve_attrs;
+
         // Mode
-        {
+        if preserve_attrs.mode {
             set_permissions(dst, src_metadata.permissions())?
         }
 
         // ACLs - requires libacl1-dev to build
         #[cfg(feature = "acls")]
-        {
+        if preserve_attrs.acl {
             if let Ok(acls) = exacl::getfacl(src, None) {
                 exacl::setfacl(&[dst], &acls, None)?;
             }
         }
 
         // Ownership
-        {
+        if preserve_attrs.owner {
             let dst_uid = src_metadata.uid();
             let dst_gid = src_metadata.gid();
 
-            chown(dst, Some(dst_uid), Some(dst_gid))?
+            // without privileges, such as when restoring across users, keep what we can and warn
+            match chown(dst, Some(dst_uid), Some(dst_gid)) {
+                Err(err) if err.raw_os_error() == Some(nix::libc::EPERM) => {
+                    static CHOWN_EPERM_WARNING: Once = Once::new();
+
+                    CHOWN_EPERM_WARNING.call_once(|| {
+                        eprintln!(
+                            "WARN: httm lacks the privileges to preserve ownership, and will not preserve ownership, such as of: {:?}",
+                            dst
+                        );
+                    });
+                }
+                res => res?,
+            }
         }
 
         // XAttrs
-        {
-            #[cfg(feature = "xattrs")]
+        #[cfg(feature = "xattrs")]
+        if preserve_attrs.xattr {
             if let Ok(xattrs) = xattr::list(src) {
                 xattrs
                     .flat_map(|attr| {
@@ -216,7 +653,7 @@ impl Preserve {
         }
 
         // Timestamps
-        {
+        if preserve_attrs.time {
             let src_times = std::fs::FileTimes::new()
                 .set_accessed(src_metadata.accessed()?)
                 .set_modified(src_metadata.modified()?);
@@ -256,38 +693,71 @@ impl Preserve {
 pub struct Remove;
 
 impl Remove {
-    pub fn recursive(src: &Path) -> HttmResult<()> {
-        Self::recursive_quiet(src)?;
-
-        eprintln!("{}: {:?} -> 🗑️", Red.paint("Removed  "), src);
+    // expected_root is the tree the caller means to remove from, such as a dataset mount,
+    // so a wrongly constructed path is never removed
+    pub fn recursive_quiet(src: &Path, expected_root: &Path) -> HttmResult<()> {
+        // canonicalize the root once, here, and never again per entry.  like any path removed,
+        // the root may itself be a symlink to be removed, so its target is never the root
+        let Some(canonical_root) = Self::canonical_no_follow(expected_root) else {
+            let msg = format!(
+                "httm refuses to remove a path when the root expected could not be determined: {:?}",
+                expected_root
+            );
+            return Err(HttmError::new(&msg).into());
+        };
 
-        Ok(())
+        Self::recursive_quiet_inner(src, &canonical_root)
     }
 
-    pub fn recursive_quiet(src: &Path) -> HttmResult<()> {
-        if src.is_dir() {
-            for entry in read_dir(&src)?.flatten() {
-                let file_type = entry.file_type()?;
-                let path = entry.path();
+    fn recursive_quiet_inner(src: &Path, canonical_root: &Path) -> HttmResult<()> {
+        Self::deny_outside_root(src, canonical_root)?;
 
-                if path.exists() {
-                    if file_type.is_dir() {
-                        Self::recursive(&path)?;
-                    } else {
-                        std::fs::remove_file(path)?
-                    }
-                }
-            }
+        // never follow a symlink, a symlink to a dir is removed, never the dir's contents
+        let Ok(src_metadata) = src.symlink_metadata() else {
+            return Ok(());
+        };
 
-            if src.exists() {
-                std::fs::remove_dir_all(&src)?
+        if src_metadata.is_dir() {
+            for entry in read_dir(&src)?.flatten() {
+                Self::recursive_quiet_inner(&entry.path(), canonical_root)?;
             }
-        } else if src.exists() {
+
+            std::fs::remove_dir(&src)?
+        } else {
             std::fs::remove_file(&src)?
         }
 
         Ok(())
     }
+
+    fn deny_outside_root(src: &Path, canonical_root: &Path) -> HttmResult<()> {
+        // nothing to remove
+        if src.symlink_metadata().is_err() {
+            return Ok(());
+        }
+
+        match Self::canonical_no_follow(src) {
+            Some(canonical_src) if canonical_src.starts_with(canonical_root) => Ok(()),
+            _ => {
+                let msg = format!(
+                    "httm refuses to remove a path which is not beneath the root expected: {:?} is not beneath {:?}",
+                    src, canonical_root
+                );
+                Err(HttmError::new(&msg).into())
+            }
+        }
+    }
+
+    // canonicalize only the parent, as a symlink to be removed is removed, never its target
+    fn canonical_no_follow(path: &Path) -> Option<PathBuf> {
+        match (path.parent(), path.file_name()) {
+            (Some(parent), Some(file_name)) => parent
+                .canonicalize()
+                .ok()
+                .map(|canonical_parent| canonical_parent.join(file_name)),
+            _ => path.canonicalize().ok(),
+        }
+    }
 }
 
 use std::hash::Hash;
@@ -328,6 +798,7 @@ impl<'a> Hash for HashFileContents<'a> {
             return;
         };
 
+        // stream the contents in buffer sized chunks, never read a large file into memory all at once
         let mut reader = BufReader::with_capacity(IN_BUFFER_SIZE, self_file);
 
         loop {
@@ -353,3 +824,103 @@ impl<'a> Hash for HashFileContents<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::SystemTime;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("httm-test-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn removal_never_follows_a_symlink_to_a_dir() {
+        let dir = temp_dir("remove-symlink");
+        let target = dir.join("target");
+        let link = dir.join("link");
+        std::fs::create_dir(&target).unwrap();
+        std::fs::write(target.join("file"), b"keep").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        Remove::recursive_quiet(&link, &link).unwrap();
+
+        assert!(link.symlink_metadata().is_err());
+        assert!(target.join("file").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn removal_outside_the_root_expected_is_refused() {
+        let dir = temp_dir("remove-outside");
+        let root = dir.join("root");
+        let outside = dir.join("outside");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(&outside, b"keep").unwrap();
+
+        assert!(Remove::recursive_quiet(&outside, &root).is_err());
+        assert!(Remove::recursive_quiet(&root.join("../outside"), &root).is_err());
+        assert!(outside.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn copied_dirs_keep_their_mode_and_mtime_after_their_entries_are_copied() {
+        let dir = temp_dir("post-order");
+        let src = dir.join("src");
+        let dst = dir.join("dst");
+        std::fs::create_dir_all(src.join("sub")).unwrap();
+        std::fs::write(src.join("file"), b"file").unwrap();
+        std::fs::write(src.join("sub/file"), b"file").unwrap();
+
+        let mtime = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+
+        [src.join("sub"), src.clone()].iter().for_each(|src_dir| {
+            set_permissions(src_dir, std::fs::Permissions::from_mode(0o750)).unwrap();
+            std::fs::File::open(src_dir)
+                .unwrap()
+                .set_times(std::fs::FileTimes::new().set_modified(mtime))
+                .unwrap();
+        });
+
+        let preserve = |src: &Path, dst: &Path| -> HttmResult<()> {
+            let src_metadata = src.metadata()?;
+            set_permissions(dst, src_metadata.permissions())?;
+            std::fs::File::open(dst)?
+                .set_times(std::fs::FileTimes::new().set_modified(src_metadata.modified()?))?;
+            Ok(())
+        };
+
+        let copy = |src: &Path, dst: &Path, should_preserve: bool| -> HttmResult<()> {
+            if src.is_dir() {
+                create_dir_all(dst)?;
+            } else {
+                std::fs::copy(src, dst)?;
+            }
+
+            if should_preserve {
+                preserve(src, dst)?;
+            }
+
+            Ok(())
+        };
+
+        Copy::post_order(&src, &dst, true, &copy, &preserve).unwrap();
+
+        [dst.join("sub"), dst.clone()].iter().for_each(|dst_dir| {
+            let dst_metadata = dst_dir.metadata().unwrap();
+            assert_eq!(dst_metadata.permissions().mode() & 0o777, 0o750);
+            assert_eq!(dst_metadata.modified().unwrap(), mtime);
+        });
+
+        assert!(dst.join("sub/file").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
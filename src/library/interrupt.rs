@@ -0,0 +1,53 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use crate::library::results::{HttmError, HttmResult};
+use nix::sys::signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// set only from within the signal handler
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_sigint(_signal: nix::libc::c_int) {
+    // an atomic store is async signal safe, printing or allocating here is not
+    INTERRUPTED.store(true, Ordering::Relaxed);
+}
+
+pub struct Interrupt;
+
+impl Interrupt {
+    // skim intercepts Ctrl-C itself, so only install for non-interactive modes, where
+    // otherwise the process would simply be killed, perhaps mid-print
+    pub fn install() -> HttmResult<()> {
+        let action = SigAction::new(
+            SigHandler::Handler(on_sigint),
+            // restart interrupted syscalls, so in flight reads and writes won't fail with EINTR,
+            // and reset to the default action once caught, so a second Ctrl-C always kills
+            SaFlags::SA_RESTART | SaFlags::SA_RESETHAND,
+            SigSet::empty(),
+        );
+
+        // SAFETY: the handler only performs an atomic store
+        unsafe { sigaction(Signal::SIGINT, &action) }
+            .map(|_| ())
+            .map_err(|err| HttmError::with_context("Could not install SIGINT handler", &err).into())
+    }
+
+    pub fn is_interrupted() -> bool {
+        INTERRUPTED.load(Ordering::Relaxed)
+    }
+}
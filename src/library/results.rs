@@ -23,6 +23,40 @@ use std::io::Error as IoError;
 // into something more simple looking. This error, FYI, is really easy to use with rayon.
 pub type HttmResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
+// the exit code contract, so scripts, such as "if httm -n file", may branch upon whether history exists.
+// clap exits with 2 upon any usage error, so 2 is never used to mean anything else
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttmExitCode {
+    // success, and, when displaying versions, at least one snapshot version was found
+    Success = 0,
+    Error = 1,
+    // the files exist, but no snapshot version was found
+    NoSnapVersions = 3,
+    // neither a live version, nor any snapshot version, was found
+    NothingFound = 4,
+}
+
+impl From<&Box<dyn std::error::Error + Send + Sync>> for HttmExitCode {
+    fn from(error: &Box<dyn std::error::Error + Send + Sync>) -> Self {
+        if error.is::<NothingFoundError>() {
+            return HttmExitCode::NothingFound;
+        }
+
+        HttmExitCode::Error
+    }
+}
+
+#[derive(Debug)]
+pub struct NothingFoundError;
+
+impl fmt::Display for NothingFoundError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "httm could find neither a live version, nor any snapshot version for all the specified paths, so, umm, 🤷? Please try another file.")
+    }
+}
+
+impl Error for NothingFoundError {}
+
 #[derive(Debug)]
 pub struct HttmError {
     pub details: String,
@@ -15,22 +15,24 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use crate::config::generate::PrintMode;
+use crate::config::generate::{ColorMode, ExecMode, FollowSymlinks, PrintMode, SizeFormat};
 use crate::data::paths::{BasicDirEntryInfo, PathData, PathMetadata};
 use crate::data::selection::SelectionCandidate;
 use crate::library::results::{HttmError, HttmResult};
-use crate::GLOBAL_CONFIG;
+use crate::{GLOBAL_CONFIG, ROOT_DIRECTORY};
 use lscolors::{Colorable, LsColors, Style};
 use nu_ansi_term::Style as AnsiTermStyle;
 use number_prefix::NumberPrefix;
+use regex::Regex;
 use std::borrow::Cow;
 use std::fs::FileType;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 use std::iter::Iterator;
 use std::path::{Path, PathBuf};
-use std::sync::LazyLock;
-use std::time::SystemTime;
-use time::{format_description, OffsetDateTime, UtcOffset};
+use std::sync::{LazyLock, OnceLock};
+use std::time::{Duration, SystemTime};
+use time::format_description::OwnedFormatItem;
+use time::{format_description, Date, OffsetDateTime, PrimitiveDateTime, UtcOffset};
 use which::which;
 
 pub fn get_mount_command() -> HttmResult<PathBuf> {
@@ -121,6 +123,9 @@ pub fn print_output_buf(output_buf: &str) -> HttmResult<()> {
     out_locked.flush().map_err(std::convert::Into::into)
 }
 
+// set once, as the config is generated, as httm_is_dir is used during config generation
+pub static FOLLOW_SYMLINKS: OnceLock<FollowSymlinks> = OnceLock::new();
+
 // is this path/dir_entry something we should count as a directory for our purposes?
 pub fn httm_is_dir<'a, T>(entry: &'a T) -> bool
 where
@@ -133,9 +138,19 @@ where
             file_type if file_type.is_dir() => true,
             file_type if file_type.is_file() => false,
             file_type if file_type.is_symlink() => {
+                let follow_symlinks = FOLLOW_SYMLINKS.get().unwrap_or(&FollowSymlinks::Dirs);
+
+                if matches!(follow_symlinks, FollowSymlinks::Never) {
+                    return false;
+                }
+
                 // canonicalize will read_link/resolve the link for us
                 match path.canonicalize() {
                     Ok(link_target) if !link_target.is_dir() => false,
+                    // never follow a link to one of its own ancestors, which would loop forever
+                    Ok(link_target) if matches!(follow_symlinks, FollowSymlinks::Always) => {
+                        path.ancestors().all(|ancestor| ancestor != link_target)
+                    }
                     Ok(link_target) => {
                         find_common_path([link_target, path.to_path_buf()].into_iter()).is_none()
                     }
@@ -181,7 +196,7 @@ impl<'a> HttmIsDir<'a> for PathData {
 
 impl<'a> HttmIsDir<'a> for BasicDirEntryInfo {
     fn httm_is_dir(&self) -> bool {
-        httm_is_dir(self)
+        self.is_dir_memoized(|| httm_is_dir(self))
     }
     fn filetype(&self) -> Result<FileType, std::io::Error> {
         //  of course, this is a placeholder error, we just need an error to report back
@@ -200,10 +215,56 @@ static ENV_LS_COLORS: LazyLock<LsColors> =
 static PHANTOM_STYLE: LazyLock<AnsiTermStyle> =
     LazyLock::new(|| nu_ansi_term::Style::default().dimmed());
 
+// interactive views are always drawn upon the terminal, even when stdout is not,
+// see: https://no-color.org/ and https://bixense.com/clicolors/
+static SHOULD_PAINT: LazyLock<bool> = LazyLock::new(|| match GLOBAL_CONFIG.color_mode {
+    ColorMode::Always => true,
+    ColorMode::Never => false,
+    ColorMode::Auto => {
+        if std::env::var_os("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+            return true;
+        }
+
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+            || std::env::var_os("CLICOLOR").is_some_and(|value| value == "0")
+        {
+            return false;
+        }
+
+        matches!(GLOBAL_CONFIG.exec_mode, ExecMode::Interactive(_))
+            || std::io::stdout().is_terminal()
+    }
+});
+
+static HOME_DIR: LazyLock<Option<PathBuf>> = LazyLock::new(|| {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .filter(|home| home.is_absolute() && home != Path::new(ROOT_DIRECTORY))
+});
+
+// purely cosmetic, replaces the user's home dir prefix with a "~"
+pub fn humanize_path(path: &Path) -> Cow<'_, str> {
+    if let Some(home_dir) = HOME_DIR.as_ref() {
+        if let Ok(relative) = path.strip_prefix(home_dir) {
+            if relative.as_os_str().is_empty() {
+                return Cow::Borrowed("~");
+            }
+
+            return Cow::Owned(format!("~/{}", relative.to_string_lossy()));
+        }
+    }
+
+    path.to_string_lossy()
+}
+
 pub fn paint_string<T>(path: T, display_name: &str) -> Cow<str>
 where
     T: PaintString,
 {
+    if !*SHOULD_PAINT {
+        return Cow::Borrowed(display_name);
+    }
+
     if path.is_phantom() {
         // paint all other phantoms/deleted files the same color, light pink
         return Cow::Owned(PHANTOM_STYLE.paint(display_name).to_string());
@@ -251,20 +312,68 @@ pub enum DateFormat {
 static DATE_FORMAT_DISPLAY: &str =
     "[weekday repr:short] [month repr:short] [day] [hour]:[minute]:[second] [year]";
 static DATE_FORMAT_TIMESTAMP: &str = "[year]-[month]-[day]-[hour]:[minute]:[second]";
+static DATE_FORMAT_ISO8601: &str =
+    "[year]-[month]-[day]T[hour]:[minute]:[second][offset_hour sign:mandatory]:[offset_minute]";
+static DATE_FORMAT_RFC3339: &str =
+    "[year]-[month]-[day] [hour]:[minute]:[second][offset_hour sign:mandatory]:[offset_minute]";
+
+static PARSED_DATE_FORMAT_DISPLAY: LazyLock<OwnedFormatItem> = LazyLock::new(|| {
+    format_description::parse_owned::<2>(DATE_FORMAT_DISPLAY)
+        .expect("display date format is invalid")
+});
+static PARSED_DATE_FORMAT_TIMESTAMP: LazyLock<OwnedFormatItem> = LazyLock::new(|| {
+    format_description::parse_owned::<2>(DATE_FORMAT_TIMESTAMP)
+        .expect("timestamp date format is invalid")
+});
+
+// a user requested display format is parsed only once, and, if invalid, we warn and
+// fall back to the default display format, rather than quit
+pub fn parse_date_format(requested_format: &str) -> Option<OwnedFormatItem> {
+    let format_str = match requested_format {
+        "iso8601" | "iso-8601" => DATE_FORMAT_ISO8601,
+        "rfc3339" | "rfc-3339" => DATE_FORMAT_RFC3339,
+        _ => requested_format,
+    };
+
+    let res = format_description::parse_owned::<2>(format_str)
+        .map_err(|err| err.to_string())
+        .and_then(|parsed_format| {
+            // an otherwise valid format may still fail to format a date
+            OffsetDateTime::now_utc()
+                .format(&parsed_format)
+                .map(|_| parsed_format)
+                .map_err(|err| err.to_string())
+        });
+
+    match res {
+        Ok(parsed_format) => Some(parsed_format),
+        Err(err) => {
+            eprintln!(
+                "WARN: Could not parse the requested date format {requested_format:?}: {err}.  Using the default date format."
+            );
+            None
+        }
+    }
+}
 
 pub fn date_string(
     utc_offset: UtcOffset,
     system_time: &SystemTime,
     date_format: DateFormat,
 ) -> String {
-    let date_time: OffsetDateTime = (*system_time).into();
-
-    let parsed_format = format_description::parse(date_string_format(&date_format))
-        .expect("timestamp date format is invalid");
+    let date_time: OffsetDateTime = OffsetDateTime::from(*system_time).to_offset(utc_offset);
+
+    // a user requested format is responsible for its own offset display
+    if let (DateFormat::Display, Some(requested_format)) =
+        (&date_format, &GLOBAL_CONFIG.opt_date_format)
+    {
+        if let Ok(raw_string) = date_time.format(requested_format) {
+            return raw_string;
+        }
+    }
 
     let raw_string = date_time
-        .to_offset(utc_offset)
-        .format(&parsed_format)
+        .format(date_string_format(&date_format))
         .expect("timestamp date format could not be applied to the date supplied");
 
     if utc_offset == UtcOffset::UTC {
@@ -277,42 +386,345 @@ pub fn date_string(
     raw_string
 }
 
-fn date_string_format<'a>(format: &DateFormat) -> &'a str {
+// a coarse, human readable age, such as "3 days ago", always in the largest whole unit
+pub fn relative_date_string(now: &SystemTime, system_time: &SystemTime) -> String {
+    const UNITS: [(u64, &str); 5] = [
+        (365 * 24 * 60 * 60, "year"),
+        (24 * 60 * 60, "day"),
+        (60 * 60, "hour"),
+        (60, "minute"),
+        (1, "second"),
+    ];
+
+    let Ok(elapsed) = now.duration_since(*system_time) else {
+        return "in the future".to_owned();
+    };
+
+    let secs = elapsed.as_secs();
+
+    let (unit_secs, unit_name) = UNITS
+        .iter()
+        .find(|(unit_secs, _)| secs >= *unit_secs)
+        .unwrap_or(&UNITS[4]);
+
+    let count = secs / unit_secs;
+    let plural = if count == 1 { "" } else { "s" };
+
+    format!("{count} {unit_name}{plural} ago")
+}
+
+fn date_string_format(format: &DateFormat) -> &'static OwnedFormatItem {
     match format {
-        DateFormat::Display => DATE_FORMAT_DISPLAY,
-        DateFormat::Timestamp => DATE_FORMAT_TIMESTAMP,
+        DateFormat::Display => &PARSED_DATE_FORMAT_DISPLAY,
+        DateFormat::Timestamp => &PARSED_DATE_FORMAT_TIMESTAMP,
     }
 }
 
 pub fn display_human_size(size: u64) -> String {
-    let size = size as f64;
+    let number_prefix = match GLOBAL_CONFIG.size_format {
+        SizeFormat::Raw => return size.to_string(),
+        SizeFormat::Binary => NumberPrefix::binary(size as f64),
+        SizeFormat::Decimal => NumberPrefix::decimal(size as f64),
+    };
 
-    match NumberPrefix::binary(size) {
+    match number_prefix {
         NumberPrefix::Standalone(bytes) => format!("{bytes} bytes"),
         NumberPrefix::Prefixed(prefix, n) => format!("{n:.1} {prefix}B"),
     }
 }
 
+// parses a size, the inverse of display_human_size, such as "10M", "1.5 GiB", or "512 bytes",
+// a prefix with an "i" is binary, one without is decimal, a number without a unit is a number of bytes
+pub fn parse_size(value: &str) -> HttmResult<u64> {
+    let value = value.trim();
+
+    let (number, unit) = value.split_at(
+        value
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(value.len()),
+    );
+
+    let Ok(number) = number.parse::<f64>() else {
+        let msg = format!("Could not parse a number from the size specified: {value:?}");
+        return Err(HttmError::new(&msg).into());
+    };
+
+    let unit = unit.trim();
+
+    let (prefix, base) = match unit
+        .strip_suffix(['B', 'b'])
+        .filter(|prefix| !prefix.is_empty())
+        .unwrap_or(unit)
+    {
+        "" | "b" | "B" | "byte" | "bytes" => ("", 1000f64),
+        prefix => match prefix.strip_suffix('i') {
+            Some(prefix) => (prefix, 1024f64),
+            None => (prefix, 1000f64),
+        },
+    };
+
+    let exponent = match prefix {
+        "" => 0,
+        "k" | "K" => 1,
+        "M" | "m" => 2,
+        "G" | "g" => 3,
+        "T" | "t" => 4,
+        "P" | "p" => 5,
+        _ => {
+            let msg = format!("Unit of the size specified is not supported: {value:?}");
+            return Err(HttmError::new(&msg).into());
+        }
+    };
+
+    Ok((number * base.powi(exponent)).round() as u64)
+}
+
+const SECONDS_PER_MINUTE: u64 = 60;
+const SECONDS_PER_HOUR: u64 = 60 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: u64 = 24 * SECONDS_PER_HOUR;
+const SECONDS_PER_WEEK: u64 = 7 * SECONDS_PER_DAY;
+const SECONDS_PER_YEAR: u64 = 365 * SECONDS_PER_DAY;
+
+// parses a duration such as "90d" or "12h", a number without a unit is a number of seconds
+pub fn parse_duration(value: &str) -> HttmResult<Duration> {
+    let value = value.trim();
+
+    let (number, unit) = value.split_at(
+        value
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(value.len()),
+    );
+
+    let Ok(number) = number.parse::<u64>() else {
+        let msg = format!("Could not parse a number from the duration specified: {value:?}");
+        return Err(HttmError::new(&msg).into());
+    };
+
+    let multiplier = match unit.trim() {
+        "" | "s" | "sec" | "secs" => 1,
+        "m" | "min" | "mins" => SECONDS_PER_MINUTE,
+        "h" | "hr" | "hrs" | "hours" => SECONDS_PER_HOUR,
+        "d" | "day" | "days" => SECONDS_PER_DAY,
+        "w" | "wk" | "wks" | "weeks" => SECONDS_PER_WEEK,
+        "y" | "yr" | "yrs" | "years" => SECONDS_PER_YEAR,
+        _ => {
+            let msg = format!("Unit of the duration specified is not supported: {value:?}");
+            return Err(HttmError::new(&msg).into());
+        }
+    };
+
+    Ok(Duration::from_secs(number.saturating_mul(multiplier)))
+}
+
+// a point in time, either a duration before now, such as "7d", or an absolute date, such as
+// "2024-01-31" or "2024-01-31 12:00:00", in the offset used for date display
+pub fn parse_time_bound(value: &str, utc_offset: UtcOffset) -> HttmResult<SystemTime> {
+    if let Ok(duration) = parse_duration(value) {
+        return SystemTime::now().checked_sub(duration).ok_or_else(|| {
+            let msg = format!("The duration specified is too far in the past: {value:?}");
+            HttmError::new(&msg).into()
+        });
+    }
+
+    static DATE_TIME_FORMATS: LazyLock<[OwnedFormatItem; 3]> = LazyLock::new(|| {
+        [
+            "[year]-[month]-[day] [hour]:[minute]:[second]",
+            "[year]-[month]-[day]T[hour]:[minute]:[second]",
+            "[year]-[month]-[day]-[hour]:[minute]:[second]",
+        ]
+        .map(|format_str| {
+            format_description::parse_owned::<2>(format_str)
+                .expect("time bound date format is invalid")
+        })
+    });
+
+    static DATE_FORMAT: LazyLock<OwnedFormatItem> = LazyLock::new(|| {
+        format_description::parse_owned::<2>("[year]-[month]-[day]")
+            .expect("time bound date format is invalid")
+    });
+
+    let value = value.trim();
+
+    let opt_date_time = DATE_TIME_FORMATS
+        .iter()
+        .find_map(|format| PrimitiveDateTime::parse(value, format).ok())
+        .or_else(|| {
+            Date::parse(value, &*DATE_FORMAT)
+                .ok()
+                .map(|date| date.midnight())
+        });
+
+    match opt_date_time {
+        Some(date_time) => Ok(date_time.assume_offset(utc_offset).into()),
+        None => {
+            let msg = format!(
+                "Could not parse either a duration, such as \"7d\", or a date, such as \"2024-01-31\" or \"2024-01-31 12:00:00\", from the value specified: {value:?}"
+            );
+            Err(HttmError::new(&msg).into())
+        }
+    }
+}
+
+// translates a shell-like glob, such as "node_modules" or "*.tmp", into an anchored regex,
+// "*" and "?" never match a path separator, but "**" matches anything
+pub fn glob_to_regex(glob: &str) -> HttmResult<Regex> {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                pattern.push_str(".*");
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    pattern.push('$');
+
+    Regex::new(&pattern).map_err(|err| {
+        let msg = format!("Could not translate the glob {glob:?} into a valid pattern: {err}");
+        HttmError::new(&msg).into()
+    })
+}
+
+pub fn display_human_duration(duration: &Duration) -> String {
+    let secs = duration.as_secs();
+
+    let days = secs / SECONDS_PER_DAY;
+    let hours = (secs % SECONDS_PER_DAY) / SECONDS_PER_HOUR;
+
+    if days > 0 {
+        return format!("{days}d {hours}h");
+    }
+
+    let minutes = (secs % SECONDS_PER_HOUR) / SECONDS_PER_MINUTE;
+
+    format!("{hours}h {minutes}m")
+}
+
+// describes how the metadata of dst differs from that of src, deltas are dst less src
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathDiff {
+    pub is_src_missing: bool,
+    pub is_dst_missing: bool,
+    pub is_size_changed: bool,
+    pub is_mtime_changed: bool,
+    pub is_symlink_changed: bool,
+    pub size_delta_bytes: i128,
+    pub mtime_delta_secs: i128,
+}
+
+impl PathDiff {
+    pub fn is_empty(&self) -> bool {
+        !(self.is_src_missing
+            || self.is_dst_missing
+            || self.is_size_changed
+            || self.is_mtime_changed
+            || self.is_symlink_changed)
+    }
+
+    // what changed, from the src to the dst, on a single line
+    pub fn summary(&self) -> String {
+        if self.is_src_missing || self.is_dst_missing {
+            return "metadata not found".to_owned();
+        }
+
+        let mut changes: Vec<String> = Vec::new();
+
+        if self.is_symlink_changed {
+            changes.push("symlink target changed".to_owned());
+        }
+
+        if self.is_size_changed {
+            changes.push(format!("size {:+} bytes", self.size_delta_bytes));
+        }
+
+        if self.is_mtime_changed {
+            let duration = Duration::from_secs(self.mtime_delta_secs.unsigned_abs() as u64);
+            let direction = if self.mtime_delta_secs < 0 {
+                "earlier"
+            } else {
+                "later"
+            };
+
+            changes.push(format!(
+                "modified {} {direction}",
+                display_human_duration(&duration)
+            ));
+        }
+
+        if changes.is_empty() {
+            return "metadata unchanged".to_owned();
+        }
+
+        changes.join(", ")
+    }
+}
+
+pub fn diff_metadata<T>(src: &T, dst: &T) -> PathDiff
+where
+    T: ComparePathMetadata + ?Sized,
+{
+    let (src_md, dst_md) = match (src.opt_metadata(), dst.opt_metadata()) {
+        (Some(src_md), Some(dst_md)) => (src_md, dst_md),
+        (opt_src_md, opt_dst_md) => {
+            return PathDiff {
+                is_src_missing: opt_src_md.is_none(),
+                is_dst_missing: opt_dst_md.is_none(),
+                ..Default::default()
+            }
+        }
+    };
+
+    // a symlink differs when either path is not a link, or the targets differ
+    let is_symlink_changed = (src_md.is_symlink() || dst_md.is_symlink())
+        && (src_md.is_symlink() != dst_md.is_symlink()
+            || src_md.opt_link_target_hash() != dst_md.opt_link_target_hash());
+
+    let secs_since_epoch = |time: SystemTime| -> i128 {
+        match time.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs() as i128,
+            Err(err) => -(err.duration().as_secs() as i128),
+        }
+    };
+
+    PathDiff {
+        is_src_missing: false,
+        is_dst_missing: false,
+        is_size_changed: src_md.size() != dst_md.size(),
+        is_mtime_changed: src_md.mtime() != dst_md.mtime(),
+        is_symlink_changed,
+        size_delta_bytes: dst_md.size() as i128 - src_md.size() as i128,
+        mtime_delta_secs: secs_since_epoch(dst_md.mtime()) - secs_since_epoch(src_md.mtime()),
+    }
+}
+
 pub fn is_metadata_same<T>(src: T, dst: T) -> HttmResult<()>
 where
     T: ComparePathMetadata,
 {
-    if src.opt_metadata().is_none() {
-        let msg = format!("Metadata not found: {:?}", src.path());
-        return Err(HttmError::new(&msg).into());
+    let diff = diff_metadata(&src, &dst);
+
+    if diff.is_empty() {
+        return Ok(());
     }
 
-    if src.path().is_symlink() && (src.path().read_link().ok() != dst.path().read_link().ok()) {
-        let msg = format!("Symlink do not match: {:?}", src.path());
+    if diff.is_src_missing {
+        let msg = format!("Metadata not found: {:?}", src.path());
         return Err(HttmError::new(&msg).into());
     }
 
-    if src.opt_metadata() != dst.opt_metadata() {
-        let msg = format!("Metadata mismatch: {:?} !-> {:?}", src.path(), dst.path());
+    if diff.is_symlink_changed && src.path().is_symlink() {
+        let msg = format!("Symlink do not match: {:?}", src.path());
         return Err(HttmError::new(&msg).into());
     }
 
-    Ok(())
+    let msg = format!("Metadata mismatch: {:?} !-> {:?}", src.path(), dst.path());
+    Err(HttmError::new(&msg).into())
 }
 
 pub trait ComparePathMetadata {
@@ -326,7 +738,7 @@ impl<T: AsRef<Path>> ComparePathMetadata for T {
         self.as_ref()
             .symlink_metadata()
             .ok()
-            .and_then(|md| PathMetadata::new(&md))
+            .and_then(|md| PathMetadata::new(self.as_ref(), &md))
     }
 
     fn path(&self) -> &Path {
@@ -0,0 +1,121 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use crate::data::paths::PathData;
+use crate::display_map::format::PrintAsMap;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::print_output_buf;
+use crate::lookup::versions::ProximateDatasetAndOptAlts;
+use crate::GLOBAL_CONFIG;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::Path;
+use std::time::SystemTime;
+
+// the newest snapshot name shared by the most proximate dataset and every alternative replicated dataset,
+// a safe common point, such as before a send/receive resync
+pub struct CommonSnap;
+
+impl CommonSnap {
+    pub fn exec(path_set: &[PathData]) -> HttmResult<()> {
+        let map: BTreeMap<String, Vec<String>> = path_set
+            .iter()
+            .filter_map(|pathdata| {
+                let path_string = pathdata.path().to_string_lossy().to_string();
+
+                match Self::newest_common(pathdata) {
+                    Ok(Some(snap_name)) => Some((path_string, vec![snap_name])),
+                    Ok(None) => {
+                        eprintln!(
+                            "NOTICE: No common snapshot exists across the datasets of: {path_string:?}"
+                        );
+                        None
+                    }
+                    Err(err) => {
+                        eprintln!("WARN: {err}");
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        if map.is_empty() {
+            return Err(HttmError::new(
+                "httm could not find a common snapshot for any of the paths specified.",
+            )
+            .into());
+        }
+
+        let printable_map = PrintAsMap::from(map).with_phantoms(path_set.iter());
+
+        print_output_buf(&printable_map.to_string())
+    }
+
+    pub fn newest_common(pathdata: &PathData) -> HttmResult<Option<String>> {
+        let prox_opt_alts = ProximateDatasetAndOptAlts::new(pathdata)?;
+
+        if prox_opt_alts.opt_alts.is_none_or(|alts| alts.is_empty()) {
+            let msg = format!(
+                "httm could not find any locally replicated dataset for the path specified: {:?}",
+                pathdata.path()
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        // the times of the snapshots upon the most proximate dataset order the snapshots in common
+        let mut common = Self::snap_names(prox_opt_alts.proximate_dataset);
+
+        prox_opt_alts
+            .datasets_of_interest()
+            .filter(|dataset| *dataset != prox_opt_alts.proximate_dataset)
+            .for_each(|dataset| {
+                let snap_names = Self::snap_names(dataset);
+
+                common.retain(|snap_name, _mtime| snap_names.contains_key(snap_name));
+            });
+
+        // a snapshot root's modify time never decreases from one snapshot to the next,
+        // so the newest common snapshot has the latest time, and, of any with the same time, the greatest name
+        let opt_newest = common
+            .into_iter()
+            .max_by(|(a_name, a_mtime), (b_name, b_mtime)| {
+                a_mtime.cmp(b_mtime).then_with(|| a_name.cmp(b_name))
+            })
+            .map(|(snap_name, _mtime)| snap_name.to_string_lossy().to_string());
+
+        Ok(opt_newest)
+    }
+
+    fn snap_names(dataset: &Path) -> BTreeMap<OsString, SystemTime> {
+        GLOBAL_CONFIG
+            .dataset_collection
+            .map_of_snaps
+            .get(dataset)
+            .into_iter()
+            .flatten()
+            .filter_map(|snap_mount| {
+                let snap_name = snap_mount.file_name()?.to_os_string();
+                let mtime = snap_mount
+                    .symlink_metadata()
+                    .and_then(|md| md.modified())
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+
+                Some((snap_name, mtime))
+            })
+            .collect()
+    }
+}
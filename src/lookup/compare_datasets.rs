@@ -0,0 +1,192 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use crate::config::generate::{DedupBy, PrintMode};
+use crate::display_map::format::PrintAsMap;
+use crate::library::file_ops::Copy;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{delimiter, print_output_buf, ComparePathMetadata};
+use crate::parse::mounts::SnapDirName;
+use crate::{BTRFS_SNAPPER_HIDDEN_DIRECTORY, GLOBAL_CONFIG, ZFS_HIDDEN_DIRECTORY};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::{read_dir, FileType};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default)]
+pub struct CompareDatasets {
+    added: Vec<PathBuf>,
+    removed: Vec<PathBuf>,
+    changed: Vec<PathBuf>,
+}
+
+impl CompareDatasets {
+    pub fn exec(first_root: &Path, second_root: &Path) -> HttmResult<()> {
+        let compare = Self::new(first_root, second_root)?;
+
+        let output_buf = match GLOBAL_CONFIG.print_mode {
+            PrintMode::RawNewline | PrintMode::RawZero if !GLOBAL_CONFIG.opt_json => compare.raw(),
+            _ => {
+                let printable_map: PrintAsMap = compare.into();
+                printable_map.to_string()
+            }
+        };
+
+        print_output_buf(&output_buf)
+    }
+
+    // in raw modes, each path is prefixed by its category, so no path is ever mistaken for another's
+    fn raw(&self) -> String {
+        let delimiter = delimiter();
+
+        [
+            ("added", &self.added),
+            ("removed", &self.removed),
+            ("changed", &self.changed),
+        ]
+        .into_iter()
+        .flat_map(|(category, paths)| paths.iter().map(move |path| (category, path)))
+        .map(|(category, path)| format!("{category}\t{}{delimiter}", path.to_string_lossy()))
+        .collect()
+    }
+
+    // paths are relative to each root, "added" are those only found in the second root,
+    // "removed" are those only found in the first root
+    pub fn new(first_root: &Path, second_root: &Path) -> HttmResult<Self> {
+        [first_root, second_root].iter().try_for_each(|root| {
+            if root.symlink_metadata().is_err() {
+                let msg = format!("Path to compare does not exist: {root:?}");
+                return Err(HttmError::new(&msg));
+            }
+
+            Ok(())
+        })?;
+
+        let mut compare = Self::default();
+
+        if first_root.is_dir() && second_root.is_dir() {
+            compare.recursive(first_root, second_root, Path::new(""))?;
+        } else if Self::is_changed(first_root, second_root)? {
+            compare.changed.push(first_root.to_path_buf());
+        }
+
+        Ok(compare)
+    }
+
+    fn recursive(
+        &mut self,
+        first_root: &Path,
+        second_root: &Path,
+        relative_dir: &Path,
+    ) -> HttmResult<()> {
+        let is_root = relative_dir.as_os_str().is_empty();
+
+        let first_entries = Self::entries(&first_root.join(relative_dir), is_root)?;
+        let mut second_entries = Self::entries(&second_root.join(relative_dir), is_root)?;
+
+        for (file_name, first_file_type) in first_entries {
+            let relative_path = relative_dir.join(&file_name);
+
+            let Some(second_file_type) = second_entries.remove(&file_name) else {
+                self.removed.push(relative_path);
+                continue;
+            };
+
+            // never follow symlinks, a symlink to a dir is compared like any other file
+            if first_file_type.is_dir() && second_file_type.is_dir() {
+                self.recursive(first_root, second_root, &relative_path)?;
+                continue;
+            }
+
+            if first_file_type != second_file_type
+                || Self::is_changed(
+                    &first_root.join(&relative_path),
+                    &second_root.join(&relative_path),
+                )?
+            {
+                self.changed.push(relative_path);
+            }
+        }
+
+        // whatever remains was only found in the second root
+        self.added.extend(
+            second_entries
+                .into_keys()
+                .map(|file_name| relative_dir.join(file_name)),
+        );
+
+        Ok(())
+    }
+
+    // as with versions, metadata may be the same, while the contents differ, see --dedup-by
+    fn is_changed(first: &Path, second: &Path) -> HttmResult<bool> {
+        if first.opt_metadata() != second.opt_metadata() {
+            return Ok(true);
+        }
+
+        if matches!(GLOBAL_CONFIG.dedup_by, DedupBy::Contents)
+            && first.is_file()
+            && !first.is_symlink()
+        {
+            return Ok(!Copy::is_same_bytes(first, second)?);
+        }
+
+        Ok(false)
+    }
+
+    fn entries(dir: &Path, is_root: bool) -> HttmResult<BTreeMap<OsString, FileType>> {
+        let entries = read_dir(dir)?
+            .flatten()
+            // never compare the hidden snapshot directories found at a dataset root
+            .filter(|entry| {
+                let file_name = entry.file_name();
+                !is_root
+                    || (file_name != ZFS_HIDDEN_DIRECTORY
+                        && file_name != BTRFS_SNAPPER_HIDDEN_DIRECTORY
+                        && !SnapDirName::is_user_hidden_dir(&entry.path()))
+            })
+            .filter_map(|entry| {
+                entry
+                    .file_type()
+                    .ok()
+                    .map(|file_type| (entry.file_name(), file_type))
+            })
+            .collect();
+
+        Ok(entries)
+    }
+}
+
+impl From<CompareDatasets> for PrintAsMap {
+    fn from(compare: CompareDatasets) -> Self {
+        let to_strings = |paths: Vec<PathBuf>| -> Vec<String> {
+            paths
+                .into_iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect()
+        };
+
+        let map: BTreeMap<String, Vec<String>> = [
+            ("added".to_owned(), to_strings(compare.added)),
+            ("removed".to_owned(), to_strings(compare.removed)),
+            ("changed".to_owned(), to_strings(compare.changed)),
+        ]
+        .into();
+
+        map.into()
+    }
+}
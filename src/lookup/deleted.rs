@@ -23,6 +23,40 @@ use std::ffi::OsString;
 use std::fs::read_dir;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+pub type LiveFilenames = HashSet<OsString>;
+
+// bounds memory on deep trees, where deleted threads may lag far behind the live enumeration,
+// dirs beyond the bound are simply read again by their deleted thread
+const MAX_CACHED_DIRS: usize = 1024;
+
+static LIVE_FILENAMES_CACHE: LazyLock<Mutex<HashMap<PathBuf, LiveFilenames>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// the live enumeration of a dir records its file names here, so the deleted search
+// of the same dir may diff against them without a second read_dir
+pub struct LiveFilenamesCache;
+
+impl LiveFilenamesCache {
+    pub fn insert(requested_dir: &Path, filenames: LiveFilenames) {
+        let Ok(mut cache) = LIVE_FILENAMES_CACHE.lock() else {
+            return;
+        };
+
+        if cache.len() < MAX_CACHED_DIRS {
+            cache.insert(requested_dir.to_path_buf(), filenames);
+        }
+    }
+
+    // an entry is only ever used once, so it is removed when taken
+    pub fn take(requested_dir: &Path) -> Option<LiveFilenames> {
+        LIVE_FILENAMES_CACHE
+            .lock()
+            .ok()
+            .and_then(|mut cache| cache.remove(requested_dir))
+    }
+}
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct DeletedFiles {
@@ -38,6 +72,18 @@ impl DeletedFiles {
         // requesting dir to those of their relative dirs on snapshots
         let requested_dir_pathdata = PathData::from(requested_dir);
 
+        // get all local entries we need to compare against these to know
+        // what is a deleted file, once for all search bundles
+        //
+        // create a collection of local file names
+        let local_filenames_set: LiveFilenames = match LiveFilenamesCache::take(requested_dir) {
+            Some(cached) => cached,
+            None => read_dir(requested_dir)?
+                .flatten()
+                .map(|dir_entry| dir_entry.file_name())
+                .collect(),
+        };
+
         // create vec of all local and replicated backups at once
         //
         // we need to make certain that what we return from possibly multiple datasets are unique
@@ -47,9 +93,8 @@ impl DeletedFiles {
             ProximateDatasetAndOptAlts::new(&requested_dir_pathdata)?
                 .into_search_bundles()
                 .flat_map(|search_bundle| {
-                    Self::unique_deleted_for_dir(&requested_dir_pathdata.path(), &search_bundle)
+                    Self::unique_deleted_for_dir(&local_filenames_set, &search_bundle)
                 })
-                .flatten()
                 .map(|basic_info| (basic_info.filename().to_os_string(), basic_info))
                 .collect();
 
@@ -63,29 +108,18 @@ impl DeletedFiles {
     }
 
     fn unique_deleted_for_dir(
-        requested_dir: &Path,
+        local_filenames_set: &LiveFilenames,
         search_bundle: &RelativePathAndSnapMounts,
-    ) -> HttmResult<Vec<BasicDirEntryInfo>> {
-        // get all local entries we need to compare against these to know
-        // what is a deleted file
-        //
-        // create a collection of local file names
-        let local_filenames_set: HashSet<OsString> = read_dir(requested_dir)?
-            .flatten()
-            .map(|dir_entry| dir_entry.file_name())
-            .collect();
-
+    ) -> Vec<BasicDirEntryInfo> {
         let unique_snap_filenames: HashMap<OsString, BasicDirEntryInfo> =
             Self::unique_snap_filenames(search_bundle.snap_mounts, search_bundle.relative_path);
 
         // compare local filenames to all unique snap filenames - none values are unique, here
-        let all_deleted_versions = unique_snap_filenames
+        unique_snap_filenames
             .into_iter()
             .filter(|(file_name, _basic_info)| !local_filenames_set.contains(file_name))
             .map(|(_file_name, basic_info)| basic_info)
-            .collect();
-
-        Ok(all_deleted_versions)
+            .collect()
     }
 
     fn unique_snap_filenames(
@@ -18,12 +18,17 @@
 use crate::data::paths::PathData;
 use crate::data::paths::PathDeconstruction;
 use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::display_human_size;
 use crate::lookup::versions::ProximateDatasetAndOptAlts;
+use crate::parse::mounts::FilesystemType;
+use crate::zfs::run_command::RunZFSCommand;
 use crate::ExecMode;
 use crate::GLOBAL_CONFIG;
 use rayon::prelude::*;
+use std::collections::HashMap;
 use std::ops::Deref;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, RwLock};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MountDisplay {
@@ -48,6 +53,58 @@ impl MountDisplay {
     }
 }
 
+// key: mount, val: the display string of the usage of the dataset, if available
+static DATASET_USAGE: LazyLock<RwLock<HashMap<PathBuf, Option<String>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+pub struct DatasetUsage;
+
+impl DatasetUsage {
+    // only ZFS datasets report usage, and each is only queried once per run
+    pub fn display(mount: &Path) -> Option<String> {
+        if let Some(cached) = DATASET_USAGE
+            .read()
+            .ok()
+            .and_then(|cache| cache.get(mount).cloned())
+        {
+            return cached;
+        }
+
+        let opt_usage = Self::query(mount);
+
+        if let Ok(mut cache) = DATASET_USAGE.write() {
+            cache.insert(mount.to_path_buf(), opt_usage.clone());
+        }
+
+        opt_usage
+    }
+
+    fn query(mount: &Path) -> Option<String> {
+        let dataset_md = GLOBAL_CONFIG
+            .dataset_collection
+            .map_of_datasets
+            .get(mount)?;
+
+        if !matches!(dataset_md.fs_type, FilesystemType::Zfs) {
+            return None;
+        }
+
+        let fs_name = dataset_md.source.to_string_lossy();
+
+        match RunZFSCommand::new().and_then(|run_zfs| run_zfs.usage(&fs_name)) {
+            Ok((used, used_by_snapshots)) => Some(format!(
+                "used: {}, used by snapshots: {}",
+                display_human_size(used),
+                display_human_size(used_by_snapshots)
+            )),
+            Err(err) => {
+                eprintln!("WARN: {err}");
+                None
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct MountsForFiles<'a> {
     inner: Vec<ProximateDatasetAndOptAlts<'a>>,
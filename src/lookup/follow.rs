@@ -0,0 +1,300 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use crate::config::generate::FollowMode;
+use crate::data::paths::PathData;
+use crate::display_versions::wrapper::VersionsDisplayWrapper;
+use crate::library::interrupt::Interrupt;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::print_output_buf;
+use crate::lookup::versions::VersionsMap;
+use crate::GLOBAL_CONFIG;
+use std::io::IsTerminal;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "linux")]
+use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify, WatchDescriptor};
+#[cfg(target_os = "linux")]
+use std::collections::{HashMap, HashSet};
+#[cfg(target_os = "linux")]
+use std::ffi::OsString;
+
+// how often to check for a change, or for Ctrl-C, while waiting
+const FOLLOW_TICK: Duration = Duration::from_millis(100);
+// redraw at least this often while a file is continuously written, and its events never settle
+const FOLLOW_MAX_DEBOUNCE: Duration = Duration::from_secs(1);
+// when changes cannot be watched, re-run the lookup on a timer
+const FOLLOW_FALLBACK_INTERVAL: Duration = Duration::from_secs(2);
+// clear the screen and return the cursor home
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[H";
+
+pub struct Follow;
+
+impl Follow {
+    pub fn exec() -> HttmResult<()> {
+        let Some(follow_mode) = &GLOBAL_CONFIG.opt_follow else {
+            return Err(HttmError::new("opt_follow should never be None in follow mode").into());
+        };
+
+        // Ctrl-C is the only way out of follow mode, so should be a clean exit
+        Interrupt::install()?;
+
+        let trigger = Trigger::new(follow_mode);
+
+        loop {
+            Self::print_versions()?;
+
+            if !trigger.wait() {
+                return Ok(());
+            }
+        }
+    }
+
+    fn print_versions() -> HttmResult<()> {
+        // metadata was read as the config was generated, and is stale after any change,
+        // so re-read each live path
+        let path_set: Vec<PathData> = GLOBAL_CONFIG
+            .paths
+            .iter()
+            .map(|pathdata| PathData::from(pathdata.path()))
+            .collect();
+
+        let res = VersionsMap::new(&GLOBAL_CONFIG, &path_set).map(|versions_map| {
+            VersionsDisplayWrapper::from(&GLOBAL_CONFIG, versions_map).to_string()
+        });
+
+        if std::io::stdout().is_terminal() {
+            print_output_buf(CLEAR_SCREEN)?;
+        }
+
+        match res {
+            Ok(output_buf) => print_output_buf(&output_buf),
+            // a live file may be briefly missing, such as mid save, so keep following
+            Err(err) => {
+                eprintln!("WARN: {err}");
+                Ok(())
+            }
+        }
+    }
+}
+
+enum Trigger {
+    #[cfg(target_os = "linux")]
+    Watch(LiveFileWatch),
+    Timer(Duration),
+}
+
+impl Trigger {
+    fn new(follow_mode: &FollowMode) -> Self {
+        match follow_mode {
+            FollowMode::Interval(interval) => Self::Timer(*interval),
+            FollowMode::Watch => {
+                #[cfg(target_os = "linux")]
+                match LiveFileWatch::new(&GLOBAL_CONFIG.paths) {
+                    Ok(watch) => return Self::Watch(watch),
+                    Err(err) => eprintln!(
+                        "WARN: {err}  httm will instead re-run the lookup every {} seconds.",
+                        FOLLOW_FALLBACK_INTERVAL.as_secs()
+                    ),
+                }
+
+                #[cfg(not(target_os = "linux"))]
+                eprintln!(
+                    "NOTICE: Watching for changes is only available on Linux.  httm will instead re-run the lookup every {} seconds.",
+                    FOLLOW_FALLBACK_INTERVAL.as_secs()
+                );
+
+                Self::Timer(FOLLOW_FALLBACK_INTERVAL)
+            }
+        }
+    }
+
+    // returns false once the user has pressed Ctrl-C
+    fn wait(&self) -> bool {
+        match self {
+            #[cfg(target_os = "linux")]
+            Self::Watch(watch) => watch.wait(),
+            Self::Timer(interval) => {
+                let deadline = Instant::now() + *interval;
+
+                while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+                    if Interrupt::is_interrupted() {
+                        return false;
+                    }
+
+                    sleep(remaining.min(FOLLOW_TICK));
+                }
+
+                !Interrupt::is_interrupted()
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+struct LiveFileWatch {
+    inotify: Inotify,
+    // we watch each live file's parent, as many editors save by replacing the file,
+    // and a watch upon the file itself would be left behind upon the old inode
+    file_names: HashMap<WatchDescriptor, HashSet<OsString>>,
+}
+
+#[cfg(target_os = "linux")]
+impl LiveFileWatch {
+    fn new(path_set: &[PathData]) -> HttmResult<Self> {
+        let inotify =
+            Inotify::init(InitFlags::IN_NONBLOCK | InitFlags::IN_CLOEXEC).map_err(|err| {
+                HttmError::with_context("Could not initialize inotify to watch for changes", &err)
+            })?;
+
+        let flags = AddWatchFlags::IN_MODIFY
+            | AddWatchFlags::IN_ATTRIB
+            | AddWatchFlags::IN_CLOSE_WRITE
+            | AddWatchFlags::IN_CREATE
+            | AddWatchFlags::IN_DELETE
+            | AddWatchFlags::IN_MOVED_FROM
+            | AddWatchFlags::IN_MOVED_TO;
+
+        let mut file_names: HashMap<WatchDescriptor, HashSet<OsString>> = HashMap::new();
+
+        for path in path_set.iter().map(PathData::path) {
+            let (Some(parent), Some(file_name)) = (path.parent(), path.file_name()) else {
+                continue;
+            };
+
+            let wd = inotify.add_watch(parent, flags).map_err(|err| {
+                let msg = format!("Could not watch for changes to path: {path:?}");
+                HttmError::with_context(&msg, &err)
+            })?;
+
+            file_names
+                .entry(wd)
+                .or_default()
+                .insert(file_name.to_owned());
+        }
+
+        if file_names.is_empty() {
+            return Err(HttmError::new("No input file could be watched for changes.").into());
+        }
+
+        Ok(Self {
+            inotify,
+            file_names,
+        })
+    }
+
+    fn wait(&self) -> bool {
+        let mut opt_first_change: Option<Instant> = None;
+
+        loop {
+            if Interrupt::is_interrupted() {
+                return false;
+            }
+
+            // non-blocking, so an error here is usually just EAGAIN, as no event is pending
+            let events = self.inotify.read_events().unwrap_or_default();
+
+            let is_change = events.iter().any(|event| {
+                event.name.as_ref().is_some_and(|name| {
+                    self.file_names
+                        .get(&event.wd)
+                        .is_some_and(|file_names| file_names.contains(name))
+                })
+            });
+
+            // a single save is usually many events, so wait for the events to settle,
+            // but for no longer than the max debounce
+            match opt_first_change {
+                Some(first_change)
+                    if !is_change || first_change.elapsed() >= FOLLOW_MAX_DEBOUNCE =>
+                {
+                    return true;
+                }
+                None if is_change => opt_first_change = Some(Instant::now()),
+                Some(_) | None => {}
+            }
+
+            sleep(FOLLOW_TICK);
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(target_os = "linux")]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    fn temp_file(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("httm-test-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let file = dir.join("file");
+        std::fs::write(&file, b"first").unwrap();
+        file
+    }
+
+    #[test]
+    fn wait_returns_once_a_single_save_settles() {
+        let file = temp_file("follow-settle");
+        let watch = LiveFileWatch::new(&[PathData::from(file.as_path())]).unwrap();
+
+        std::fs::write(&file, b"second").unwrap();
+
+        let start = Instant::now();
+        assert!(watch.wait());
+        assert!(start.elapsed() < FOLLOW_MAX_DEBOUNCE);
+
+        std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn wait_returns_while_a_file_is_continuously_written() {
+        let file = temp_file("follow-continuous");
+        let watch = LiveFileWatch::new(&[PathData::from(file.as_path())]).unwrap();
+
+        let is_done = Arc::new(AtomicBool::new(false));
+
+        let writer = {
+            let file = file.clone();
+            let is_done = is_done.clone();
+
+            std::thread::spawn(move || {
+                while !is_done.load(Ordering::Relaxed) {
+                    std::fs::write(&file, b"again").unwrap();
+                    sleep(FOLLOW_TICK / 4);
+                }
+            })
+        };
+
+        let start = Instant::now();
+        assert!(watch.wait());
+        let elapsed = start.elapsed();
+
+        is_done.store(true, Ordering::Relaxed);
+        writer.join().unwrap();
+
+        assert!(elapsed >= FOLLOW_MAX_DEBOUNCE);
+        assert!(elapsed < FOLLOW_MAX_DEBOUNCE * 3);
+
+        std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+    }
+}
@@ -19,7 +19,7 @@ use crate::config::generate::ListSnapsFilters;
 use crate::data::paths::PathDeconstruction;
 use crate::data::paths::{PathData, ZfsSnapPathGuard};
 use crate::library::results::{HttmError, HttmResult};
-use crate::lookup::versions::VersionsMap;
+use crate::lookup::versions::{ProximateDatasetAndOptAlts, VersionsMap};
 use crate::parse::mounts::FilesystemType;
 use rayon::prelude::*;
 use std::collections::BTreeMap;
@@ -51,7 +51,7 @@ impl SnapNameMap {
         opt_filters: &Option<ListSnapsFilters>,
     ) -> HttmResult<Self> {
         let inner: BTreeMap<PathData, Vec<String>> = versions_map
-            .iter()
+            .into_pairs()
             .filter(|(pathdata, snaps)| {
                 if snaps.is_empty() {
                     let msg = format!(
@@ -127,7 +127,7 @@ impl SnapNameMap {
                     }
                 }
 
-                Some((pathdata.to_owned(), vec_snaps))
+                Some((pathdata, vec_snaps))
             })
             .collect();
 
@@ -143,3 +143,60 @@ impl SnapNameMap {
         Ok(inner.into())
     }
 }
+
+// the dataset's whole set of snapshot names, whether or not a snapshot contains a version of the path,
+// such as for shell completions
+pub struct DatasetSnapNames;
+
+impl DatasetSnapNames {
+    pub fn snap_names(
+        path_set: &[PathData],
+        opt_filters: &Option<ListSnapsFilters>,
+    ) -> HttmResult<Vec<String>> {
+        let mut snap_names: Vec<String> = path_set
+            .iter()
+            .filter_map(|pathdata| match ProximateDatasetAndOptAlts::new(pathdata) {
+                Ok(prox_opt_alts) => Some(
+                    prox_opt_alts
+                        .into_search_bundles()
+                        .flat_map(|search_bundle| search_bundle.snap_mounts.iter())
+                        .filter_map(|snap_mount| {
+                            let snap_mount_pathdata = PathData::from(snap_mount);
+
+                            ZfsSnapPathGuard::new(&snap_mount_pathdata)
+                                .and_then(|spg| spg.source(None))
+                        })
+                        .map(|snap_name| snap_name.to_string_lossy().to_string())
+                        .collect::<Vec<String>>(),
+                ),
+                Err(err) => {
+                    eprintln!("WARN: {err}");
+                    None
+                }
+            })
+            .flatten()
+            .filter(|snap_name| {
+                let Some(names) = opt_filters
+                    .as_ref()
+                    .and_then(|filters| filters.name_filters.as_ref())
+                else {
+                    return true;
+                };
+
+                names.iter().any(|pattern| snap_name.contains(pattern))
+            })
+            .collect();
+
+        snap_names.sort();
+        snap_names.dedup();
+
+        if snap_names.is_empty() {
+            return Err(HttmError::new(
+                "httm could not find any snapshot names for the datasets of the paths specified.  Note: This is a ZFS only option.",
+            )
+            .into());
+        }
+
+        Ok(snap_names)
+    }
+}
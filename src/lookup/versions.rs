@@ -15,43 +15,131 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use hashbrown::HashSet;
+use hashbrown::{HashMap, HashSet};
 use rayon::prelude::*;
+use serde::ser::{SerializeMap, SerializeStruct};
+use serde::{Serialize, Serializer};
 
-use crate::config::generate::{Config, DedupBy, ExecMode, LastSnapMode};
+use crate::config::generate::{Config, DedupBy, ExecMode, LastSnapMode, MapOfNumberedVersions};
 use crate::data::paths::PathDeconstruction;
-use crate::data::paths::{CompareVersionsContainer, PathData};
-use crate::library::results::{HttmError, HttmResult};
+use crate::data::paths::{CompareVersionsContainer, PathData, PathMetadata};
+use crate::library::results::{HttmError, HttmExitCode, HttmResult, NothingFoundError};
 use crate::parse::mounts::LinkType;
 use crate::GLOBAL_CONFIG;
+use indexmap::IndexMap;
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::io::ErrorKind;
 use std::ops::{Deref, DerefMut};
 use std::path::{Path, PathBuf};
 use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, SystemTime};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VersionsMap {
-    inner: BTreeMap<PathData, Vec<PathData>>,
+    // in the order of the paths requested
+    inner: IndexMap<PathData, Vec<PathData>>,
+    // keyed by the path of the version displayed, see VersionNote
+    notes: BTreeMap<PathBuf, VersionNote>,
 }
 
-impl From<BTreeMap<PathData, Vec<PathData>>> for VersionsMap {
-    fn from(map: BTreeMap<PathData, Vec<PathData>>) -> Self {
-        Self { inner: map }
+// where else identical versions were found, when those versions were collapsed into this version,
+// by --omit-identical-across-datasets or by --dedup-global
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionNote {
+    pub also_found_on: Vec<PathBuf>,
+    pub shared_with: Vec<PathBuf>,
+}
+
+impl VersionNote {
+    // a trailing field for display, like the source tags
+    pub fn tag(&self) -> Option<String> {
+        let join = |paths: &[PathBuf]| -> String {
+            paths
+                .iter()
+                .map(|path| format!("{path:?}"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        let tags: Vec<String> = [
+            ("also found on", &self.also_found_on),
+            ("shared with", &self.shared_with),
+        ]
+        .into_iter()
+        .filter(|(_label, paths)| !paths.is_empty())
+        .map(|(label, paths)| format!("[{label}: {}]", join(paths)))
+        .collect();
+
+        if tags.is_empty() {
+            return None;
+        }
+
+        Some(tags.join(" "))
     }
 }
 
-impl From<[(PathData, Vec<PathData>); 1]> for VersionsMap {
-    fn from(slice: [(PathData, Vec<PathData>); 1]) -> Self {
+impl From<IndexMap<PathData, Vec<PathData>>> for VersionsMap {
+    fn from(map: IndexMap<PathData, Vec<PathData>>) -> Self {
         Self {
-            inner: slice.into(),
+            inner: map,
+            notes: BTreeMap::new(),
+        }
+    }
+}
+
+impl From<Versions> for VersionsMap {
+    fn from(versions: Versions) -> Self {
+        std::iter::once(versions).collect()
+    }
+}
+
+impl FromIterator<Versions> for VersionsMap {
+    fn from_iter<I: IntoIterator<Item = Versions>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::from(IndexMap::new()), |mut versions_map, versions| {
+                versions_map.notes.extend(versions.notes);
+                versions_map
+                    .inner
+                    .insert(versions.live_path, versions.snap_versions);
+                versions_map
+            })
+    }
+}
+
+// these decouple consumers from the map which backs VersionsMap
+impl VersionsMap {
+    pub fn live_paths(&self) -> impl Iterator<Item = &PathData> {
+        self.inner.keys()
+    }
+
+    pub fn versions_for(&self, live: &Path) -> Option<&[PathData]> {
+        self.inner
+            .iter()
+            .find(|(live_pathdata, _snaps)| live_pathdata.path() == live)
+            .map(|(_live_pathdata, snaps)| snaps.as_slice())
+    }
+
+    pub fn note(&self, version: &PathData) -> Option<&VersionNote> {
+        self.notes.get(version.path())
+    }
+
+    // for scripts, whether any history exists, after all filters have been applied
+    pub fn exit_code(&self) -> HttmExitCode {
+        if self.inner.values().any(|snaps| !snaps.is_empty()) {
+            return HttmExitCode::Success;
         }
+
+        HttmExitCode::NoSnapVersions
+    }
+
+    pub fn into_pairs(self) -> impl Iterator<Item = (PathData, Vec<PathData>)> {
+        self.inner.into_iter()
     }
 }
 
 impl Deref for VersionsMap {
-    type Target = BTreeMap<PathData, Vec<PathData>>;
+    type Target = IndexMap<PathData, Vec<PathData>>;
 
     fn deref(&self) -> &Self::Target {
         &self.inner
@@ -59,42 +147,168 @@ impl Deref for VersionsMap {
 }
 
 impl DerefMut for VersionsMap {
-    fn deref_mut(&mut self) -> &mut BTreeMap<PathData, Vec<PathData>> {
+    fn deref_mut(&mut self) -> &mut IndexMap<PathData, Vec<PathData>> {
         &mut self.inner
     }
 }
 
+// stable form for consumers of our JSON, unlike PrintAsMap, which is only strings,
+// each entry is keyed by its live path string
+impl Serialize for VersionsMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(self.inner.len()))?;
+
+        self.inner.iter().try_for_each(|(live_path, versions)| {
+            let versions: Vec<VersionEntry> = versions
+                .iter()
+                .map(|version| VersionEntry::new(version, self.note(version)))
+                .collect();
+
+            state.serialize_entry(
+                &live_path.path().to_string_lossy(),
+                &VersionsMapEntry {
+                    live_path,
+                    versions: &versions,
+                },
+            )
+        })?;
+
+        state.end()
+    }
+}
+
+struct VersionsMapEntry<'a> {
+    live_path: &'a PathData,
+    versions: &'a [VersionEntry<'a>],
+}
+
+impl<'a> Serialize for VersionsMapEntry<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("VersionsMapEntry", 2)?;
+
+        state.serialize_field("live_path", self.live_path)?;
+        state.serialize_field("versions", self.versions)?;
+        state.end()
+    }
+}
+
+// a version is serialized just as a PathData, plus the note of where else it was found, if any
+pub struct VersionEntry<'a> {
+    pathdata: &'a PathData,
+    opt_note: Option<&'a VersionNote>,
+}
+
+impl<'a> VersionEntry<'a> {
+    pub fn new(pathdata: &'a PathData, opt_note: Option<&'a VersionNote>) -> Self {
+        Self { pathdata, opt_note }
+    }
+}
+
+impl<'a> Serialize for VersionEntry<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(None)?;
+
+        state.serialize_entry("path", self.pathdata.path())?;
+        state.serialize_entry("metadata", self.pathdata.opt_metadata())?;
+        state.serialize_entry("is_phantom", &self.pathdata.opt_metadata().is_none())?;
+
+        if let Some(note) = self.opt_note {
+            if !note.also_found_on.is_empty() {
+                state.serialize_entry("also_found_on", &note.also_found_on)?;
+            }
+
+            if !note.shared_with.is_empty() {
+                state.serialize_entry("shared_with", &note.shared_with)?;
+            }
+        }
+
+        state.end()
+    }
+}
+
 impl VersionsMap {
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "description": "map of each live file path to its live version and its snapshot versions",
+            "type": "object",
+            "additionalProperties": {
+                "type": "object",
+                "properties": {
+                    "live_path": { "$ref": "#/$defs/path_data" },
+                    "versions": {
+                        "type": "array",
+                        "items": { "$ref": "#/$defs/path_data" }
+                    }
+                },
+                "required": ["live_path", "versions"]
+            }
+        })
+    }
+
     pub fn new(config: &Config, path_set: &[PathData]) -> HttmResult<VersionsMap> {
         let is_interactive_mode = matches!(GLOBAL_CONFIG.exec_mode, ExecMode::Interactive(_));
 
-        let all_snap_versions: BTreeMap<PathData, Vec<PathData>> = path_set
-            .par_iter()
-            .filter_map(|pathdata| match Versions::new(pathdata, config) {
-                Ok(versions) => Some(versions),
-                Err(err) => {
-                    if !is_interactive_mode {
-                        eprintln!("WARN: {}", err.to_string())
-                    }
-                    None
-                }
-            })
-            .map(|versions| {
-                if !is_interactive_mode
-                    && versions.live_path.opt_metadata().is_none()
-                    && versions.snap_versions.is_empty()
-                {
-                    eprintln!(
-                        "WARN: Input file may have never existed: {:?}",
-                        versions.live_path.path()
-                    );
+        // filter before any lookup, and an empty set is not an error, as the user asked for it
+        let matching_set: Vec<PathData>;
+
+        let path_set = match &config.opt_match_regex {
+            Some(regex) => {
+                matching_set = path_set
+                    .iter()
+                    .filter(|pathdata| regex.is_match(&pathdata.path().to_string_lossy()))
+                    .cloned()
+                    .collect();
+
+                if matching_set.is_empty() {
+                    return Ok(IndexMap::new().into());
                 }
 
-                versions.into_inner()
-            })
-            .collect();
+                &matching_set
+            }
+            None => path_set,
+        };
+
+        // the same file requested twice, perhaps once by way of a symlink, needs only one lookup
+        let unique_set: Vec<PathData>;
+
+        let path_set = if !config.opt_no_dedup_input && path_set.len() > 1 {
+            unique_set = Self::dedup_input(path_set);
+            &unique_set
+        } else {
+            path_set
+        };
+
+        let all_snap_versions: Vec<Versions> = match config.opt_parallel_files {
+            // bound the outer parallelism by looking up each chunk of paths sequentially,
+            // the inner per-dataset parallelism remains the same
+            Some(parallel_files) => {
+                let chunk_size = path_set.len().div_ceil(parallel_files).max(1);
+
+                path_set
+                    .par_chunks(chunk_size)
+                    .flat_map_iter(|chunk| {
+                        chunk.iter().filter_map(|pathdata| {
+                            Self::versions(pathdata, config, is_interactive_mode)
+                        })
+                    })
+                    .collect()
+            }
+            None => path_set
+                .par_iter()
+                .filter_map(|pathdata| Self::versions(pathdata, config, is_interactive_mode))
+                .collect(),
+        };
 
-        let mut versions_map: VersionsMap = all_snap_versions.into();
+        let mut versions_map: VersionsMap = all_snap_versions.into_iter().collect();
 
         // check if all files (snap and live) do not exist, if this is true, then user probably messed up
         // and entered a file that never existed (that is, perhaps a wrong file name)?
@@ -103,10 +317,23 @@ impl VersionsMap {
                 .keys()
                 .all(|pathdata| pathdata.opt_metadata().is_none())
         {
-            return Err(HttmError::new(
-                "httm could find neither a live version, nor any snapshot version for all the specified paths, so, umm, 🤷? Please try another file.",
-            )
-            .into());
+            return Err(NothingFoundError.into());
+        }
+
+        if config.opt_newer_than.is_some() || config.opt_older_than.is_some() {
+            versions_map.time_window(config.opt_newer_than, config.opt_older_than)
+        }
+
+        if config.opt_min_size.is_some() || config.opt_max_size.is_some() {
+            versions_map.size_window(config.opt_min_size, config.opt_max_size)
+        }
+
+        if config.opt_dedup_global {
+            versions_map.dedup_global()
+        }
+
+        if let Some(map_of_numbered) = &config.opt_numbered_versions {
+            versions_map.numbered_versions(map_of_numbered)?
         }
 
         // process last snap mode after omit_ditto
@@ -118,9 +345,64 @@ impl VersionsMap {
             versions_map.last_snap(last_snap_mode)
         }
 
+        // process prune empty last, so files emptied by the filters above are pruned too
+        if config.opt_prune_empty {
+            versions_map.retain(|_live_version, snaps| !snaps.is_empty())
+        }
+
         Ok(versions_map)
     }
 
+    // preserves the first seen order of the paths
+    fn dedup_input(path_set: &[PathData]) -> Vec<PathData> {
+        let mut seen: HashSet<PathBuf> = HashSet::with_capacity(path_set.len());
+
+        path_set
+            .iter()
+            .filter(|pathdata| {
+                // paths are already canonical, except for symlinks, which are compared by their targets
+                let key = match pathdata.opt_metadata() {
+                    Some(md) if md.is_symlink() => pathdata
+                        .path()
+                        .canonicalize()
+                        .unwrap_or_else(|_| pathdata.path().to_path_buf()),
+                    _ => pathdata.path().to_path_buf(),
+                };
+
+                seen.insert(key)
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn versions(
+        pathdata: &PathData,
+        config: &Config,
+        is_interactive_mode: bool,
+    ) -> Option<Versions> {
+        let versions = match Versions::new(pathdata, config) {
+            Ok(versions) => versions,
+            Err(err) => {
+                if !is_interactive_mode {
+                    eprintln!("WARN: {}", err.to_string())
+                }
+                return None;
+            }
+        };
+
+        if !is_interactive_mode
+            && versions.live_path.opt_metadata().is_none()
+            && versions.snap_versions.is_empty()
+        {
+            eprintln!(
+                "WARN: Input file may have never existed: {:?}",
+                versions.live_path.path()
+            );
+        }
+
+        Some(versions)
+    }
+
     pub fn is_live_version_redundant(live_pathdata: &PathData, snaps: &[PathData]) -> bool {
         if let Some(last_snap) = snaps.last() {
             return last_snap.opt_metadata() == live_pathdata.opt_metadata();
@@ -129,6 +411,93 @@ impl VersionsMap {
         false
     }
 
+    // drop versions modified outside the window, bounds are exclusive
+    fn time_window(
+        &mut self,
+        opt_newer_than: Option<SystemTime>,
+        opt_older_than: Option<SystemTime>,
+    ) {
+        self.values_mut().for_each(|snaps| {
+            snaps.retain(|snap| {
+                let mtime = snap.metadata_infallible().mtime();
+
+                opt_newer_than.is_none_or(|newer_than| mtime > newer_than)
+                    && opt_older_than.is_none_or(|older_than| mtime < older_than)
+            })
+        });
+    }
+
+    // drop versions sized outside the window, bounds are inclusive
+    fn size_window(&mut self, opt_min_size: Option<u64>, opt_max_size: Option<u64>) {
+        self.values_mut().for_each(|snaps| {
+            snaps.retain(|snap| {
+                let size = snap.metadata_infallible().size();
+
+                opt_min_size.is_none_or(|min_size| size >= min_size)
+                    && opt_max_size.is_none_or(|max_size| size <= max_size)
+            })
+        });
+    }
+
+    // a version identical (same size and mtime) to one already listed under another live path,
+    // is listed only once, under the first such live path, and notes the others with which it is shared
+    fn dedup_global(&mut self) {
+        // each metadata maps to the first live path and the version listed under it
+        let mut first_listed: HashMap<PathMetadata, (PathBuf, PathBuf)> = HashMap::new();
+        let notes = &mut self.notes;
+
+        self.inner.iter_mut().for_each(|(pathdata, snaps)| {
+            snaps.retain(|snap| {
+                let Some(metadata) = snap.opt_metadata() else {
+                    return true;
+                };
+
+                match first_listed.get(metadata) {
+                    Some((first_live, first_snap)) if first_live.as_path() != pathdata.path() => {
+                        let shared_with =
+                            &mut notes.entry(first_snap.clone()).or_default().shared_with;
+
+                        if !shared_with.iter().any(|live| live == pathdata.path()) {
+                            shared_with.push(pathdata.path().to_path_buf());
+                        }
+
+                        false
+                    }
+                    Some(_) => true,
+                    None => {
+                        first_listed.insert(
+                            *metadata,
+                            (pathdata.path().to_path_buf(), snap.path().to_path_buf()),
+                        );
+                        true
+                    }
+                }
+            })
+        });
+    }
+
+    // versions are sorted oldest first, so the 1st version is the last
+    fn numbered_versions(&mut self, map_of_numbered: &MapOfNumberedVersions) -> HttmResult<()> {
+        self.iter_mut().try_for_each(|(pathdata, snaps)| {
+            let Some(number) = map_of_numbered.get(pathdata.path()) else {
+                return Ok(());
+            };
+
+            let Some(idx) = snaps.len().checked_sub(*number) else {
+                let msg = format!(
+                    "Version number {number} was requested, but only {} snapshot version/s exist for: {:?}",
+                    snaps.len(),
+                    pathdata.path()
+                );
+                return Err(HttmError::new(&msg).into());
+            };
+
+            *snaps = vec![snaps.swap_remove(idx)];
+
+            Ok(())
+        })
+    }
+
     fn omit_ditto(&mut self) {
         self.iter_mut().for_each(|(pathdata, snaps)| {
             // process omit_ditto before last snap
@@ -168,6 +537,7 @@ impl VersionsMap {
 pub struct Versions {
     live_path: PathData,
     snap_versions: Vec<PathData>,
+    notes: BTreeMap<PathBuf, VersionNote>,
 }
 
 impl Versions {
@@ -175,6 +545,27 @@ impl Versions {
     pub fn new(pathdata: &PathData, config: &Config) -> HttmResult<Self> {
         let prox_opt_alts = ProximateDatasetAndOptAlts::new(pathdata)?;
         let live_path = prox_opt_alts.pathdata.clone();
+
+        if config.opt_omit_identical_across_datasets {
+            let versions_by_dataset: Vec<(&Path, Vec<PathData>)> = prox_opt_alts
+                .into_search_bundles()
+                .map(|relative_path_snap_mounts| {
+                    (
+                        relative_path_snap_mounts.dataset_of_interest,
+                        relative_path_snap_mounts.versions_processed(&config.dedup_by),
+                    )
+                })
+                .collect();
+
+            let (snap_versions, notes) = Self::omit_identical_across_datasets(versions_by_dataset);
+
+            return Ok(Self {
+                live_path,
+                snap_versions,
+                notes,
+            });
+        }
+
         let snap_versions: Vec<PathData> = prox_opt_alts
             .into_search_bundles()
             .flat_map(|relative_path_snap_mounts| {
@@ -185,12 +576,45 @@ impl Versions {
         Ok(Self {
             live_path,
             snap_versions,
+            notes: BTreeMap::new(),
         })
     }
 
-    #[inline(always)]
-    pub fn into_inner(self) -> (PathData, Vec<PathData>) {
-        (self.live_path, self.snap_versions)
+    // each version displayed notes the other datasets upon which identical versions were found
+    fn omit_identical_across_datasets(
+        versions_by_dataset: Vec<(&Path, Vec<PathData>)>,
+    ) -> (Vec<PathData>, BTreeMap<PathBuf, VersionNote>) {
+        let mut first_seen: HashMap<PathMetadata, PathBuf> = HashMap::new();
+        let mut notes: BTreeMap<PathBuf, VersionNote> = BTreeMap::new();
+
+        // the most proximate dataset is always last, and its versions are preferred
+        let mut snap_versions: Vec<PathData> = versions_by_dataset
+            .into_iter()
+            .rev()
+            .flat_map(|(dataset, versions)| versions.into_iter().map(move |v| (dataset, v)))
+            .filter_map(|(dataset, version)| {
+                let Some(metadata) = version.opt_metadata() else {
+                    return Some(version);
+                };
+
+                let Some(first) = first_seen.get(metadata) else {
+                    first_seen.insert(*metadata, version.path().to_path_buf());
+                    return Some(version);
+                };
+
+                let also_found_on = &mut notes.entry(first.clone()).or_default().also_found_on;
+
+                if !also_found_on.iter().any(|found_on| found_on == dataset) {
+                    also_found_on.push(dataset.to_path_buf());
+                }
+
+                None
+            })
+            .collect();
+
+        snap_versions.sort_by_key(|version| version.metadata_infallible().mtime());
+
+        (snap_versions, notes)
     }
 }
 
@@ -327,6 +751,18 @@ impl<'a> RelativePathAndSnapMounts<'a> {
 
     #[inline(always)]
     fn all_versions_unprocessed(&'a self) -> impl Iterator<Item = PathData> + 'a {
+        // only network datasets are subject to a timeout, which requires the link type
+        // classification of each dataset already found in DatasetMetadata
+        let opt_timeout = GLOBAL_CONFIG.opt_network_timeout.filter(|_| {
+            GLOBAL_CONFIG
+                .dataset_collection
+                .map_of_datasets
+                .get(self.dataset_of_interest)
+                .is_some_and(|md| matches!(md.link_type, LinkType::Network))
+        });
+
+        let mut timed_out = false;
+
         // get the DirEntry for our snapshot path which will have all our possible
         // snapshots, like so: .zfs/snapshots/<some snap name>/
         self
@@ -335,8 +771,19 @@ impl<'a> RelativePathAndSnapMounts<'a> {
             .map(|snap_path| {
                 snap_path.join(self.relative_path)
             })
-            .filter_map(|joined_path| {
-                match joined_path.symlink_metadata() {
+            .filter_map(move |joined_path| {
+                let res = match opt_timeout {
+                    // once a stat has hung, presume the mount is stale and don't wait upon it again
+                    Some(_) if timed_out => return None,
+                    Some(timeout) => {
+                        let res = Self::symlink_metadata_with_timeout(&joined_path, timeout);
+                        timed_out = matches!(&res, Err(err) if err.kind() == ErrorKind::TimedOut);
+                        res
+                    }
+                    None => joined_path.symlink_metadata(),
+                };
+
+                match res {
                     Ok(md) => {
                         // why not PathData::new()? because symlinks will resolve!
                         // symlinks from a snap will end up looking just like the link target, so this is very confusing...
@@ -361,6 +808,30 @@ impl<'a> RelativePathAndSnapMounts<'a> {
             })
     }
 
+    // a stat upon a stale network mount may never return, so stat upon a helper thread,
+    // and, if it doesn't return in time, leave that thread behind
+    fn symlink_metadata_with_timeout(
+        path: &Path,
+        timeout: Duration,
+    ) -> std::io::Result<std::fs::Metadata> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let path_buf = path.to_path_buf();
+
+        std::thread::spawn(move || {
+            let _ = tx.send(path_buf.symlink_metadata());
+        });
+
+        match rx.recv_timeout(timeout) {
+            Ok(res) => res,
+            Err(_) => {
+                eprintln!(
+                    "WARN: httm timed out waiting upon the metadata of a snapshot version on a network dataset: {path:?}"
+                );
+                Err(std::io::Error::from(ErrorKind::TimedOut))
+            }
+        }
+    }
+
     // remove duplicates with the same system modify time and size/file len (or contents! See --DEDUP_BY)
     #[inline(always)]
     fn sort_dedup_versions(
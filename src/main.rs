@@ -24,8 +24,11 @@ mod display_map {
     pub mod format;
 }
 mod display_versions {
+    pub mod count_only;
     pub mod format;
     pub mod num_versions;
+    pub mod stale;
+    pub mod stat;
     pub mod wrapper;
 }
 mod background {
@@ -46,19 +49,25 @@ mod roll_forward {
     pub mod preserve_hard_links;
 }
 mod config {
+    pub mod config_file;
     pub mod generate;
     pub mod install_hot_keys;
+    pub mod json_schema;
 }
 mod library {
     pub mod diff_copy;
     pub mod file_ops;
+    pub mod interrupt;
     pub mod iter_extensions;
     pub mod results;
     pub mod utility;
 }
 mod lookup {
+    pub mod common_snap;
+    pub mod compare_datasets;
     pub mod deleted;
     pub mod file_mounts;
+    pub mod follow;
     pub mod snap_names;
     pub mod versions;
 }
@@ -69,6 +78,7 @@ mod parse {
     pub mod snaps;
 }
 mod zfs {
+    pub mod mount_snap;
     pub mod run_command;
     pub mod snap_guard;
     pub mod snap_mounts;
@@ -81,16 +91,21 @@ use crate::interactive::browse::InteractiveBrowse;
 use crate::interactive::select::InteractiveSelect;
 use background::recursive::NonInteractiveRecursiveWrapper;
 use config::generate::{Config, ExecMode};
+use config::json_schema::print_json_schema;
 use display_map::format::PrintAsMap;
 use display_versions::wrapper::VersionsDisplayWrapper;
 use interactive::prune::PruneSnaps;
 use interactive::restore::InteractiveRestore;
-use library::results::HttmResult;
-use library::utility::print_output_buf;
+use library::results::{HttmExitCode, HttmResult};
+use library::utility::{delimiter, print_output_buf};
+use lookup::compare_datasets::CompareDatasets;
 use lookup::file_mounts::MountsForFiles;
-use lookup::snap_names::SnapNameMap;
+use lookup::common_snap::CommonSnap;
+use lookup::follow::Follow;
+use lookup::snap_names::{DatasetSnapNames, SnapNameMap};
 use lookup::versions::VersionsMap;
 use roll_forward::exec::RollForward;
+use zfs::mount_snap::MountSnapshot;
 use zfs::snap_mounts::SnapshotMounts;
 
 pub const ZFS_HIDDEN_DIRECTORY: &str = ".zfs";
@@ -107,10 +122,10 @@ pub const IN_BUFFER_SIZE: usize = 131_072;
 
 fn main() {
     match exec() {
-        Ok(_) => std::process::exit(0),
+        Ok(exit_code) => std::process::exit(exit_code as i32),
         Err(error) => {
             eprintln!("ERROR: {error}");
-            std::process::exit(1)
+            std::process::exit(HttmExitCode::from(&error) as i32)
         }
     }
 }
@@ -126,9 +141,17 @@ static GLOBAL_CONFIG: LazyLock<Config> = LazyLock::new(|| {
         .unwrap()
 });
 
-fn exec() -> HttmResult<()> {
+fn exec() -> HttmResult<HttmExitCode> {
     // fn exec() handles the basic display cases, and sends other cases to be processed elsewhere
     match &GLOBAL_CONFIG.exec_mode {
+        // a restore from a named snapshot needs no interactive browse or select
+        ExecMode::Interactive(InteractiveMode::Restore(_))
+            if GLOBAL_CONFIG.opt_from_snapshot.is_some() =>
+        {
+            let snap_name = GLOBAL_CONFIG.opt_from_snapshot.as_deref().unwrap_or_default();
+
+            InteractiveRestore::from_named_snapshot(snap_name)?.restore_named_snapshot()
+        }
         // ExecMode::Interactive *may* return back to this function to be printed
         ExecMode::Interactive(interactive_mode) => {
             let mut browse_result = InteractiveBrowse::new()?;
@@ -158,17 +181,45 @@ fn exec() -> HttmResult<()> {
                 }
             }
         }
+        ExecMode::BasicDisplay if GLOBAL_CONFIG.opt_common_snap => {
+            CommonSnap::exec(&GLOBAL_CONFIG.paths)
+        }
+        // follow mode loops the basic display until the user presses Ctrl-C
+        ExecMode::BasicDisplay
+        | ExecMode::NumVersions(_)
+        | ExecMode::StaleThan(_)
+        | ExecMode::Stat(_)
+            if GLOBAL_CONFIG.opt_follow.is_some() =>
+        {
+            Follow::exec()
+        }
         // ExecMode::BasicDisplay will be just printed, we already know the paths
-        ExecMode::BasicDisplay | ExecMode::NumVersions(_) => {
+        ExecMode::BasicDisplay
+        | ExecMode::NumVersions(_)
+        | ExecMode::StaleThan(_)
+        | ExecMode::Stat(_) => {
             let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &GLOBAL_CONFIG.paths)?;
+            let exit_code = versions_map.exit_code();
             let output_buf = VersionsDisplayWrapper::from(&GLOBAL_CONFIG, versions_map).to_string();
 
-            print_output_buf(&output_buf)
+            print_output_buf(&output_buf)?;
+
+            return Ok(exit_code);
         }
         // ExecMode::NonInteractiveRecursive, ExecMode::SnapFileMount, and ExecMode::MountsForFiles will print their
         // output elsewhere
         ExecMode::NonInteractiveRecursive(_) => NonInteractiveRecursiveWrapper::exec(),
         ExecMode::SnapFileMount(snapshot_suffix) => SnapshotMounts::exec(snapshot_suffix),
+        ExecMode::SnapsForFiles(opt_filters) if GLOBAL_CONFIG.opt_dataset_snaps => {
+            let delimiter = delimiter();
+
+            let output_buf: String = DatasetSnapNames::snap_names(&GLOBAL_CONFIG.paths, opt_filters)?
+                .into_iter()
+                .map(|snap_name| format!("{snap_name}{delimiter}"))
+                .collect();
+
+            print_output_buf(&output_buf)
+        }
         ExecMode::SnapsForFiles(opt_filters) => {
             let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &GLOBAL_CONFIG.paths)?;
             let snap_name_map = SnapNameMap::new(versions_map, opt_filters)?;
@@ -189,5 +240,13 @@ fn exec() -> HttmResult<()> {
             print_output_buf(&output_buf)
         }
         ExecMode::RollForward(full_snap_name) => RollForward::new(full_snap_name)?.exec(),
+        ExecMode::CompareDatasets(first_root, second_root) => {
+            CompareDatasets::exec(first_root, second_root)
+        }
+        ExecMode::MountSnapshot(full_snap_name, target) => {
+            MountSnapshot::exec(full_snap_name, target)
+        }
+        ExecMode::JsonSchema => print_json_schema(),
     }
+    .map(|_| HttmExitCode::Success)
 }
@@ -50,8 +50,8 @@ impl Deref for MapOfAliases {
 impl MapOfAliases {
     pub fn new(
         opt_raw_aliases: Option<RawValues>,
-        opt_remote_dir: Option<&str>,
-        opt_local_dir: Option<&str>,
+        opt_raw_remote_dirs: Option<RawValues>,
+        opt_raw_local_dirs: Option<RawValues>,
         pwd: &Path,
     ) -> HttmResult<Option<MapOfAliases>> {
         let alias_values: Option<Vec<String>> = match std::env::var_os("HTTM_MAP_ALIASES") {
@@ -69,31 +69,43 @@ impl MapOfAliases {
             }),
         };
 
-        let opt_snap_dir = if let Some(value) = opt_remote_dir {
-            Some(PathBuf::from(value))
-        } else if std::env::var_os("HTTM_REMOTE_DIR").is_some() {
-            std::env::var_os("HTTM_REMOTE_DIR").map(|s| PathBuf::from(s))
-        } else {
-            // legacy env var name
-            std::env::var_os("HTTM_SNAP_POINT").map(|s| PathBuf::from(s))
+        let snap_dirs: Vec<PathBuf> = match opt_raw_remote_dirs {
+            Some(remote_dirs) => remote_dirs.map(PathBuf::from).collect(),
+            None => std::env::var_os("HTTM_REMOTE_DIR")
+                // legacy env var name
+                .or_else(|| std::env::var_os("HTTM_SNAP_POINT"))
+                .map(PathBuf::from)
+                .into_iter()
+                .collect(),
         };
 
-        if opt_snap_dir.is_some() || alias_values.is_some() {
-            let env_local_dir = std::env::var_os("HTTM_LOCAL_DIR").map(|s| PathBuf::from(s));
-
-            let opt_local_dir = if let Some(value) = opt_local_dir {
-                Some(PathBuf::from(value))
-            } else {
-                env_local_dir
+        if !snap_dirs.is_empty() || alias_values.is_some() {
+            let local_dirs: Vec<PathBuf> = match opt_raw_local_dirs {
+                Some(local_dirs) => local_dirs.map(PathBuf::from).collect(),
+                None => std::env::var_os("HTTM_LOCAL_DIR")
+                    .map(PathBuf::from)
+                    .into_iter()
+                    .collect(),
             };
 
-            // user defined dir exists?: check that path contains the hidden snapshot directory
-            let snap_point = opt_snap_dir.map(|snap_dir| {
-                // local relative dir can be set at cmdline or as an env var,
-                // but defaults to current working directory if empty
-                let local_dir = opt_local_dir.unwrap_or_else(|| pwd.to_path_buf());
+            if local_dirs.len() > snap_dirs.len() {
+                return Err(HttmError::new(
+                    "Each LOCAL_DIR specified must be paired with a REMOTE_DIR.  The nth LOCAL_DIR is paired with the nth REMOTE_DIR.",
+                )
+                .into());
+            }
 
-                (snap_dir, local_dir)
+            // user defined dir exists?: check that path contains the hidden snapshot directory
+            //
+            // the nth remote dir is paired with the nth local dir, which can be set at cmdline
+            // or as an env var, but defaults to current working directory if empty
+            let snap_points = snap_dirs.into_iter().enumerate().map(|(idx, snap_dir)| {
+                let local_dir = local_dirs
+                    .get(idx)
+                    .cloned()
+                    .unwrap_or_else(|| pwd.to_path_buf());
+
+                (local_dir, snap_dir)
             });
 
             let mut aliases_iter: Vec<(PathBuf, PathBuf)> = match alias_values {
@@ -116,9 +128,7 @@ impl MapOfAliases {
                 None => Vec::new(),
             };
 
-            if let Some(value) = snap_point {
-                aliases_iter.push(value)
-            }
+            aliases_iter.extend(snap_points);
 
             let map_of_aliases: BTreeMap<PathBuf, RemotePathAndFsType> = aliases_iter
                 .into_iter()
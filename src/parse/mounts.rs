@@ -46,6 +46,43 @@ pub const SMB_FSTYPE: &str = "smbfs";
 pub const NFS_FSTYPE: &str = "nfs";
 pub const AFP_FSTYPE: &str = "afpfs";
 pub const RESTIC_FSTYPE: &str = "restic";
+pub const APFS_FSTYPE: &str = "apfs";
+
+// the user may override the compiled in hidden snapshot directory names, such as for custom snapshot mounts,
+// or btrfs layouts which don't follow the snapper convention.  set once, as the config is generated,
+// before any mounts are parsed
+pub static OPT_SNAP_DIR_NAME: OnceLock<PathBuf> = OnceLock::new();
+
+pub struct SnapDirName;
+
+impl SnapDirName {
+    // the user's snapshot dir, where it exists upon this dataset, each of its entries is a snapshot
+    pub fn user_snap_dir(dataset_mount: &Path) -> Option<PathBuf> {
+        OPT_SNAP_DIR_NAME
+            .get()
+            .map(|snap_dir_name| dataset_mount.join(snap_dir_name))
+            .filter(|snap_dir| snap_dir.symlink_metadata().is_ok())
+    }
+
+    // is this path the top most dir of the user's snapshot dir, found at the root of a btrfs dataset?
+    pub fn is_user_hidden_dir(path: &Path) -> bool {
+        let Some(top_dir) = OPT_SNAP_DIR_NAME
+            .get()
+            .and_then(|snap_dir_name| snap_dir_name.components().next())
+        else {
+            return false;
+        };
+
+        path.file_name() == Some(top_dir.as_os_str())
+            && path.parent().is_some_and(|parent| {
+                GLOBAL_CONFIG
+                    .dataset_collection
+                    .map_of_datasets
+                    .get(parent)
+                    .is_some_and(|md| matches!(md.fs_type, FilesystemType::Btrfs(_)))
+            })
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum LinkType {
@@ -74,6 +111,16 @@ pub enum FilesystemType {
 }
 
 impl FilesystemType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilesystemType::Zfs => ZFS_FSTYPE,
+            FilesystemType::Btrfs(_) => BTRFS_FSTYPE,
+            FilesystemType::Nilfs2 => NILFS2_FSTYPE,
+            FilesystemType::Apfs => APFS_FSTYPE,
+            FilesystemType::Restic(_) => RESTIC_FSTYPE,
+        }
+    }
+
     pub fn new(dataset_mount: &Path) -> Option<FilesystemType> {
         // set fstype, known by whether there is a ZFS hidden snapshot dir in the root dir
         if dataset_mount
@@ -88,6 +135,9 @@ impl FilesystemType {
             .is_ok()
         {
             Some(FilesystemType::Btrfs(None))
+        } else if SnapDirName::user_snap_dir(dataset_mount).is_some() {
+            // ZFS always has its hidden snapshot dir, so a user snapshot dir alone is btrfs
+            Some(FilesystemType::Btrfs(None))
         } else {
             None
         }
@@ -101,7 +151,7 @@ pub struct DatasetMetadata {
     pub link_type: LinkType,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct FilterDirs {
     inner: BTreeSet<PathBuf>,
 }
@@ -116,7 +166,8 @@ impl Deref for FilterDirs {
 
 impl FilterDirs {
     pub fn is_filter_dir(&self, path: &Path) -> bool {
-        self.iter().any(|filter_dir| path == filter_dir)
+        // paths compare by component, same as the ordering of the set
+        self.inner.contains(path)
     }
 }
 
@@ -141,6 +192,9 @@ pub trait MaxLen {
 }
 
 impl MaxLen for FilterDirs {
+    // equal paths always have an equal number of components, so any path with more components
+    // than the deepest filter dir cannot be a filter dir.  when there are no filter dirs,
+    // we return usize::MAX, so that no path ever takes the early out
     fn max_len(&self) -> usize {
         self.inner
             .iter()
@@ -150,7 +204,7 @@ impl MaxLen for FilterDirs {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct MapOfDatasets {
     inner: BTreeMap<PathBuf, DatasetMetadata>,
 }
@@ -567,3 +621,55 @@ impl BaseFilesystemInfo {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter_dirs(dirs: &[&str]) -> FilterDirs {
+        FilterDirs {
+            inner: dirs.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    // mirrors the early out in SharedRecursive, which skips the set lookup
+    fn is_filter_dir_with_early_out(filter_dirs: &FilterDirs, path: &Path) -> bool {
+        if path.components().count() > filter_dirs.max_len() {
+            return false;
+        }
+
+        filter_dirs.is_filter_dir(path)
+    }
+
+    #[test]
+    fn filter_dir_early_out_never_skips_a_filter_dir() {
+        let filter_dirs = filter_dirs(&["/proc", "/srv/a/b/c/deeply/nested", "/sys/kernel"]);
+
+        assert_eq!(filter_dirs.max_len(), 7);
+
+        // the deepest filter dir sits exactly at the max len, and so is not skipped
+        filter_dirs.iter().for_each(|dir| {
+            assert!(is_filter_dir_with_early_out(&filter_dirs, dir));
+        });
+
+        assert!(!is_filter_dir_with_early_out(
+            &filter_dirs,
+            Path::new("/srv/a/b/c/deeply/nested/child")
+        ));
+        assert!(!is_filter_dir_with_early_out(
+            &filter_dirs,
+            Path::new("/srv/a")
+        ));
+    }
+
+    #[test]
+    fn no_filter_dirs_never_takes_the_early_out() {
+        let filter_dirs = filter_dirs(&[]);
+
+        assert_eq!(filter_dirs.max_len(), usize::MAX);
+        assert!(!is_filter_dir_with_early_out(
+            &filter_dirs,
+            Path::new("/proc")
+        ));
+    }
+}
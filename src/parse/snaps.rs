@@ -19,10 +19,11 @@ use crate::library::results::{HttmError, HttmResult};
 use crate::library::utility::{get_btrfs_command, user_has_effective_root};
 use crate::parse::mounts::BTRFS_ROOT_SUBVOL;
 use crate::parse::mounts::PROC_MOUNTS;
-use crate::parse::mounts::{DatasetMetadata, FilesystemType};
+use crate::parse::mounts::{DatasetMetadata, FilesystemType, SnapDirName};
 use crate::{
-    BTRFS_SNAPPER_HIDDEN_DIRECTORY, BTRFS_SNAPPER_SUFFIX, RESTIC_SNAPSHOT_DIRECTORY,
-    ROOT_DIRECTORY, TM_DIR_LOCAL, TM_DIR_REMOTE, ZFS_SNAPSHOT_DIRECTORY,
+    BTRFS_SNAPPER_HIDDEN_DIRECTORY, BTRFS_SNAPPER_SUFFIX, NILFS2_SNAPSHOT_ID_KEY,
+    RESTIC_SNAPSHOT_DIRECTORY, ROOT_DIRECTORY, TM_DIR_LOCAL, TM_DIR_REMOTE,
+    ZFS_SNAPSHOT_DIRECTORY,
 };
 use proc_mounts::MountIter;
 use rayon::prelude::*;
@@ -33,7 +34,7 @@ use std::path::{Path, PathBuf};
 use std::process::Command as ExecProcess;
 use std::sync::Once;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct MapOfSnaps {
     inner: BTreeMap<PathBuf, Vec<PathBuf>>,
 }
@@ -317,6 +318,21 @@ impl MapOfSnaps {
             mount_point_path: &Path,
             dataset_metadata: &DatasetMetadata,
         ) -> HttmResult<Vec<PathBuf>> {
+            // the user's snapshot dir replaces the compiled in default, where it exists upon this btrfs dataset,
+            // ZFS snapshots are always found at ZFS_SNAPSHOT_DIRECTORY, as ZFS snapshot paths are parsed as such
+            if let (FilesystemType::Btrfs(_), Some(snap_dir)) = (
+                &dataset_metadata.fs_type,
+                SnapDirName::user_snap_dir(mount_point_path),
+            ) {
+                let snaps = read_dir(snap_dir)?
+                    .flatten()
+                    .par_bridge()
+                    .map(|entry| entry.path())
+                    .collect();
+
+                return Ok(snaps);
+            }
+
             let snaps = match &dataset_metadata.fs_type {
                 FilesystemType::Btrfs(_) => {
                     read_dir(mount_point_path.join(BTRFS_SNAPPER_HIDDEN_DIRECTORY))?
@@ -389,8 +405,12 @@ impl MapOfSnaps {
                         .par_bridge()
                         .flatten()
                         .filter(|mount_info| Path::new(&mount_info.source) == source_path)
+                        // nilfs2 snapshots are the checkpoints mounted read only with a "cp=" option
                         .filter(|mount_info| {
-                            mount_info.options.iter().any(|opt| opt.contains("cp="))
+                            mount_info
+                                .options
+                                .iter()
+                                .any(|opt| opt.contains(NILFS2_SNAPSHOT_ID_KEY))
                         })
                         .map(|mount_info| PathBuf::from(mount_info.dest))
                         .collect()
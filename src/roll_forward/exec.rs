@@ -23,6 +23,7 @@ use crate::library::file_ops::Remove;
 use crate::library::results::{HttmError, HttmResult};
 use crate::library::utility::is_metadata_same;
 use crate::library::utility::user_has_effective_root;
+use crate::parse::mounts::FilesystemType;
 use crate::roll_forward::preserve_hard_links::PreserveHardLinks;
 use crate::roll_forward::preserve_hard_links::SpawnPreserveLinks;
 use crate::zfs::run_command::RunZFSCommand;
@@ -51,29 +52,73 @@ pub struct RollForward {
 
 impl RollForward {
     pub fn new(full_snap_name: &str) -> HttmResult<Self> {
-        let (dataset, snap) = if let Some(res) = full_snap_name.split_once('@') {
-            res
-        } else {
-            let msg = format!("\"{}\" is not a valid data set name.  A valid ZFS snapshot name requires a '@' separating dataset name and snapshot name.", &full_snap_name);
-            return Err(HttmError::new(&msg).into());
-        };
+        let (dataset, snap) = Self::split_full_snap_name(full_snap_name)?;
 
         let proximate_dataset_mount = GLOBAL_CONFIG
             .dataset_collection
             .map_of_datasets
             .iter()
             .find(|(_mount, md)| md.source == PathBuf::from(&dataset))
-            .map(|(mount, _)| mount.to_owned())
-            .ok_or_else(|| HttmError::new("Could not determine proximate dataset mount"))?;
+            .map(|(mount, md)| {
+                if !matches!(md.fs_type, FilesystemType::Zfs) {
+                    let msg = format!(
+                        "The dataset requested, \"{dataset}\", is not a ZFS dataset.  Roll forward is a ZFS only option."
+                    );
+                    return Err(HttmError::new(&msg));
+                }
+
+                Ok(mount.to_owned())
+            })
+            .unwrap_or_else(|| {
+                let msg = format!(
+                    "httm could not find the dataset requested, \"{dataset}\", among the mounted datasets.  \
+                    Is the dataset name correct, and is the dataset mounted?"
+                );
+                Err(HttmError::new(&msg))
+            })?;
 
         let progress_bar: ProgressBar = indicatif::ProgressBar::new_spinner();
 
-        Ok(Self {
+        let roll_forward = Self {
             dataset: dataset.to_string(),
             snap: snap.to_string(),
             progress_bar,
             proximate_dataset_mount,
-        })
+        };
+
+        // the snapshot must exist on that dataset, before we take any precautionary snapshot
+        let snap_dataset = roll_forward.snap_dataset();
+
+        if snap_dataset.symlink_metadata().is_err() {
+            let msg = format!(
+                "httm could not find the snapshot requested, \"{snap}\", on the dataset \"{dataset}\".  \
+                No such snapshot exists at: {snap_dataset:?}"
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(roll_forward)
+    }
+
+    // a valid ZFS snapshot name is a dataset name and a snapshot name separated by a single '@',
+    // such as "rpool/home@snap_2024-01-01"
+    pub fn split_full_snap_name(full_snap_name: &str) -> HttmResult<(&str, &str)> {
+        let Some((dataset, snap)) = full_snap_name.split_once('@') else {
+            let msg = format!("\"{full_snap_name}\" is not a valid snapshot name.  A valid ZFS snapshot name requires a '@' separating dataset name and snapshot name, such as \"rpool/home@snap_name\".");
+            return Err(HttmError::new(&msg).into());
+        };
+
+        if dataset.is_empty() || dataset.starts_with('/') || dataset.ends_with('/') {
+            let msg = format!("\"{full_snap_name}\" is not a valid snapshot name.  The dataset name, before the '@', must be a ZFS dataset name, such as \"rpool/home\", not a path or an empty string.");
+            return Err(HttmError::new(&msg).into());
+        }
+
+        if snap.is_empty() || snap.contains(['@', '/']) {
+            let msg = format!("\"{full_snap_name}\" is not a valid snapshot name.  The snapshot name, after the '@', must not be empty, and must not contain a '@' or a '/'.");
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok((dataset, snap))
     }
 
     pub fn full_name(&self) -> String {
@@ -374,17 +419,17 @@ impl RollForward {
         // we should make sure it has the latest data, so a simple rename is not enough
         // this is internal to the fn Self::remove()
         match &event.diff_type {
-            DiffType::Removed | DiffType::Modified => Self::copy(&snap_file_path, &event.path_buf),
-            DiffType::Created => Self::overwrite_or_remove(&snap_file_path, &event.path_buf),
+            DiffType::Removed | DiffType::Modified => self.copy(&snap_file_path, &event.path_buf),
+            DiffType::Created => self.overwrite_or_remove(&snap_file_path, &event.path_buf),
             DiffType::Renamed(new_file_name) => {
                 let snap_new_file_name = self.snap_path(new_file_name).ok_or_else(|| {
                     HttmError::new("Could not obtain snap file path for live version.")
                 })?;
 
-                Self::overwrite_or_remove(&snap_new_file_name, new_file_name)?;
+                self.overwrite_or_remove(&snap_new_file_name, new_file_name)?;
 
                 if snap_file_path.exists() {
-                    Self::copy(&snap_file_path, &event.path_buf)?
+                    self.copy(&snap_file_path, &event.path_buf)?
                 }
 
                 Ok(())
@@ -392,8 +437,8 @@ impl RollForward {
         }
     }
 
-    pub fn copy(src: &Path, dst: &Path) -> HttmResult<()> {
-        if let Err(err) = Copy::direct_quiet(src, dst, true) {
+    pub fn copy(&self, src: &Path, dst: &Path) -> HttmResult<()> {
+        if let Err(err) = Copy::direct_quiet(src, dst, &self.proximate_dataset_mount, true) {
             eprintln!("Error: {}", err);
             let msg = format!(
                 "Could not overwrite {:?} with snapshot file version {:?}",
@@ -418,23 +463,23 @@ impl RollForward {
         .collect()
     }
 
-    fn overwrite_or_remove(src: &Path, dst: &Path) -> HttmResult<()> {
+    fn overwrite_or_remove(&self, src: &Path, dst: &Path) -> HttmResult<()> {
         // overwrite
         if src.exists() {
-            return Self::copy(src, dst);
+            return self.copy(src, dst);
         }
 
         // or remove
-        Self::remove(dst)
+        self.remove(dst)
     }
 
-    pub fn remove(dst: &Path) -> HttmResult<()> {
+    pub fn remove(&self, dst: &Path) -> HttmResult<()> {
         // overwrite
         if !dst.exists() {
             return Ok(());
         }
 
-        match Remove::recursive_quiet(dst) {
+        match Remove::recursive_quiet(dst, &self.proximate_dataset_mount) {
             Ok(_) => {
                 if dst.exists() {
                     let msg = format!("File should not exist after deletion {:?}", dst);
@@ -184,7 +184,7 @@ impl<'a> PreserveHardLinks<'a> {
 
                     if !snap_path.exists() {
                         NONE_REMOVED.store(false, std::sync::atomic::Ordering::Relaxed);
-                        return Self::rm_hard_link(live_path.path());
+                        return self.rm_hard_link(live_path.path());
                     }
 
                     Ok(())
@@ -233,12 +233,12 @@ impl<'a> PreserveHardLinks<'a> {
 
                         match opt_original {
                             Some(original) if original == live_path => {
-                                RollForward::copy(snap_path, live_path)
+                                self.roll_forward.copy(snap_path, live_path)
                             }
                             Some(original) => self.hard_link(original, live_path),
                             None => {
                                 opt_original = Some(live_path);
-                                RollForward::copy(snap_path, live_path)
+                                self.roll_forward.copy(snap_path, live_path)
                             }
                         }
                     })
@@ -288,7 +288,7 @@ impl<'a> PreserveHardLinks<'a> {
         live_diff
             .clone()
             .par_bridge()
-            .try_for_each(|path| RollForward::remove(path))?;
+            .try_for_each(|path| self.roll_forward.remove(path))?;
 
         // only on snap dataset - means we want to copy these
         snap_diff.clone().par_bridge().try_for_each(|live_path| {
@@ -296,7 +296,7 @@ impl<'a> PreserveHardLinks<'a> {
                 RollForward::snap_path(self.roll_forward, live_path)
                     .ok_or_else(|| HttmError::new("Could obtain live path for snap path").into());
 
-            RollForward::copy(&snap_path?, live_path)
+            self.roll_forward.copy(&snap_path?, live_path)
         })?;
 
         let combined = live_diff.chain(snap_diff).cloned().collect();
@@ -322,7 +322,7 @@ impl<'a> PreserveHardLinks<'a> {
         orphans_intersection
             .clone()
             .par_bridge()
-            .try_for_each(|live_path| Self::rm_hard_link(live_path))?;
+            .try_for_each(|live_path| self.rm_hard_link(live_path))?;
 
         let res = orphans_intersection.cloned().collect();
 
@@ -347,7 +347,7 @@ impl<'a> PreserveHardLinks<'a> {
                 }
             }
 
-            Remove::recursive_quiet(link)?
+            Remove::recursive_quiet(link, &self.roll_forward.proximate_dataset_mount)?
         }
 
         Copy::generate_dst_parent(link)?;
@@ -371,8 +371,8 @@ impl<'a> PreserveHardLinks<'a> {
         Ok(())
     }
 
-    fn rm_hard_link(link: &Path) -> HttmResult<()> {
-        match Remove::recursive_quiet(link) {
+    fn rm_hard_link(&self, link: &Path) -> HttmResult<()> {
+        match Remove::recursive_quiet(link, &self.roll_forward.proximate_dataset_mount) {
             Ok(_) => {
                 if link.exists() {
                     let msg = format!("Target link should not exist after removal {:?}", link);
@@ -0,0 +1,195 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{print_output_buf, user_has_effective_root};
+use crate::parse::mounts::FilesystemType;
+use crate::{BTRFS_SNAPPER_SUFFIX, GLOBAL_CONFIG};
+use std::fs::read_dir;
+use std::path::{Path, PathBuf};
+use std::process::Command as ExecProcess;
+use which::which;
+
+// exposes a snapshot's full tree at a mount point of the user's choosing, read-only
+pub struct MountSnapshot {
+    full_snap_name: String,
+    fs_type: FilesystemType,
+    // the ZFS dataset name, or the btrfs dataset mount
+    dataset_source: PathBuf,
+    snap: String,
+    // where httm found the snapshot
+    snap_mount: PathBuf,
+    target: PathBuf,
+}
+
+impl MountSnapshot {
+    pub fn exec(full_snap_name: &str, target: &Path) -> HttmResult<()> {
+        let mount_snapshot = Self::new(full_snap_name, target)?;
+
+        mount_snapshot.mount()
+    }
+
+    fn new(full_snap_name: &str, target: &Path) -> HttmResult<Self> {
+        let Some((dataset, snap)) = full_snap_name.split_once('@').filter(|(dataset, snap)| {
+            !dataset.is_empty() && !snap.is_empty() && !snap.contains(['@', '/'])
+        }) else {
+            let msg = format!("\"{full_snap_name}\" is not a valid snapshot name.  A snapshot is specified by its dataset and snapshot name, separated by a '@', \
+                such as \"rpool/home@snap_name\" for ZFS, or, for btrfs, by the dataset's mount, such as \"/home@snap_name\".");
+            return Err(HttmError::new(&msg).into());
+        };
+
+        // a ZFS dataset is known by its name, a btrfs dataset by its mount
+        let Some((dataset_mount, dataset_md)) = GLOBAL_CONFIG
+            .dataset_collection
+            .map_of_datasets
+            .iter()
+            .find(|(mount, md)| match md.fs_type {
+                FilesystemType::Zfs => md.source == Path::new(dataset),
+                FilesystemType::Btrfs(_) => mount.as_path() == Path::new(dataset),
+                _ => false,
+            })
+        else {
+            let msg = format!(
+                "httm could not find the dataset requested, \"{dataset}\", among the mounted ZFS or btrfs datasets.  \
+                Is the dataset name correct, and is the dataset mounted?"
+            );
+            return Err(HttmError::new(&msg).into());
+        };
+
+        // the snapshot must be one httm has found for the dataset
+        let Some(snap_mount) = GLOBAL_CONFIG
+            .dataset_collection
+            .map_of_snaps
+            .get(dataset_mount)
+            .and_then(|snap_mounts| {
+                snap_mounts
+                    .iter()
+                    .find(|snap_mount| Self::snap_name(snap_mount) == Some(snap))
+            })
+        else {
+            let msg = format!(
+                "httm could not find the snapshot requested, \"{snap}\", on the dataset \"{dataset}\"."
+            );
+            return Err(HttmError::new(&msg).into());
+        };
+
+        // never mount over anything, a mount would hide the target's contents
+        let is_empty_dir = target.is_dir()
+            && read_dir(target)
+                .map(|mut entries| entries.next().is_none())
+                .unwrap_or(false);
+
+        if !is_empty_dir {
+            let msg = format!(
+                "The mount target {target:?} must be an existing, empty directory.  httm will not mount a snapshot over an existing directory's contents."
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(Self {
+            full_snap_name: full_snap_name.to_owned(),
+            fs_type: dataset_md.fs_type.clone(),
+            dataset_source: match dataset_md.fs_type {
+                FilesystemType::Zfs => dataset_md.source.clone(),
+                _ => dataset_mount.clone(),
+            },
+            snap: snap.to_owned(),
+            snap_mount: snap_mount.clone(),
+            target: target.to_path_buf(),
+        })
+    }
+
+    // snapper's snapshots are found at ".snapshots/<name>/snapshot", all others at ".../<name>"
+    fn snap_name(snap_mount: &Path) -> Option<&str> {
+        let snap_name = match snap_mount.file_name() {
+            Some(file_name) if file_name == BTRFS_SNAPPER_SUFFIX => {
+                snap_mount.parent().and_then(|parent| parent.file_name())
+            }
+            opt_file_name => opt_file_name,
+        };
+
+        snap_name.and_then(|snap_name| snap_name.to_str())
+    }
+
+    fn mount_args(&self) -> Vec<String> {
+        match self.fs_type {
+            FilesystemType::Zfs => vec![
+                "-t".to_owned(),
+                "zfs".to_owned(),
+                "-o".to_owned(),
+                "ro".to_owned(),
+                format!("{}@{}", self.dataset_source.to_string_lossy(), self.snap),
+                self.target.to_string_lossy().into_owned(),
+            ],
+            // a btrfs snapshot is already a dir upon the live filesystem, so we bind mount that dir
+            _ => vec![
+                "-o".to_owned(),
+                "bind,ro".to_owned(),
+                self.snap_mount.to_string_lossy().into_owned(),
+                self.target.to_string_lossy().into_owned(),
+            ],
+        }
+    }
+
+    fn mount(&self) -> HttmResult<()> {
+        // like a roll forward, a zfs allow of mount is not sufficient on Linux, as mount(2) requires root
+        if let Err(root_error) = user_has_effective_root("Mount a snapshot") {
+            // a dry run should be possible to audit before privileges are granted
+            if !GLOBAL_CONFIG.opt_dry_run {
+                return Err(root_error);
+            }
+
+            eprintln!("WARN: {root_error}  Continuing, as this is a dry run.");
+        }
+
+        let mount_command = which("mount").map_err(|_err| {
+            HttmError::new(
+                "'mount' command not found. Make sure the command 'mount' is in your path.",
+            )
+        })?;
+
+        let process_args = self.mount_args();
+
+        // only print what we would have done
+        if GLOBAL_CONFIG.opt_dry_run {
+            let output_buf = format!("{} {}\n", mount_command.display(), process_args.join(" "));
+            return print_output_buf(&output_buf);
+        }
+
+        let process_output = ExecProcess::new(&mount_command)
+            .args(&process_args)
+            .output()?;
+
+        if !process_output.status.success() {
+            let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+            let msg = format!(
+                "httm was unable to mount the snapshot \"{}\".  The 'mount' command issued the following error: {stderr_string}",
+                self.full_snap_name
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let output_buf = format!(
+            "httm mounted the snapshot \"{}\" read-only at {:?}.  Unmount with 'umount {}' when done.\n",
+            self.full_snap_name,
+            self.target,
+            self.target.display()
+        );
+
+        print_output_buf(&output_buf)
+    }
+}
@@ -19,9 +19,11 @@ use crate::data::paths::PathData;
 use crate::data::paths::PathDeconstruction;
 use crate::library::results::HttmError;
 use crate::library::results::HttmResult;
-use crate::library::utility::user_has_effective_root;
+use crate::library::utility::{print_output_buf, user_has_effective_root};
 use crate::parse::mounts::FilesystemType;
 use crate::roll_forward::exec::RollForward;
+use crate::GLOBAL_CONFIG;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command as ExecProcess, Stdio};
 use which::which;
@@ -44,6 +46,16 @@ impl RunZFSCommand {
 
         process_args.extend_from_slice(snapshot_names);
 
+        // only print what we would have done
+        if GLOBAL_CONFIG.opt_dry_run {
+            let output_buf = format!(
+                "{} {}\n",
+                self.zfs_command.display(),
+                process_args.join(" ")
+            );
+            return print_output_buf(&output_buf);
+        }
+
         let process_output = ExecProcess::new(&self.zfs_command)
             .args(&process_args)
             .output()?;
@@ -66,6 +78,155 @@ impl RunZFSCommand {
         Ok(())
     }
 
+    pub fn set_property(&self, property: &str, snapshot_names: &[String]) -> HttmResult<()> {
+        let mut process_args = vec!["set".to_owned(), property.to_owned()];
+
+        process_args.extend_from_slice(snapshot_names);
+
+        let process_output = ExecProcess::new(&self.zfs_command)
+            .args(&process_args)
+            .output()?;
+        let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+
+        // stderr_string is a string not an error, so here we build an err or output
+        if !stderr_string.is_empty() {
+            let msg = if stderr_string.contains("permission denied") {
+                "httm must have root or 'zfs allow' userprop privileges to set a snapshot property"
+                    .to_owned()
+            } else {
+                "httm was unable to set a snapshot property. The 'zfs' command issued the following error: "
+                    .to_owned()
+                    + stderr_string
+            };
+
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(())
+    }
+
+    // the 'written' property is the space written to a dataset since its latest snapshot
+    pub fn written(&self, fs_name: &str) -> HttmResult<u64> {
+        let process_args = vec!["get", "-Hp", "-o", "value", "written", fs_name];
+
+        let process_output = ExecProcess::new(&self.zfs_command)
+            .args(&process_args)
+            .output()?;
+        let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+        let stdout_string: &str = std::str::from_utf8(&process_output.stdout)?.trim();
+
+        // stderr_string is a string not an error, so here we build an err or output
+        if !stderr_string.is_empty() {
+            let msg = "httm was unable to read the 'written' property of the dataset. The 'zfs' command issued the following error: ".to_owned() + stderr_string;
+
+            return Err(HttmError::new(&msg).into());
+        }
+
+        stdout_string.parse::<u64>().map_err(|_err| {
+            let msg =
+                format!("httm could not parse the 'written' property value: {stdout_string:?}");
+            HttmError::new(&msg).into()
+        })
+    }
+
+    // the space used by a dataset, and the portion of that space used by its snapshots
+    pub fn usage(&self, fs_name: &str) -> HttmResult<(u64, u64)> {
+        let process_args = vec!["list", "-Hp", "-o", "used,usedbysnapshots", fs_name];
+
+        let process_output = ExecProcess::new(&self.zfs_command)
+            .args(&process_args)
+            .output()?;
+        let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+        let stdout_string: &str = std::str::from_utf8(&process_output.stdout)?.trim();
+
+        // stderr_string is a string not an error, so here we build an err or output
+        if !stderr_string.is_empty() {
+            let msg = "httm was unable to read the space used by the dataset. The 'zfs' command issued the following error: ".to_owned() + stderr_string;
+
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let mut values = stdout_string
+            .split_ascii_whitespace()
+            .map(|value| value.parse::<u64>());
+
+        match (values.next(), values.next()) {
+            (Some(Ok(used)), Some(Ok(used_by_snapshots))) => Ok((used, used_by_snapshots)),
+            _ => {
+                let msg = format!(
+                    "httm could not parse the 'used' and 'usedbysnapshots' property values: {stdout_string:?}"
+                );
+                Err(HttmError::new(&msg).into())
+            }
+        }
+    }
+
+    // the 'used' property of a snapshot is the space unique to it, which destroying it would reclaim
+    pub fn snapshot_usage(&self, snapshot_names: &[String]) -> HttmResult<BTreeMap<String, u64>> {
+        let mut process_args = vec![
+            "list".to_owned(),
+            "-Hp".to_owned(),
+            "-t".to_owned(),
+            "snapshot".to_owned(),
+            "-o".to_owned(),
+            "name,used".to_owned(),
+        ];
+
+        process_args.extend_from_slice(snapshot_names);
+
+        let process_output = ExecProcess::new(&self.zfs_command)
+            .args(&process_args)
+            .output()?;
+        let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+        let stdout_string: &str = std::str::from_utf8(&process_output.stdout)?.trim();
+
+        // stderr_string is a string not an error, so here we build an err or output
+        if !stderr_string.is_empty() {
+            let msg = "httm was unable to read the space used by the snapshots. The 'zfs' command issued the following error: ".to_owned() + stderr_string;
+
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let map = stdout_string
+            .lines()
+            .filter_map(|line| {
+                let (name, used) = line.split_once('\t')?;
+                used.trim()
+                    .parse::<u64>()
+                    .ok()
+                    .map(|used| (name.to_owned(), used))
+            })
+            .collect();
+
+        Ok(map)
+    }
+
+    // ZFS user property names must contain a colon, and may only contain
+    // lowercase letters, numbers, and the ':', '-', '.', and '_' characters
+    pub fn validate_user_property(property: &str) -> HttmResult<()> {
+        const MAX_USER_PROPERTY_NAME_LEN: usize = 256;
+
+        let Some((name, _value)) = property.split_once('=') else {
+            let msg = format!("User property is not in the form KEY=VALUE: {property:?}");
+            return Err(HttmError::new(&msg).into());
+        };
+
+        if !name.contains(':')
+            || name.starts_with(['-', ':', '.'])
+            || name.len() > MAX_USER_PROPERTY_NAME_LEN
+            || !name.chars().all(|c| {
+                c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, ':' | '-' | '.' | '_')
+            })
+        {
+            let msg = format!(
+                "User property name is not a legal ZFS user property name (for instance, \"httm:batch\"): {name:?}"
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(())
+    }
+
     pub fn rollback(&self, snapshot_names: &[String]) -> HttmResult<()> {
         let mut process_args = vec!["rollback".to_owned(), "-r".to_owned()];
 
@@ -212,6 +373,12 @@ impl ZfsAllowPriv {
 
         if let Err(root_error) = user_has_effective_root(msg) {
             if let Err(_allow_priv_error) = self.user_has_zfs_allow_priv(fs_name) {
+                // a dry run should be possible to audit before privileges are granted
+                if GLOBAL_CONFIG.opt_dry_run {
+                    eprintln!("WARN: {root_error}  Continuing, as this is a dry run.");
+                    return Ok(());
+                }
+
                 return Err(root_error);
             }
         }
@@ -15,7 +15,7 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use crate::library::results::HttmResult;
+use crate::library::results::{HttmError, HttmResult};
 use crate::library::utility::{date_string, DateFormat};
 use crate::zfs::run_command::ZfsAllowPriv;
 use crate::{print_output_buf, GLOBAL_CONFIG};
@@ -30,6 +30,20 @@ pub enum PrecautionarySnapType {
     PreRestore,
 }
 
+impl PrecautionarySnapType {
+    // the suffix given to a snapshot name template, which notes the type of precautionary snapshot,
+    // so pre and post snapshots taken within the same second remain distinct
+    fn template_suffix(&self) -> String {
+        match self {
+            PrecautionarySnapType::PreRollForward => "pre_httmSnapRollForward".to_owned(),
+            PrecautionarySnapType::PostRollForward(additional_snap_info_str) => {
+                format!("post_:{additional_snap_info_str}:_httmSnapRollForward")
+            }
+            PrecautionarySnapType::PreRestore => "pre_httmSnapRestore".to_owned(),
+        }
+    }
+}
+
 impl TryFrom<&Path> for SnapGuard {
     type Error = Box<dyn std::error::Error + Send + Sync>;
 
@@ -57,8 +71,13 @@ impl SnapGuard {
             DateFormat::Timestamp,
         );
 
-        let new_snap_name = match &snap_type {
-            PrecautionarySnapType::PreRollForward => {
+        let new_snap_name = match (&GLOBAL_CONFIG.opt_snap_name_template, &snap_type) {
+            (Some(template), _) => template.render_precautionary(
+                dataset_name,
+                &timestamp,
+                &snap_type.template_suffix(),
+            )?,
+            (None, PrecautionarySnapType::PreRollForward) => {
                 // all snapshots should have the same timestamp
                 let new_snap_name = format!(
                     "{}@snap_pre_{}_httmSnapRollForward",
@@ -67,7 +86,7 @@ impl SnapGuard {
 
                 new_snap_name
             }
-            PrecautionarySnapType::PostRollForward(additional_snap_info_str) => {
+            (None, PrecautionarySnapType::PostRollForward(additional_snap_info_str)) => {
                 let new_snap_name = format!(
                     "{}@snap_post_{}_:{}:_httmSnapRollForward",
                     dataset_name, timestamp, additional_snap_info_str
@@ -75,7 +94,7 @@ impl SnapGuard {
 
                 new_snap_name
             }
-            PrecautionarySnapType::PreRestore => {
+            (None, PrecautionarySnapType::PreRestore) => {
                 // all snapshots should have the same timestamp
                 let new_snap_name =
                     format!("{}@snap_pre_{}_httmSnapRestore", dataset_name, timestamp);
@@ -88,6 +107,14 @@ impl SnapGuard {
 
         run_zfs.snapshot(&[new_snap_name.clone()])?;
 
+        // a dry run has printed the snapshot command, and took no snapshot
+        if GLOBAL_CONFIG.opt_dry_run {
+            return Ok(SnapGuard {
+                new_snap_name,
+                dataset_name: dataset_name.to_string(),
+            });
+        }
+
         let output_buf = match &snap_type {
             PrecautionarySnapType::PreRollForward | PrecautionarySnapType::PreRestore => {
                 format!(
@@ -112,10 +139,32 @@ impl SnapGuard {
     }
 
     pub fn rollback(&self) -> HttmResult<()> {
+        self.rollback_to(&self.new_snap_name)
+    }
+
+    // snap_name may be either a full snapshot name, "rpool/kimono@snap_name", or only
+    // the part after the "@", but the snapshot must always belong to the guarded dataset
+    pub fn rollback_to(&self, snap_name: &str) -> HttmResult<()> {
+        let full_snap_name = match snap_name.split_once('@') {
+            Some((dataset_name, _snap_suffix)) if dataset_name != self.dataset_name => {
+                let msg = format!(
+                    "Snapshot {snap_name:?} does not belong to the guarded dataset {:?}, and httm will not roll back to it.",
+                    self.dataset_name
+                );
+                return Err(HttmError::new(&msg).into());
+            }
+            Some((_dataset_name, snap_suffix)) if !snap_suffix.is_empty() => snap_name.to_owned(),
+            None if !snap_name.is_empty() => format!("{}@{}", self.dataset_name, snap_name),
+            _ => {
+                let msg = format!("Snapshot name {snap_name:?} is not a valid snapshot name.");
+                return Err(HttmError::new(&msg).into());
+            }
+        };
+
         ZfsAllowPriv::Rollback.from_fs_name(&self.dataset_name)?;
 
         let run_zfs = RunZFSCommand::new()?;
-        run_zfs.rollback(&[self.new_snap_name.to_owned()])?;
+        run_zfs.rollback(&[full_snap_name])?;
 
         Ok(())
     }
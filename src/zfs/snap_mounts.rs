@@ -15,53 +15,440 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use crate::config::generate::PrintMode;
+use crate::config::generate::{PrintMode, SnapshotOutput};
+use crate::data::paths::PathDeconstruction;
+use crate::display_map::format::JsonOutput;
 use crate::library::iter_extensions::HttmIter;
 use crate::library::results::{HttmError, HttmResult};
-use crate::library::utility::{date_string, delimiter, print_output_buf, DateFormat};
+use crate::library::utility::{
+    date_string, delimiter, get_btrfs_command, print_output_buf, user_has_effective_root,
+    DateFormat,
+};
 use crate::lookup::file_mounts::MountDisplay;
 use crate::lookup::file_mounts::MountsForFiles;
-use crate::GLOBAL_CONFIG;
+use crate::parse::mounts::FilesystemType;
+use crate::{BTRFS_SNAPPER_HIDDEN_DIRECTORY, BTRFS_SNAPPER_SUFFIX, GLOBAL_CONFIG};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
 use std::collections::BTreeMap;
+use std::fs::{create_dir_all, read_to_string, remove_dir};
+use std::path::{Path, PathBuf};
+use std::process::Command as ExecProcess;
 use std::time::SystemTime;
 
 use super::run_command::{RunZFSCommand, ZfsAllowPriv};
 
+const SNAP_NAME_PLACEHOLDERS: [&str; 4] = ["{dataset}", "{timestamp}", "{suffix}", "{hostname}"];
+
+// ZFS also allows a space, which we disallow, as it is too easily mangled by shell scripts
+const SNAP_NAME_LEGAL_PUNCTUATION: [char; 4] = ['_', '-', ':', '.'];
+
+// ZFS_MAX_DATASET_NAME_LEN, including the NUL terminator
+const SNAP_NAME_MAX_LEN: usize = 256;
+
+// a user requested snapshot name, such as "{dataset}@autosnap-{timestamp}", in place of
+// the default "{dataset}@snap_{timestamp}_{suffix}"
+#[derive(Debug, Clone)]
+pub struct SnapNameTemplate {
+    snap_part: String,
+}
+
+impl SnapNameTemplate {
+    pub fn new(template: &str) -> HttmResult<Self> {
+        let Some((dataset_part, snap_part)) = template.split_once('@') else {
+            let msg = format!(
+                "Snapshot name template {template:?} is missing the '@' which separates the dataset from the snapshot name."
+            );
+            return Err(HttmError::new(&msg).into());
+        };
+
+        // httm only ever snapshots the dataset of each path
+        if dataset_part != "{dataset}" {
+            let msg = format!(
+                "Snapshot name template {template:?} must begin with \"{{dataset}}@\", as httm may only snapshot the dataset of each path."
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        // a dataset name contains '/', which a snapshot name may not
+        if snap_part.contains("{dataset}") {
+            let msg = format!(
+                "Snapshot name template {template:?} may only contain \"{{dataset}}\" before the '@'."
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        // an unknown placeholder is likely a typo, which would otherwise be taken literally
+        let remainder = SNAP_NAME_PLACEHOLDERS
+            .iter()
+            .fold(snap_part.to_owned(), |remainder, placeholder| {
+                remainder.replace(placeholder, "")
+            });
+
+        if remainder.contains(['{', '}']) {
+            let msg = format!(
+                "Snapshot name template {template:?} contains an unknown placeholder.  Known placeholders are: {}",
+                SNAP_NAME_PLACEHOLDERS.join(", ")
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let snap_name_template = Self {
+            snap_part: snap_part.to_owned(),
+        };
+
+        // must produce a legal snapshot name, when given typical values
+        snap_name_template.render(
+            "pool/dataset",
+            "2024-01-31-12:00:00_UTC",
+            "httmSnapFileMount",
+        )?;
+
+        Ok(snap_name_template)
+    }
+
+    // a precautionary snapshot must always note its type, and pre and post snapshots taken within
+    // the same second must remain distinct, so a template without a suffix has one appended
+    pub fn render_precautionary(
+        &self,
+        dataset: &str,
+        timestamp: &str,
+        suffix: &str,
+    ) -> HttmResult<String> {
+        if self.snap_part.contains("{suffix}") {
+            return self.render(dataset, timestamp, suffix);
+        }
+
+        Self {
+            snap_part: format!("{}_{{suffix}}", self.snap_part),
+        }
+        .render(dataset, timestamp, suffix)
+    }
+
+    pub fn render(&self, dataset: &str, timestamp: &str, suffix: &str) -> HttmResult<String> {
+        let mut snap_part = self
+            .snap_part
+            .replace("{timestamp}", timestamp)
+            .replace("{suffix}", suffix);
+
+        if snap_part.contains("{hostname}") {
+            snap_part = snap_part.replace("{hostname}", &Self::hostname()?);
+        }
+
+        let snap_name = format!("{dataset}@{snap_part}");
+
+        let is_legal = !snap_part.is_empty()
+            && snap_name.len() < SNAP_NAME_MAX_LEN
+            && snap_part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || SNAP_NAME_LEGAL_PUNCTUATION.contains(&c));
+
+        if !is_legal {
+            let msg = format!(
+                "Snapshot name template produced an illegal ZFS snapshot name: {snap_name:?}.  A snapshot name may only contain alphanumeric characters, and the characters: {:?}",
+                SNAP_NAME_LEGAL_PUNCTUATION
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(snap_name)
+    }
+
+    fn hostname() -> HttmResult<String> {
+        let mut buffer = [0u8; 256];
+
+        // SAFETY: gethostname writes at most buffer.len() bytes to the buffer we own
+        let res = unsafe { nix::libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) };
+
+        if res != 0 {
+            return Err(HttmError::with_context(
+                "httm could not determine the hostname for the snapshot name template",
+                &std::io::Error::last_os_error(),
+            )
+            .into());
+        }
+
+        let len = buffer
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(buffer.len());
+
+        Ok(String::from_utf8_lossy(&buffer[..len]).into_owned())
+    }
+}
+
 pub struct SnapshotMounts;
 
 impl SnapshotMounts {
     pub fn exec(requested_snapshot_suffix: &str) -> HttmResult<()> {
-        let mounts_for_files: MountsForFiles = MountsForFiles::new(&MountDisplay::Target)?;
-
-        let map_snapshot_names =
-            Self::snapshot_names(&mounts_for_files, requested_snapshot_suffix)?;
-
-        let run_zfs = RunZFSCommand::new()?;
-
-        map_snapshot_names.values().try_for_each(|snapshot_names| {
-            run_zfs.snapshot(snapshot_names)?;
-
-            let output_buf: String = snapshot_names
-                .iter()
-                .map(|snap_name| {
-                    if matches!(
-                        GLOBAL_CONFIG.print_mode,
-                        PrintMode::RawNewline | PrintMode::RawZero
-                    ) {
-                        let delimiter = delimiter();
-                        format!("{}{delimiter}", &snap_name)
-                    } else {
-                        format!("httm took a snapshot named: {}\n", &snap_name)
-                    }
-                })
+        // all snapshots should have the same timestamp
+        let timestamp = date_string(
+            GLOBAL_CONFIG.requested_utc_offset,
+            &SystemTime::now(),
+            DateFormat::Timestamp,
+        );
+
+        let snap_results = Self::snapshot_mounts_collect(requested_snapshot_suffix, &timestamp)?;
+
+        // a dry run prints only the commands which would have been run
+        let is_json = !GLOBAL_CONFIG.opt_dry_run
+            && matches!(
+                GLOBAL_CONFIG.opt_snapshot_output,
+                Some(SnapshotOutput::Json)
+            );
+
+        let succeeded = snap_results
+            .iter()
+            .filter(|snap_result| snap_result.succeeded);
+
+        // json is printed all at once, after every snapshot has been taken
+        if is_json {
+            let snapshots_taken: Vec<SnapshotTaken> = succeeded
+                .map(|snap_result| SnapshotTaken::new(snap_result, &timestamp))
                 .collect();
 
-            print_output_buf(&output_buf)
-        })?;
+            print_output_buf(&SnapshotTaken::to_json(&snapshots_taken)?)?;
+        } else {
+            let output_buf: String = succeeded
+                .map(|snap_result| Self::snapshot_output(&snap_result.snapshot_name))
+                .collect();
+
+            print_output_buf(&output_buf)?;
+        }
+
+        let failed: Vec<&str> = snap_results
+            .iter()
+            .filter(|snap_result| !snap_result.succeeded)
+            .map(|snap_result| snap_result.snapshot_name.as_str())
+            .collect();
+
+        if !failed.is_empty() {
+            let msg = format!("httm was unable to take the following snapshots: {failed:?}");
+            return Err(HttmError::new(&msg).into());
+        }
 
         Ok(())
     }
 
+    // takes each snapshot requested, and returns the result of each, printing nothing but warnings,
+    // so a caller may react to each snapshot, instead of to printed text.  a dry run takes no snapshots,
+    // and so returns no results
+    pub fn snapshot_mounts_collect(
+        requested_snapshot_suffix: &str,
+        timestamp: &str,
+    ) -> HttmResult<Vec<SnapResult>> {
+        let (vec_fs_names, vec_btrfs_mounts) = match &GLOBAL_CONFIG.opt_datasets_from {
+            Some(datasets_file) => (Self::datasets_from(datasets_file)?, Vec::new()),
+            None => {
+                let mounts_for_files: MountsForFiles = MountsForFiles::new(&MountDisplay::Target)?;
+
+                Self::fs_names(&mounts_for_files)?
+            }
+        };
+
+        // resolve every ZFS snapshot name before taking any snapshot, so a request which fails
+        // to resolve never leaves behind btrfs snapshots already taken.  a request which resolves
+        // only to btrfs subvolumes never needs the 'zfs' command
+        let opt_zfs = if vec_fs_names.is_empty() && !vec_btrfs_mounts.is_empty() {
+            None
+        } else {
+            let run_zfs = RunZFSCommand::new()?;
+
+            let map_snapshot_names =
+                Self::snapshot_names(vec_fs_names, requested_snapshot_suffix, timestamp, &run_zfs)?;
+
+            Some((run_zfs, map_snapshot_names))
+        };
+
+        let mut snap_results =
+            Self::snapshot_btrfs(&vec_btrfs_mounts, requested_snapshot_suffix, timestamp)?;
+
+        let Some((run_zfs, map_snapshot_names)) = opt_zfs else {
+            return Ok(snap_results);
+        };
+
+        map_snapshot_names
+            .iter()
+            .for_each(|(pool, snapshot_names)| {
+                // the snapshots of a pool are taken all at once, and so succeed or fail all at once
+                let succeeded = match run_zfs.snapshot(snapshot_names) {
+                    Ok(_) => true,
+                    Err(err) => {
+                        eprintln!("WARN: {err}");
+                        false
+                    }
+                };
+
+                if GLOBAL_CONFIG.opt_dry_run {
+                    return;
+                }
+
+                // a label which can't be set should not fail the snapshot which has already been taken
+                if let (true, Some(labels)) = (succeeded, &GLOBAL_CONFIG.opt_snap_labels) {
+                    labels.iter().for_each(|label| {
+                        if let Err(err) = run_zfs.set_property(label, snapshot_names) {
+                            eprintln!("WARN: Could not set label {label:?}: {err}");
+                        }
+                    });
+                }
+
+                snap_results.extend(snapshot_names.iter().map(|snap_name| {
+                    SnapResult {
+                        dataset: snap_name
+                            .split_once('@')
+                            .map_or(snap_name.as_str(), |(dataset, _snap_name)| dataset)
+                            .to_owned(),
+                        snapshot_name: snap_name.clone(),
+                        pool: pool.clone(),
+                        succeeded,
+                    }
+                }));
+            });
+
+        Ok(snap_results)
+    }
+
+    fn snapshot_output(snap_name: &str) -> String {
+        if matches!(
+            GLOBAL_CONFIG.print_mode,
+            PrintMode::RawNewline | PrintMode::RawZero
+        ) || matches!(
+            GLOBAL_CONFIG.opt_snapshot_output,
+            Some(SnapshotOutput::Names)
+        ) {
+            let delimiter = delimiter();
+            return format!("{}{delimiter}", &snap_name);
+        }
+
+        format!("httm took a snapshot named: {}\n", &snap_name)
+    }
+
+    // btrfs has no pools, and each snapshot is taken one subvolume at a time, into the
+    // same snapper-like layout httm searches, "<mount>/.snapshots/<name>/snapshot"
+    fn snapshot_btrfs(
+        vec_btrfs_mounts: &[PathBuf],
+        requested_snapshot_suffix: &str,
+        timestamp: &str,
+    ) -> HttmResult<Vec<SnapResult>> {
+        if vec_btrfs_mounts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        if let Err(root_error) = user_has_effective_root("A snapshot of a btrfs subvolume") {
+            if !GLOBAL_CONFIG.opt_dry_run {
+                return Err(root_error);
+            }
+
+            eprintln!("WARN: {root_error}  Continuing, as this is a dry run.");
+        }
+
+        let btrfs_command = get_btrfs_command()?;
+
+        if GLOBAL_CONFIG.opt_snap_labels.is_some() {
+            eprintln!("WARN: Labels are only supported for ZFS snapshots, and will not be set on btrfs snapshots.");
+        }
+
+        if GLOBAL_CONFIG.opt_snapshot_if_changed {
+            eprintln!("WARN: httm cannot determine whether a btrfs subvolume has changed since its latest snapshot.  Proceeding with btrfs snapshots.");
+        }
+
+        let snap_results = vec_btrfs_mounts
+            .iter()
+            .filter_map(|mount| {
+                let snap_dir = mount
+                    .join(BTRFS_SNAPPER_HIDDEN_DIRECTORY)
+                    .join(format!("snap_{timestamp}_{requested_snapshot_suffix}"));
+
+                let snap_path = snap_dir.join(BTRFS_SNAPPER_SUFFIX);
+
+                // only print what we would have done
+                if GLOBAL_CONFIG.opt_dry_run {
+                    let output_buf = format!(
+                        "{} subvolume snapshot -r {} {}\n",
+                        btrfs_command.display(),
+                        mount.display(),
+                        snap_path.display()
+                    );
+
+                    return print_output_buf(&output_buf).err().map(Err);
+                }
+
+                let succeeded =
+                    match Self::snapshot_btrfs_subvol(mount, &snap_dir, &snap_path, &btrfs_command)
+                    {
+                        Ok(_) => true,
+                        Err(err) => {
+                            eprintln!("WARN: {err}");
+                            false
+                        }
+                    };
+
+                let device = GLOBAL_CONFIG
+                    .dataset_collection
+                    .map_of_datasets
+                    .get(mount)
+                    .map(|metadata| metadata.source.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+
+                Some(Ok(SnapResult {
+                    dataset: mount.to_string_lossy().into_owned(),
+                    snapshot_name: snap_path.to_string_lossy().into_owned(),
+                    pool: device,
+                    succeeded,
+                }))
+            })
+            .collect::<HttmResult<Vec<SnapResult>>>()?;
+
+        Ok(snap_results)
+    }
+
+    fn snapshot_btrfs_subvol(
+        mount: &Path,
+        snap_dir: &Path,
+        snap_path: &Path,
+        btrfs_command: &Path,
+    ) -> HttmResult<()> {
+        create_dir_all(snap_dir)?;
+
+        let process_output = ExecProcess::new(btrfs_command)
+            .args(["subvolume", "snapshot", "-r"])
+            .arg(mount)
+            .arg(snap_path)
+            .output()?;
+
+        if !process_output.status.success() {
+            // don't leave behind an empty snapshot dir
+            let _ = remove_dir(snap_dir);
+
+            let stderr_string = String::from_utf8_lossy(&process_output.stderr);
+            let msg = format!(
+                "httm was unable to take a snapshot of btrfs subvolume {mount:?}.  The 'btrfs' command issued the following error: {}",
+                stderr_string.trim()
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(())
+    }
+
+    fn is_changed(fs_name: &str, run_zfs: &RunZFSCommand) -> bool {
+        match run_zfs.written(fs_name) {
+            Ok(0) => {
+                eprintln!(
+                    "NOTICE: httm skipped a snapshot of dataset {fs_name:?}, as nothing has been written to it since its latest snapshot."
+                );
+                false
+            }
+            Ok(_) => true,
+            // reading a property requires fewer privileges than taking a snapshot,
+            // so if we can't read it, we proceed and let the snapshot succeed or fail on its own
+            Err(err) => {
+                eprintln!("WARN: {err}  Proceeding with snapshot of dataset {fs_name:?}.");
+                true
+            }
+        }
+    }
+
     pub fn pool_from_snap_name(snapshot_name: &str) -> HttmResult<String> {
         // split on "/" why?  because a snap looks like: rpool/kimono@snap...
         // splits according to pool name, then the rest of the snap name
@@ -76,36 +463,148 @@ impl SnapshotMounts {
         }
     }
 
+    pub fn dataset_from_snap_name(snapshot_name: &str) -> HttmResult<String> {
+        // split on "@" why?  because a snap looks like: rpool/kimono@snap...
+        match snapshot_name.split_once('@') {
+            Some((dataset_name, _snap_name)) => Ok(dataset_name.into()),
+            None => {
+                let msg = format!(
+                    "Could not determine dataset name from the snapshot name: {snapshot_name}"
+                );
+                Err(HttmError::new(&msg).into())
+            }
+        }
+    }
+
+    // groups snapshot names by the key (such as the pool or dataset name) of each, sorted and deduped
+    pub fn group_snapshot_names(
+        vec_snapshot_names: Vec<String>,
+        key_from_snap_name: fn(&str) -> HttmResult<String>,
+    ) -> BTreeMap<String, Vec<String>> {
+        vec_snapshot_names
+            .into_iter()
+            .into_group_map_by(|snapshot_name| {
+                key_from_snap_name(snapshot_name).unwrap_or_else(|err| {
+                    eprintln!("ERROR: {:?}", err);
+                    std::process::exit(1)
+                })
+            })
+            .iter_mut()
+            .map(|(key, group)| {
+                group.sort();
+                group.dedup();
+                (key.clone(), group.clone())
+            })
+            .collect()
+    }
+
+    fn fs_names(mounts_for_files: &MountsForFiles) -> HttmResult<(Vec<String>, Vec<PathBuf>)> {
+        let mut vec_fs_names: Vec<String> = Vec::new();
+        let mut vec_btrfs_mounts: Vec<PathBuf> = Vec::new();
+
+        mounts_for_files.iter().try_for_each(|prox| {
+            let pathdata = prox.pathdata;
+
+            if let Some(FilesystemType::Btrfs(_)) = pathdata.fs_type(Some(prox.proximate_dataset)) {
+                vec_btrfs_mounts.push(prox.proximate_dataset.to_path_buf());
+                return Ok(());
+            }
+
+            let fs_name = ZfsAllowPriv::Snapshot
+                .from_opt_proximate_dataset(&pathdata, Some(prox.proximate_dataset))
+                .map_err(|err| HttmError::from(err))?;
+
+            vec_fs_names.push(fs_name.to_string_lossy().into_owned());
+
+            Ok::<(), HttmError>(())
+        })?;
+
+        // many files may share a subvolume
+        vec_btrfs_mounts.sort();
+        vec_btrfs_mounts.dedup();
+
+        Ok((vec_fs_names, vec_btrfs_mounts))
+    }
+
+    // each line may be either a dataset name or a dataset mount, and either must be
+    // a ZFS dataset we already know about
+    fn datasets_from(datasets_file: &Path) -> HttmResult<Vec<String>> {
+        let contents = read_to_string(datasets_file).map_err(|err| {
+            let msg = format!("httm could not read the datasets file {datasets_file:?}");
+            HttmError::with_context(&msg, &err)
+        })?;
+
+        let map_of_datasets = &GLOBAL_CONFIG.dataset_collection.map_of_datasets;
+
+        let mut vec_fs_names: Vec<String> = Vec::new();
+
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let opt_metadata = if line.starts_with('/') {
+                map_of_datasets.get(Path::new(line))
+            } else {
+                map_of_datasets
+                    .values()
+                    .find(|metadata| metadata.source == Path::new(line))
+            };
+
+            let fs_name = match opt_metadata {
+                Some(metadata) if matches!(metadata.fs_type, FilesystemType::Zfs) => {
+                    metadata.source.to_string_lossy().into_owned()
+                }
+                _ if GLOBAL_CONFIG.opt_ignore_unknown => {
+                    eprintln!(
+                        "WARN: {line:?} is not a known ZFS dataset, or dataset mount.  Skipping."
+                    );
+                    continue;
+                }
+                _ => {
+                    let msg = format!(
+                        "{line:?} is not a known ZFS dataset, or dataset mount.  Quitting.  See also IGNORE_UNKNOWN."
+                    );
+                    return Err(HttmError::new(&msg).into());
+                }
+            };
+
+            ZfsAllowPriv::Snapshot.from_fs_name(&fs_name)?;
+
+            vec_fs_names.push(fs_name);
+        }
+
+        Ok(vec_fs_names)
+    }
+
     fn snapshot_names(
-        mounts_for_files: &MountsForFiles,
+        mut vec_fs_names: Vec<String>,
         requested_snapshot_suffix: &str,
+        timestamp: &str,
+        run_zfs: &RunZFSCommand,
     ) -> HttmResult<BTreeMap<String, Vec<String>>> {
-        // all snapshots should have the same timestamp
-        let timestamp = date_string(
-            GLOBAL_CONFIG.requested_utc_offset,
-            &SystemTime::now(),
-            DateFormat::Timestamp,
-        );
+        // many files may share a dataset, so only check each dataset once
+        vec_fs_names.sort();
+        vec_fs_names.dedup();
 
-        let vec_snapshot_names: Vec<String> = mounts_for_files
-            .iter()
-            .map(|prox| {
-                let pathdata = prox.pathdata;
-
-                let fs_name = ZfsAllowPriv::Snapshot
-                    .from_opt_proximate_dataset(&pathdata, Some(prox.proximate_dataset))
-                    .map_err(|err| HttmError::from(err))?;
-
-                let snapshot_name = format!(
-                    "{}@snap_{}_{}",
-                    fs_name.to_string_lossy(),
-                    timestamp,
-                    requested_snapshot_suffix,
-                );
+        if GLOBAL_CONFIG.opt_snapshot_if_changed && !vec_fs_names.is_empty() {
+            vec_fs_names.retain(|fs_name| Self::is_changed(fs_name, run_zfs));
 
-                Ok(snapshot_name)
+            // every dataset is unchanged, so there is nothing to do
+            if vec_fs_names.is_empty() {
+                return Ok(BTreeMap::new());
+            }
+        }
+
+        let vec_snapshot_names: Vec<String> = vec_fs_names
+            .into_iter()
+            .map(|fs_name| match &GLOBAL_CONFIG.opt_snap_name_template {
+                Some(template) => template.render(&fs_name, timestamp, requested_snapshot_suffix),
+                None => Ok(format!(
+                    "{fs_name}@snap_{timestamp}_{requested_snapshot_suffix}"
+                )),
             })
-            .collect::<Result<Vec<String>, HttmError>>()?;
+            .collect::<HttmResult<Vec<String>>>()?;
 
         if vec_snapshot_names.is_empty() {
             return Err(HttmError::new(
@@ -117,21 +616,8 @@ impl SnapshotMounts {
         // why all this garbage with BTreeMaps, etc.? ZFS will not allow one to take snapshots
         // with the same name, at the same time, across pools.  Since we don't really care, we break
         // the snapshots into groups by pool name and then just take snapshots for each pool
-        let map_snapshot_names: BTreeMap<String, Vec<String>> = vec_snapshot_names
-            .into_iter()
-            .into_group_map_by(|snapshot_name| {
-                Self::pool_from_snap_name(snapshot_name).unwrap_or_else(|err| {
-                    eprintln!("ERROR: {:?}", err);
-                    std::process::exit(1)
-                })
-            })
-            .iter_mut()
-            .map(|(key, group)| {
-                group.sort();
-                group.dedup();
-                (key.clone(), group.clone())
-            })
-            .collect();
+        let map_snapshot_names: BTreeMap<String, Vec<String>> =
+            Self::group_snapshot_names(vec_snapshot_names, Self::pool_from_snap_name);
 
         if map_snapshot_names.is_empty() {
             return Err(HttmError::new("httm could not generate a valid map of snapshot names from the requested input.  Quitting.").into());
@@ -140,3 +626,74 @@ impl SnapshotMounts {
         Ok(map_snapshot_names)
     }
 }
+
+// the result of each snapshot requested, for btrfs the dataset is the subvolume's mount,
+// the snapshot name is the snapshot's path, and the pool is the subvolume's device
+#[derive(Debug, Clone)]
+pub struct SnapResult {
+    pub dataset: String,
+    pub snapshot_name: String,
+    pub pool: String,
+    pub succeeded: bool,
+}
+
+pub struct SnapshotTaken<'a> {
+    dataset: &'a str,
+    snapshot: &'a str,
+    pool: &'a str,
+    created: &'a str,
+}
+
+impl<'a> SnapshotTaken<'a> {
+    pub fn new(snap_result: &'a SnapResult, created: &'a str) -> Self {
+        Self {
+            dataset: &snap_result.dataset,
+            snapshot: &snap_result.snapshot_name,
+            pool: &snap_result.pool,
+            created,
+        }
+    }
+
+    fn to_json(snapshots_taken: &[SnapshotTaken]) -> HttmResult<String> {
+        match JsonOutput::new("snapshots_taken", snapshots_taken).to_json() {
+            Ok(s) => Ok(s),
+            Err(err) => Err(HttmError::with_context(
+                "httm could not serialize the snapshots taken",
+                &err,
+            )
+            .into()),
+        }
+    }
+
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "description": "list of each snapshot taken in snapshot mode",
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "dataset": { "type": "string" },
+                    "snapshot": { "type": "string" },
+                    "pool": { "type": "string" },
+                    "created": { "type": "string" }
+                },
+                "required": ["dataset", "snapshot", "pool", "created"]
+            }
+        })
+    }
+}
+
+impl<'a> Serialize for SnapshotTaken<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("SnapshotTaken", 4)?;
+
+        state.serialize_field("dataset", self.dataset)?;
+        state.serialize_field("snapshot", self.snapshot)?;
+        state.serialize_field("pool", self.pool)?;
+        state.serialize_field("created", self.created)?;
+        state.end()
+    }
+}